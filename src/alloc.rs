@@ -0,0 +1,104 @@
+//! Memory allocation APIs used by the collections of this crate
+//!
+//! With the `allocator-api2` feature enabled, the [`Allocator`] trait and the
+//! [`Global`] allocator are the ones of the [`allocator-api2`] crate, which
+//! mirrors the unstable allocator API of the standard library on stable Rust.
+//! Any allocator implementing it, e.g. a bump arena, can then be used with
+//! the collections of this crate.
+//!
+//! Without the feature, [`Allocator`] is a sealed trait which is only
+//! implemented by [`Global`].
+//!
+//! [`Allocator`]: trait.Allocator.html
+//! [`Global`]: struct.Global.html
+//! [`allocator-api2`]: https://docs.rs/allocator-api2
+
+use std::{
+    alloc::{handle_alloc_error, Layout},
+    ptr::NonNull,
+};
+
+#[cfg(feature = "allocator-api2")]
+pub use allocator_api2::alloc::{Allocator, Global};
+
+#[cfg(not(feature = "allocator-api2"))]
+pub use self::fallback::{Allocator, Global};
+
+#[cfg(not(feature = "allocator-api2"))]
+mod fallback {
+    use std::{
+        alloc::{self, Layout},
+        fmt,
+        ptr::NonNull,
+    };
+
+    // Error returned by the fallback `Allocator` if the memory could not be
+    // allocated. Not exported, as no other allocator can be implemented.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct AllocError;
+
+    impl fmt::Display for AllocError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("memory allocation failed")
+        }
+    }
+
+    /// An implementation of `Allocator` can allocate and deallocate blocks of
+    /// memory.
+    ///
+    /// This trait is sealed and only implemented by [`Global`]. Enable the
+    /// `allocator-api2` feature to use other allocators.
+    ///
+    /// # Safety
+    ///
+    /// Memory blocks returned by an allocator must point to valid memory and
+    /// retain their validity until they are deallocated.
+    ///
+    /// [`Global`]: struct.Global.html
+    pub unsafe trait Allocator: sealed::Sealed {
+        #[doc(hidden)]
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError>;
+
+        #[doc(hidden)]
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+    }
+
+    /// The global memory allocator.
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct Global;
+
+    unsafe impl Allocator for Global {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            // zero-sized blocks are never requested by this crate
+            debug_assert!(layout.size() != 0);
+            let ptr = NonNull::new(unsafe { alloc::alloc(layout) }).ok_or(AllocError)?;
+            Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            alloc::dealloc(ptr.as_ptr(), layout)
+        }
+    }
+
+    mod sealed {
+        pub trait Sealed {}
+
+        impl Sealed for super::Global {}
+    }
+}
+
+/// Allocates uninitialized memory for a single `T`, which must not be
+/// zero-sized. Aborts on allocation failure.
+pub(crate) fn allocate<T, A: Allocator>(alloc: &A) -> NonNull<T> {
+    let layout = Layout::new::<T>();
+    match alloc.allocate(layout) {
+        Ok(ptr) => ptr.cast(),
+        Err(_) => handle_alloc_error(layout),
+    }
+}
+
+/// Deallocates the memory of a single `T` which was allocated by `allocate`
+/// with the same allocator. The value itself is not dropped.
+pub(crate) unsafe fn deallocate<T, A: Allocator>(alloc: &A, ptr: NonNull<T>) {
+    alloc.deallocate(ptr.cast(), Layout::new::<T>());
+}
@@ -0,0 +1,3363 @@
+//! A doubly-linked list with nodes stored in a contiguous arena
+//!
+//! The `ArenaXorLinkedList` offers the same operations as the [`XorLinkedList`].
+//! Instead of allocating every node on its own, all nodes live in a single `Vec`
+//! and are linked by the XOR of their `u32` slot indices. Slots of removed
+//! elements are kept in a free list and reused by later insertions.
+//!
+//! # Moving elements between lists
+//!
+//! A slot index is only meaningful within the arena it belongs to, so the nodes of
+//! one list cannot be linked into another one. Operations which move elements
+//! between lists, like [`append`], [`split_off`] or the splicing and splitting
+//! methods of [`CursorMut`], move the elements into the slots of the other arena
+//! instead. Where the operation allows it, the smaller part is moved and the
+//! arena of the larger part is kept.
+//!
+//! Almost always it is better to use `Vec` or [`VecDeque`] instead of
+//! [`LinkedList`]. In general, array-based containers are faster,
+//! more memory efficient and make better use of CPU cache.
+//!
+//! [`XorLinkedList`]: ../xor_linked_list/struct.XorLinkedList.html
+//! [`append`]: struct.ArenaXorLinkedList.html#method.append
+//! [`split_off`]: struct.ArenaXorLinkedList.html#method.split_off
+//! [`CursorMut`]: struct.CursorMut.html
+//! [`LinkedList`]: ../linked_list/struct.LinkedList.html
+//! [`VecDeque`]: ../vec_deque/struct.VecDeque.html
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::{FromIterator, FusedIterator},
+    marker::PhantomData,
+    mem::{self, MaybeUninit},
+    ops::{Bound, Index, IndexMut, RangeBounds},
+    ptr::NonNull,
+};
+
+/// A doubly-linked list with nodes stored in a contiguous arena
+///
+/// The `ArenaXorLinkedList` allows pushing and popping elements at either end
+/// in constant time. Nodes are kept in a single `Vec` and freed slots are
+/// reused, so pushing and popping only allocates when the arena has to grow.
+/// The arena holds at most `u32::MAX` nodes.
+///
+/// Almost always it is better to use `Vec` or `VecDeque` instead of
+/// `ArenaXorLinkedList`. In general, array-based containers are faster,
+/// more memory efficient and make better use of CPU cache.
+pub struct ArenaXorLinkedList<T> {
+    nodes: Vec<Node<T>>,
+    head: u32,
+    tail: u32,
+    free: u32,
+    len: usize,
+}
+
+// Links are slot indices shifted by one, which leaves `0` to denote no node.
+// Linked nodes store the XOR of the links of both of their neighbours in
+// `reference` and hold an initialized element. Vacant nodes store the link of
+// the next vacant node and hold no element.
+struct Node<T> {
+    reference: u32,
+    data: MaybeUninit<T>,
+}
+
+/// A pair of adjacent links `(prev, node)` required to traverse the list starting at `node`
+type Position = (u32, u32);
+
+/// An iterator over the elements of a `ArenaXorLinkedList`.
+///
+/// This `struct` is created by the [`iter`] method on [`ArenaXorLinkedList`]. See its
+/// documentation for more.
+///
+/// [`iter`]: struct.ArenaXorLinkedList.html#method.iter
+/// [`ArenaXorLinkedList`]: struct.ArenaXorLinkedList.html
+pub struct Iter<'a, T: 'a> {
+    nodes: &'a [Node<T>],
+    head: u32,
+    tail: u32,
+    last_head: u32,
+    last_tail: u32,
+    len: usize,
+}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter {
+            nodes: self.nodes,
+            head: self.head,
+            tail: self.tail,
+            last_head: self.last_head,
+            last_tail: self.last_tail,
+            len: self.len,
+        }
+    }
+}
+
+impl<'a, T: 'a + fmt::Debug> fmt::Debug for Iter<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Iter").field(&self.len).finish()
+    }
+}
+
+/// A mutable iterator over the elements of a `ArenaXorLinkedList`.
+///
+/// This `struct` is created by the [`iter_mut`] method on [`ArenaXorLinkedList`]. See its
+/// documentation for more.
+///
+/// [`iter_mut`]: struct.ArenaXorLinkedList.html#method.iter_mut
+/// [`ArenaXorLinkedList`]: struct.ArenaXorLinkedList.html
+pub struct IterMut<'a, T: 'a> {
+    nodes: *mut Node<T>,
+    head: u32,
+    tail: u32,
+    last_head: u32,
+    last_tail: u32,
+    len: usize,
+    marker: PhantomData<&'a mut [Node<T>]>,
+}
+
+impl<'a, T: 'a + fmt::Debug> fmt::Debug for IterMut<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IterMut").field(&self.len).finish()
+    }
+}
+
+/// An owning iterator over the elements of a `ArenaXorLinkedList`.
+///
+/// This `struct` is created by the [`into_iter`] method on
+/// [`ArenaXorLinkedList`][`ArenaXorLinkedList`] (provided by the `IntoIterator` trait).
+/// See its documentation for more.
+///
+/// [`into_iter`]: struct.ArenaXorLinkedList.html#method.into_iter
+/// [`ArenaXorLinkedList`]: struct.ArenaXorLinkedList.html
+pub struct IntoIter<T> {
+    list: ArenaXorLinkedList<T>,
+}
+
+impl<T: Clone> Clone for IntoIter<T> {
+    fn clone(&self) -> Self {
+        IntoIter {
+            list: self.list.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for IntoIter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.list).finish()
+    }
+}
+
+/// A cursor over a `ArenaXorLinkedList`.
+///
+/// A `Cursor` is like an iterator, except that it can freely seek back-and-forth. As a node
+/// of a `ArenaXorLinkedList` only stores the combination of both of its neighbours, the
+/// cursor keeps track of the previous node in addition to the current one.
+///
+/// Cursors always rest between two elements in the list, and index in a logically circular way.
+/// To accommodate this, there is a "ghost" non-element that yields `None` between the head and
+/// tail of the list.
+///
+/// This `struct` is created by the [`cursor_front`] and [`cursor_back`] methods on
+/// [`ArenaXorLinkedList`]. See their documentation for more.
+///
+/// [`cursor_front`]: struct.ArenaXorLinkedList.html#method.cursor_front
+/// [`cursor_back`]: struct.ArenaXorLinkedList.html#method.cursor_back
+/// [`ArenaXorLinkedList`]: struct.ArenaXorLinkedList.html
+pub struct Cursor<'a, T: 'a> {
+    index: usize,
+    current: u32,
+    prev: u32,
+    list: &'a ArenaXorLinkedList<T>,
+}
+
+impl<'a, T> Clone for Cursor<'a, T> {
+    fn clone(&self) -> Self {
+        Cursor {
+            index: self.index,
+            current: self.current,
+            prev: self.prev,
+            list: self.list,
+        }
+    }
+}
+
+impl<'a, T: 'a + fmt::Debug> fmt::Debug for Cursor<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Cursor")
+            .field(&self.list)
+            .field(&self.index())
+            .finish()
+    }
+}
+
+/// A cursor over a `ArenaXorLinkedList` with editing operations.
+///
+/// A `CursorMut` is like an iterator, except that it can freely seek back-and-forth, and can
+/// safely mutate the list during iteration. As a node of a `ArenaXorLinkedList` only stores
+/// the combination of both of its neighbours, the cursor keeps track of the previous node in
+/// addition to the current one.
+///
+/// Cursors always rest between two elements in the list, and index in a logically circular way.
+/// To accommodate this, there is a "ghost" non-element that yields `None` between the head and
+/// tail of the list.
+///
+/// This `struct` is created by the [`cursor_front_mut`] and [`cursor_back_mut`] methods on
+/// [`ArenaXorLinkedList`]. See their documentation for more.
+///
+/// [`cursor_front_mut`]: struct.ArenaXorLinkedList.html#method.cursor_front_mut
+/// [`cursor_back_mut`]: struct.ArenaXorLinkedList.html#method.cursor_back_mut
+/// [`ArenaXorLinkedList`]: struct.ArenaXorLinkedList.html
+pub struct CursorMut<'a, T: 'a> {
+    index: usize,
+    current: u32,
+    prev: u32,
+    list: &'a mut ArenaXorLinkedList<T>,
+}
+
+impl<'a, T: 'a + fmt::Debug> fmt::Debug for CursorMut<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("CursorMut")
+            .field(&self.list)
+            .field(&self.index())
+            .finish()
+    }
+}
+
+/// A draining iterator over a range of elements of a `ArenaXorLinkedList`.
+///
+/// This `struct` is created by the [`drain`] method on [`ArenaXorLinkedList`]. See its
+/// documentation for more.
+///
+/// [`drain`]: struct.ArenaXorLinkedList.html#method.drain
+/// [`ArenaXorLinkedList`]: struct.ArenaXorLinkedList.html
+pub struct Drain<'a, T: 'a> {
+    list: &'a mut ArenaXorLinkedList<T>,
+    head: u32,
+    tail: u32,
+    len: usize,
+}
+
+impl<'a, T: 'a + fmt::Debug> fmt::Debug for Drain<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Drain")
+            .field(&self.iter().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// An iterator produced by calling `extract_if` on a `ArenaXorLinkedList`.
+///
+/// This `struct` is created by the [`extract_if`] method on [`ArenaXorLinkedList`]. See its
+/// documentation for more.
+///
+/// [`extract_if`]: struct.ArenaXorLinkedList.html#method.extract_if
+/// [`ArenaXorLinkedList`]: struct.ArenaXorLinkedList.html
+pub struct ExtractIf<'a, T: 'a, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    cursor: CursorMut<'a, T>,
+    pred: F,
+}
+
+impl<'a, T: 'a + fmt::Debug, F> fmt::Debug for ExtractIf<'a, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ExtractIf").field(&self.cursor.list).finish()
+    }
+}
+
+// private methods
+impl<T> ArenaXorLinkedList<T> {
+    #[inline]
+    fn get_element(previous_or_next: u32, reference: u32) -> u32 {
+        previous_or_next ^ reference
+    }
+
+    #[inline]
+    fn node(&self, link: u32) -> &Node<T> {
+        &self.nodes[link as usize - 1]
+    }
+
+    #[inline]
+    fn node_mut(&mut self, link: u32) -> &mut Node<T> {
+        &mut self.nodes[link as usize - 1]
+    }
+
+    /// Returns the element of the given node, or `None` for the null link. The
+    /// link has to be taken from the list, which only ever refers to linked nodes.
+    #[inline]
+    fn element(&self, link: u32) -> Option<&T> {
+        match link {
+            0 => None,
+            _ => Some(unsafe { self.node(link).data.assume_init_ref() }),
+        }
+    }
+
+    /// Returns the element of the given node mutably, or `None` for the null link.
+    /// The link has to be taken from the list, which only ever refers to linked nodes.
+    #[inline]
+    fn element_mut(&mut self, link: u32) -> Option<&mut T> {
+        match link {
+            0 => None,
+            _ => Some(unsafe { self.node_mut(link).data.assume_init_mut() }),
+        }
+    }
+
+    /// Returns the elements of two distinct linked nodes mutably.
+    fn elements_mut(&mut self, first: u32, second: u32) -> (&mut T, &mut T) {
+        let (first_index, second_index) = (first as usize - 1, second as usize - 1);
+        let (first_node, second_node) = if first_index < second_index {
+            let (front, back) = self.nodes.split_at_mut(second_index);
+            (&mut front[first_index], &mut back[0])
+        } else {
+            let (front, back) = self.nodes.split_at_mut(first_index);
+            (&mut back[0], &mut front[second_index])
+        };
+        unsafe {
+            (
+                first_node.data.assume_init_mut(),
+                second_node.data.assume_init_mut(),
+            )
+        }
+    }
+
+    /// Stores the given element in a vacant slot, growing the arena if there is
+    /// none, and returns its link. The node is not linked yet.
+    fn allocate(&mut self, data: T) -> u32 {
+        match self.free {
+            0 => {
+                assert!(
+                    self.nodes.len() < u32::MAX as usize,
+                    "Cannot store more than u32::MAX nodes"
+                );
+                self.nodes.push(Node {
+                    reference: 0,
+                    data: MaybeUninit::new(data),
+                });
+                self.nodes.len() as u32
+            }
+            link => {
+                let node = self.node_mut(link);
+                let free = mem::replace(&mut node.reference, 0);
+                node.data = MaybeUninit::new(data);
+                self.free = free;
+                link
+            }
+        }
+    }
+
+    /// Moves the element out of the given unlinked node and adds its slot to
+    /// the free list.
+    ///
+    /// The node must hold an element, i.e. it must have been unlinked right before.
+    #[inline]
+    unsafe fn release(&mut self, link: u32) -> T {
+        let free = mem::replace(&mut self.free, link);
+        let node = self.node_mut(link);
+        node.reference = free;
+        node.data.assume_init_read()
+    }
+
+    /// Links the given node in between the two adjacent nodes `prev` and `next`.
+    /// `0` denotes the position before the head or after the tail.
+    #[inline]
+    fn link_node(&mut self, prev: u32, next: u32, node: u32) {
+        self.node_mut(node).reference = prev ^ next;
+        match prev {
+            0 => self.head = node,
+            _ => self.node_mut(prev).reference ^= next ^ node,
+        }
+        match next {
+            0 => self.tail = node,
+            _ => self.node_mut(next).reference ^= prev ^ node,
+        }
+        self.len += 1;
+    }
+
+    /// Unlinks the given node which resides in between `prev` and `next` and
+    /// connects both neighbours with each other. The node keeps its element.
+    #[inline]
+    fn unlink_node(&mut self, prev: u32, node: u32, next: u32) {
+        match prev {
+            0 => self.head = next,
+            _ => self.node_mut(prev).reference ^= node ^ next,
+        }
+        match next {
+            0 => self.tail = prev,
+            _ => self.node_mut(next).reference ^= node ^ prev,
+        }
+        self.len -= 1;
+    }
+
+    /// Provides a cursor with editing operations at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at >= len`.
+    fn cursor_mut_at(&mut self, at: usize) -> CursorMut<'_, T> {
+        let (current, prev) = self.node_at(at);
+        CursorMut {
+            index: at,
+            current,
+            prev,
+            list: self,
+        }
+    }
+
+    /// Returns the node in front of the tail.
+    #[inline]
+    fn tail_prev(&self) -> u32 {
+        match self.tail {
+            0 => 0,
+            tail => Self::get_element(0, self.node(tail).reference),
+        }
+    }
+
+    /// Returns the node at the given index together with the node in front of it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at >= len`.
+    fn node_at(&self, at: usize) -> (u32, u32) {
+        let len = self.len();
+        assert!(at < len, "Cannot access a nonexistent index");
+
+        // Below, we iterate towards the `at`th node, either from the start or the end,
+        // depending on which would be faster
+        if at <= len - 1 - at {
+            let (prev, node) = self.walk((0, self.head), at);
+            (node, prev)
+        } else {
+            // better off starting from the end, the walk yields the node behind
+            let (next, node) = self.walk((0, self.tail), len - 1 - at);
+            (node, Self::get_element(next, self.node(node).reference))
+        }
+    }
+
+    /// Advances the adjacent node pair `(prev, node)` by `steps` nodes. As the links
+    /// are symmetric, the direction is given by the order of the pair.
+    #[inline]
+    fn walk(&self, (mut prev, mut node): Position, steps: usize) -> Position {
+        for _ in 0..steps {
+            if node == 0 {
+                break;
+            }
+            let next = Self::get_element(prev, self.node(node).reference);
+            prev = node;
+            node = next;
+        }
+        (prev, node)
+    }
+
+    /// Merges the two adjacent, sorted runs of `left_len` and `right_len` nodes which
+    /// start at `first` by relinking the nodes of the right run into the left one.
+    /// Returns the adjacent node pair following the merged run.
+    fn merge_runs<F>(
+        &mut self,
+        (prev, first): Position,
+        mut left_len: usize,
+        mut right_len: usize,
+        compare: &mut F,
+    ) -> Position
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let (mut left_prev, mut left) = (prev, first);
+        let (right_prev, mut right) = self.walk((prev, first), left_len);
+        while left_len > 0 && right_len > 0 && left != 0 && right != 0 {
+            let ordering = match (self.element(right), self.element(left)) {
+                (Some(right_data), Some(left_data)) => compare(right_data, left_data),
+                _ => break,
+            };
+            if ordering == Ordering::Less {
+                // Only move nodes of the right run to keep the sort stable
+                let right_next = Self::get_element(right_prev, self.node(right).reference);
+                self.unlink_node(right_prev, right, right_next);
+                self.link_node(left_prev, left, right);
+                left_prev = right;
+                right = right_next;
+                right_len -= 1;
+            } else {
+                let left_next = Self::get_element(left_prev, self.node(left).reference);
+                left_prev = left;
+                left = left_next;
+                left_len -= 1;
+            }
+        }
+        self.walk((right_prev, right), right_len)
+    }
+}
+
+impl<T> Default for ArenaXorLinkedList<T> {
+    /// Creates an empty `ArenaXorLinkedList<T>`
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ArenaXorLinkedList<T> {
+    /// Creates an empty `ArenaXorLinkedList`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let list: ArenaXorLinkedList<u32> = ArenaXorLinkedList::new();
+    /// ```
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates an empty `ArenaXorLinkedList` with space for at least `capacity`
+    /// elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let list: ArenaXorLinkedList<u32> = ArenaXorLinkedList::with_capacity(10);
+    /// assert!(list.capacity() >= 10);
+    /// ```
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        ArenaXorLinkedList {
+            nodes: Vec::with_capacity(capacity),
+            head: 0,
+            tail: 0,
+            free: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements the list can hold without reallocating
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list = ArenaXorLinkedList::with_capacity(10);
+    /// list.push_back(1);
+    /// assert!(list.capacity() >= 10);
+    /// ```
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.nodes.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` more elements to be inserted
+    /// without reallocating. Vacant slots of removed elements are taken into
+    /// account.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list = ArenaXorLinkedList::new();
+    /// list.push_back(1);
+    /// list.reserve(10);
+    /// assert!(list.capacity() >= 11);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        let vacant = self.nodes.len() - self.len;
+        if additional > vacant {
+            self.nodes.reserve(additional - vacant);
+        }
+    }
+
+    /// Shrinks the capacity of the list as much as possible.
+    ///
+    /// The elements are moved into a contiguous block of slots in list order,
+    /// which drops the free list and improves the locality of later iterations.
+    ///
+    /// This operation should compute in O(n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<u32> = (0..100).collect();
+    /// list.split_off(10);
+    /// list.shrink_to_fit();
+    /// assert!(list.capacity() >= 10);
+    /// assert_eq!(list.len(), 10);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        let len = self.len;
+        let mut nodes = Vec::with_capacity(len);
+        while let Some(data) = self.pop_front() {
+            // the link of the slot at index `i` is `i + 1`
+            let index = nodes.len() as u32;
+            let next = if (index as usize) + 1 < len {
+                index + 2
+            } else {
+                0
+            };
+            nodes.push(Node {
+                reference: index ^ next,
+                data: MaybeUninit::new(data),
+            });
+        }
+        *self = ArenaXorLinkedList {
+            nodes,
+            head: if len > 0 { 1 } else { 0 },
+            tail: len as u32,
+            free: 0,
+            len,
+        };
+    }
+
+    /// Moves all elements from `other` to the end of the list.
+    ///
+    /// As a node can only be linked within its own arena, the elements of the
+    /// shorter list are moved into the arena of the longer one, which then becomes
+    /// the arena of `self`. After this operation, `other` becomes empty.
+    ///
+    /// This operation should compute in O(min(n, m)) time, where `m` is the length
+    /// of `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list1 = ArenaXorLinkedList::new();
+    /// list1.push_back('a');
+    ///
+    /// let mut list2 = ArenaXorLinkedList::new();
+    /// list2.push_back('b');
+    /// list2.push_back('c');
+    ///
+    /// list1.append(&mut list2);
+    ///
+    /// let mut iter = list1.iter();
+    /// assert_eq!(iter.next(), Some(&'a'));
+    /// assert_eq!(iter.next(), Some(&'b'));
+    /// assert_eq!(iter.next(), Some(&'c'));
+    /// assert!(iter.next().is_none());
+    ///
+    /// assert!(list2.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut Self) {
+        if self.len < other.len {
+            other.reserve(self.len);
+            while let Some(data) = self.pop_back() {
+                other.push_front(data);
+            }
+            mem::swap(self, other);
+        } else {
+            self.reserve(other.len);
+            while let Some(data) = other.pop_front() {
+                self.push_back(data);
+            }
+        }
+    }
+
+    /// Reverses the order of the elements in place.
+    ///
+    /// As every node stores the combination of both of its neighbours, the list
+    /// is symmetric and swapping the head with the tail reverses it without
+    /// touching any node.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<u32> = (0..4).collect();
+    ///
+    /// list.reverse();
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![3, 2, 1, 0]);
+    ///
+    /// list.push_back(4);
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![3, 2, 1, 0, 4]);
+    /// ```
+    #[inline]
+    pub fn reverse(&mut self) {
+        mem::swap(&mut self.head, &mut self.tail);
+    }
+
+    /// Consumes the list and returns it with the order of its elements reversed.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let list: ArenaXorLinkedList<u32> = (0..4).collect();
+    ///
+    /// let reversed: Vec<u32> = list.reversed().into_iter().collect();
+    /// assert_eq!(reversed, vec![3, 2, 1, 0]);
+    /// ```
+    #[inline]
+    pub fn reversed(mut self) -> Self {
+        self.reverse();
+        self
+    }
+
+    /// Provides a forward iterator
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<u32> = ArenaXorLinkedList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(1);
+    /// list.push_back(2);
+    ///
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&0));
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next(), Some(&2));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            nodes: &self.nodes,
+            head: self.head,
+            tail: self.tail,
+            last_head: 0,
+            last_tail: 0,
+            len: self.len,
+        }
+    }
+
+    /// Provides a forward iterator with mutable references
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<u32> = ArenaXorLinkedList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(1);
+    /// list.push_back(2);
+    ///
+    /// for element in list.iter_mut() {
+    ///     *element += 10;
+    /// }
+    ///
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&10));
+    /// assert_eq!(iter.next(), Some(&11));
+    /// assert_eq!(iter.next(), Some(&12));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            nodes: self.nodes.as_mut_ptr(),
+            head: self.head,
+            tail: self.tail,
+            last_head: 0,
+            last_tail: 0,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Provides a cursor at the front element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<u32> = ArenaXorLinkedList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(1);
+    ///
+    /// let mut cursor = list.cursor_front();
+    /// assert_eq!(cursor.current(), Some(&0));
+    /// cursor.move_next();
+    /// assert_eq!(cursor.current(), Some(&1));
+    /// cursor.move_next();
+    /// assert_eq!(cursor.current(), None);
+    /// cursor.move_prev();
+    /// assert_eq!(cursor.current(), Some(&1));
+    /// ```
+    #[inline]
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor {
+            index: 0,
+            current: self.head,
+            prev: 0,
+            list: self,
+        }
+    }
+
+    /// Provides a cursor at the back element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<u32> = ArenaXorLinkedList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(1);
+    ///
+    /// let mut cursor = list.cursor_back();
+    /// assert_eq!(cursor.current(), Some(&1));
+    /// assert_eq!(cursor.index(), Some(1));
+    /// assert_eq!(cursor.peek_prev(), Some(&0));
+    /// ```
+    #[inline]
+    pub fn cursor_back(&self) -> Cursor<'_, T> {
+        Cursor {
+            index: self.len.saturating_sub(1),
+            current: self.tail,
+            prev: self.tail_prev(),
+            list: self,
+        }
+    }
+
+    /// Provides a cursor with editing operations at the front element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<u32> = ArenaXorLinkedList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(2);
+    ///
+    /// {
+    ///     let mut cursor = list.cursor_front_mut();
+    ///     cursor.insert_after(1);
+    ///     assert_eq!(cursor.current(), Some(&mut 0));
+    ///     assert_eq!(cursor.peek_next(), Some(&mut 1));
+    /// }
+    ///
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&0));
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next(), Some(&2));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[inline]
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: 0,
+            current: self.head,
+            prev: 0,
+            list: self,
+        }
+    }
+
+    /// Provides a cursor with editing operations at the back element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<u32> = ArenaXorLinkedList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(2);
+    ///
+    /// {
+    ///     let mut cursor = list.cursor_back_mut();
+    ///     cursor.insert_before(1);
+    ///     assert_eq!(cursor.current(), Some(&mut 2));
+    ///     assert_eq!(cursor.peek_prev(), Some(&mut 1));
+    /// }
+    ///
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&0));
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next(), Some(&2));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[inline]
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: self.len.saturating_sub(1),
+            current: self.tail,
+            prev: self.tail_prev(),
+            list: self,
+        }
+    }
+
+    /// Returns `true` if the `ArenaXorLinkedList` is empty
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut dl = ArenaXorLinkedList::new();
+    /// assert!(dl.is_empty());
+    ///
+    /// dl.push_front("foo");
+    /// assert!(!dl.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.head == 0
+    }
+
+    /// Returns the length of the `ArenaXorLinkedList`
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut dl = ArenaXorLinkedList::new();
+    ///
+    /// dl.push_front(2);
+    /// assert_eq!(dl.len(), 1);
+    ///
+    /// dl.push_front(1);
+    /// assert_eq!(dl.len(), 2);
+    ///
+    /// dl.push_back(3);
+    /// assert_eq!(dl.len(), 3);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Removes all elements from the `ArenaXorLinkedList`. The arena keeps its
+    /// capacity, use [`shrink_to_fit`] to free it.
+    ///
+    /// This operation should compute in O(n) time
+    ///
+    /// [`shrink_to_fit`]: #method.shrink_to_fit
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut dl = ArenaXorLinkedList::new();
+    ///
+    /// dl.push_front(2);
+    /// dl.push_front(1);
+    /// assert_eq!(dl.len(), 2);
+    /// assert_eq!(dl.front(), Some(&1));
+    ///
+    /// dl.clear();
+    /// assert_eq!(dl.len(), 0);
+    /// assert_eq!(dl.front(), None);
+    /// ```
+    pub fn clear(&mut self) {
+        // Slots do not drop their elements, so drop every linked element by popping it
+        if mem::needs_drop::<T>() {
+            while self.pop_back().is_some() {}
+        }
+        self.nodes.clear();
+        self.head = 0;
+        self.tail = 0;
+        self.free = 0;
+        self.len = 0;
+    }
+
+    /// Returns `true` if the `ArenaXorLinkedList` contains an element equal to the
+    /// given value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<u32> = ArenaXorLinkedList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(1);
+    /// list.push_back(2);
+    ///
+    /// assert_eq!(list.contains(&0), true);
+    /// assert_eq!(list.contains(&10), false);
+    /// ```
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq<T>,
+    {
+        self.iter().any(|e| e == x)
+    }
+
+    /// Provides a reference to the front element, or `None` if the list is
+    /// empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut dl = ArenaXorLinkedList::new();
+    /// assert_eq!(dl.front(), None);
+    ///
+    /// dl.push_front(1);
+    /// assert_eq!(dl.front(), Some(&1));
+    /// ```
+    #[inline]
+    pub fn front(&self) -> Option<&T> {
+        self.element(self.head)
+    }
+
+    /// Provides a mutable reference to the front element, or `None` if the list
+    /// is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut dl = ArenaXorLinkedList::new();
+    /// assert_eq!(dl.front(), None);
+    ///
+    /// dl.push_front(1);
+    /// assert_eq!(dl.front(), Some(&1));
+    ///
+    /// match dl.front_mut() {
+    ///     None => {},
+    ///     Some(x) => *x = 5,
+    /// }
+    /// assert_eq!(dl.front(), Some(&5));
+    /// ```
+    #[inline]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        let head = self.head;
+        self.element_mut(head)
+    }
+
+    /// Provides a reference to the back element, or `None` if the list is
+    /// empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut dl = ArenaXorLinkedList::new();
+    /// assert_eq!(dl.back(), None);
+    ///
+    /// dl.push_back(1);
+    /// assert_eq!(dl.back(), Some(&1));
+    /// ```
+    #[inline]
+    pub fn back(&self) -> Option<&T> {
+        self.element(self.tail)
+    }
+
+    /// Provides a mutable reference to the back element, or `None` if the list
+    /// is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut dl = ArenaXorLinkedList::new();
+    /// assert_eq!(dl.back(), None);
+    ///
+    /// dl.push_back(1);
+    /// assert_eq!(dl.back(), Some(&1));
+    ///
+    /// match dl.back_mut() {
+    ///     None => {},
+    ///     Some(x) => *x = 5,
+    /// }
+    /// assert_eq!(dl.back(), Some(&5));
+    /// ```
+    #[inline]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        let tail = self.tail;
+        self.element_mut(tail)
+    }
+
+    /// Provides a reference to the element at the given index, or `None` if
+    /// the index is out of bounds
+    ///
+    /// This operation should compute in O(min(i, n - i)) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut dl = ArenaXorLinkedList::new();
+    /// dl.push_back(3);
+    /// dl.push_back(4);
+    /// dl.push_back(5);
+    ///
+    /// assert_eq!(dl.get(1), Some(&4));
+    /// assert_eq!(dl.get(3), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            self.element(self.node_at(index).0)
+        } else {
+            None
+        }
+    }
+
+    /// Provides a mutable reference to the element at the given index, or `None`
+    /// if the index is out of bounds
+    ///
+    /// This operation should compute in O(min(i, n - i)) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut dl = ArenaXorLinkedList::new();
+    /// dl.push_back(3);
+    /// dl.push_back(4);
+    /// dl.push_back(5);
+    ///
+    /// if let Some(elem) = dl.get_mut(1) {
+    ///     *elem = 7;
+    /// }
+    /// assert_eq!(dl.get(1), Some(&7));
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len {
+            let node = self.node_at(index).0;
+            self.element_mut(node)
+        } else {
+            None
+        }
+    }
+
+    /// Adds an element first in the list.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut dl = ArenaXorLinkedList::new();
+    ///
+    /// dl.push_front(2);
+    /// assert_eq!(dl.front().unwrap(), &2);
+    ///
+    /// dl.push_front(1);
+    /// assert_eq!(dl.front().unwrap(), &1);
+    /// ```
+    pub fn push_front(&mut self, data: T) {
+        let node = self.allocate(data);
+        let head = self.head;
+        self.link_node(0, head, node);
+    }
+
+    /// Removes the first element and returns it, or `None` if the list is
+    /// empty.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut d = ArenaXorLinkedList::new();
+    /// assert_eq!(d.pop_front(), None);
+    ///
+    /// d.push_front(1);
+    /// d.push_front(3);
+    /// assert_eq!(d.pop_front(), Some(3));
+    /// assert_eq!(d.pop_front(), Some(1));
+    /// assert_eq!(d.pop_front(), None);
+    /// ```
+    pub fn pop_front(&mut self) -> Option<T> {
+        match self.head {
+            0 => None,
+            head => {
+                let next = Self::get_element(0, self.node(head).reference);
+                self.unlink_node(0, head, next);
+                Some(unsafe { self.release(head) })
+            }
+        }
+    }
+
+    /// Appends an element to the back of a list
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut d = ArenaXorLinkedList::new();
+    /// d.push_back(1);
+    /// d.push_back(3);
+    /// assert_eq!(3, *d.back().unwrap());
+    /// ```
+    pub fn push_back(&mut self, data: T) {
+        let node = self.allocate(data);
+        let tail = self.tail;
+        self.link_node(tail, 0, node);
+    }
+
+    /// Removes the last element from a list and returns it, or `None` if
+    /// it is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut d = ArenaXorLinkedList::new();
+    /// assert_eq!(d.pop_back(), None);
+    /// d.push_back(1);
+    /// d.push_back(3);
+    /// assert_eq!(d.pop_back(), Some(3));
+    /// ```
+    pub fn pop_back(&mut self) -> Option<T> {
+        match self.tail {
+            0 => None,
+            tail => {
+                let prev = Self::get_element(0, self.node(tail).reference);
+                self.unlink_node(prev, tail, 0);
+                Some(unsafe { self.release(tail) })
+            }
+        }
+    }
+
+    /// Inserts an element at `index` within the list, shifting all elements
+    /// with indices greater than or equal to `index` towards the back.
+    ///
+    /// This operation should compute in O(min(i, n - i)) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut dl = ArenaXorLinkedList::new();
+    /// dl.push_back('a');
+    /// dl.push_back('c');
+    ///
+    /// dl.insert(1, 'b');
+    ///
+    /// let mut iter = dl.iter();
+    /// assert_eq!(iter.next(), Some(&'a'));
+    /// assert_eq!(iter.next(), Some(&'b'));
+    /// assert_eq!(iter.next(), Some(&'c'));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn insert(&mut self, index: usize, value: T) {
+        assert!(index <= self.len, "Cannot insert at a nonexistent index");
+        if index == self.len {
+            self.push_back(value);
+        } else {
+            self.cursor_mut_at(index).insert_before(value);
+        }
+    }
+
+    /// Removes and returns the element at `index` from the list, or `None` if
+    /// the index is out of bounds.
+    ///
+    /// This operation should compute in O(min(i, n - i)) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut dl = ArenaXorLinkedList::new();
+    /// dl.push_back(1);
+    /// dl.push_back(2);
+    /// dl.push_back(3);
+    ///
+    /// assert_eq!(dl.remove(1), Some(2));
+    /// assert_eq!(dl.remove(2), None);
+    /// assert_eq!(dl.len(), 2);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index < self.len {
+            self.cursor_mut_at(index).remove_current()
+        } else {
+            None
+        }
+    }
+
+    /// Splits the list into two at the given index. Returns everything after the given index,
+    /// including the index
+    ///
+    /// The smaller part is moved into a new arena, the larger part keeps the arena of
+    /// the list.
+    ///
+    /// This operation should compute in O(min(i, n - i)) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut d = ArenaXorLinkedList::new();
+    ///
+    /// d.push_front(1);
+    /// d.push_front(2);
+    /// d.push_front(3);
+    ///
+    /// let mut splitted = d.split_off(2);
+    ///
+    /// assert_eq!(splitted.pop_front(), Some(1));
+    /// assert_eq!(splitted.pop_front(), None);
+    /// ```
+    pub fn split_off(&mut self, at: usize) -> ArenaXorLinkedList<T> {
+        let len = self.len();
+        assert!(at <= len, "Cannot split off at a nonexistent index");
+
+        if at <= len - at {
+            let mut first_part = Self::with_capacity(at);
+            for _ in 0..at {
+                if let Some(data) = self.pop_front() {
+                    first_part.push_back(data);
+                }
+            }
+            mem::replace(self, first_part)
+        } else {
+            let mut second_part = Self::with_capacity(len - at);
+            for _ in at..len {
+                if let Some(data) = self.pop_back() {
+                    second_part.push_front(data);
+                }
+            }
+            second_part
+        }
+    }
+
+    /// Removes the specified range from the list in bulk, returning all removed
+    /// elements as an iterator.
+    ///
+    /// The range is unlinked from the list right away and the remaining nodes are
+    /// relinked, so the list is consistent no matter how much of the iterator is
+    /// consumed. Elements which are not yielded are dropped together with the
+    /// iterator.
+    ///
+    /// This operation should compute in O(min(i, n - i) + m) time, where `i` is
+    /// the start of the range and `m` its length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if the end
+    /// point is greater than the length of the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<u32> = (0..6).collect();
+    ///
+    /// let drained: Vec<u32> = list.drain(1..4).collect();
+    /// assert_eq!(drained, vec![1, 2, 3]);
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![0, 4, 5]);
+    ///
+    /// // A partially consumed iterator removes the whole range
+    /// assert_eq!(list.drain(..2).next_back(), Some(4));
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![5]);
+    /// ```
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start
+                .checked_add(1)
+                .expect("Cannot drain from a nonexistent index"),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end
+                .checked_add(1)
+                .expect("Cannot drain up to a nonexistent index"),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len,
+        };
+        assert!(
+            start <= end,
+            "Cannot drain a range which starts after its end"
+        );
+        assert!(end <= self.len, "Cannot drain up to a nonexistent index");
+
+        if start == end {
+            return Drain {
+                list: self,
+                head: 0,
+                tail: 0,
+                len: 0,
+            };
+        }
+
+        let (first, prev) = self.node_at(start);
+        let (last, next) = self.walk((prev, first), end - start);
+
+        // Detach the range by linking its neighbours to each other. As the
+        // references are XORed, the range itself only has to drop its outer
+        // neighbours.
+        match prev {
+            0 => self.head = next,
+            _ => self.node_mut(prev).reference ^= first ^ next,
+        }
+        match next {
+            0 => self.tail = prev,
+            _ => self.node_mut(next).reference ^= last ^ prev,
+        }
+        self.node_mut(first).reference ^= prev;
+        self.node_mut(last).reference ^= next;
+        self.len -= end - start;
+
+        Drain {
+            list: self,
+            head: first,
+            tail: last,
+            len: end - start,
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` for which `f(&e)` returns false.
+    /// This method operates in place, visiting each element exactly once in the
+    /// original order, and preserves the order of the retained elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut d: ArenaXorLinkedList<u32> = (1..7).collect();
+    ///
+    /// d.retain(|&x| x % 2 == 0);
+    ///
+    /// assert_eq!(d.iter().cloned().collect::<Vec<_>>(), vec![2, 4, 6]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain_mut(|elem| f(elem));
+    }
+
+    /// Retains only the elements specified by the predicate, passing a mutable
+    /// reference to it.
+    ///
+    /// In other words, remove all elements `e` for which `f(&mut e)` returns false.
+    /// This method operates in place, visiting each element exactly once in the
+    /// original order, and preserves the order of the retained elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut d: ArenaXorLinkedList<u32> = (1..7).collect();
+    ///
+    /// d.retain_mut(|x| {
+    ///     *x += 10;
+    ///     *x % 2 == 0
+    /// });
+    ///
+    /// assert_eq!(d.iter().cloned().collect::<Vec<_>>(), vec![12, 14, 16]);
+    /// ```
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let mut cursor = self.cursor_front_mut();
+        while let Some(elem) = cursor.current() {
+            if f(elem) {
+                cursor.move_next();
+            } else {
+                cursor.remove_current();
+            }
+        }
+    }
+
+    /// Removes consecutive repeated elements in the list according to the
+    /// `PartialEq` trait implementation.
+    ///
+    /// If the list is sorted, this removes all duplicates. The nodes of the
+    /// removed elements are unlinked in place.
+    ///
+    /// This operation should compute in O(n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<u32> = vec![1, 2, 2, 3, 2].into_iter().collect();
+    ///
+    /// list.dedup();
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![1, 2, 3, 2]);
+    /// ```
+    #[inline]
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b);
+    }
+
+    /// Removes all but the first of consecutive elements in the list that
+    /// resolve to the same key.
+    ///
+    /// If the list is sorted by the key, this removes all duplicates. The nodes
+    /// of the removed elements are unlinked in place.
+    ///
+    /// This operation should compute in O(n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<u32> = vec![10, 20, 21, 30, 20].into_iter().collect();
+    ///
+    /// list.dedup_by_key(|i| *i / 10);
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![10, 20, 30, 20]);
+    /// ```
+    #[inline]
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    /// Removes all but the first of consecutive elements in the list satisfying
+    /// a given equality relation.
+    ///
+    /// The `same_bucket` function is passed references to two elements from the
+    /// list and must determine if the elements compare equal. The elements are
+    /// passed in opposite order from their order in the list, so if
+    /// `same_bucket(a, b)` returns `true`, `a` is removed.
+    ///
+    /// If the list is sorted, this removes all duplicates. The nodes of the
+    /// removed elements are unlinked in place.
+    ///
+    /// This operation should compute in O(n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<&str> =
+    ///     vec!["foo", "bar", "Bar", "baz", "bar"].into_iter().collect();
+    ///
+    /// list.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec!["foo", "bar", "baz", "bar"]);
+    /// ```
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        let mut cursor = self.cursor_front_mut();
+        while cursor.current != 0 {
+            let kept = cursor.current;
+            cursor.move_next();
+            while cursor.current != 0 {
+                let (elem, kept_elem) = cursor.list.elements_mut(cursor.current, kept);
+                if same_bucket(elem, kept_elem) {
+                    cursor.remove_current();
+                } else {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Creates an iterator which uses a closure to determine if an element should
+    /// be removed.
+    ///
+    /// If the closure returns true, then the element is removed and yielded.
+    /// If the closure returns false, the element will remain in the list and will
+    /// not be yielded by the iterator.
+    ///
+    /// The iterator unlinks the elements lazily. If it is dropped before being fully
+    /// consumed, the remaining elements are kept in the list. If the closure panics,
+    /// the list stays consistent and keeps every element not yet yielded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut numbers: ArenaXorLinkedList<u32> = ArenaXorLinkedList::new();
+    /// numbers.extend(&[1, 2, 3, 4, 5, 6, 8, 9, 11, 13, 14, 15]);
+    ///
+    /// let evens = numbers.extract_if(|x| *x % 2 == 0).collect::<ArenaXorLinkedList<_>>();
+    /// let odds = numbers;
+    ///
+    /// assert_eq!(evens.into_iter().collect::<Vec<_>>(), vec![2, 4, 6, 8, 14]);
+    /// assert_eq!(odds.into_iter().collect::<Vec<_>>(), vec![1, 3, 5, 9, 11, 13, 15]);
+    /// ```
+    pub fn extract_if<F>(&mut self, filter: F) -> ExtractIf<'_, T, F>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        ExtractIf {
+            cursor: self.cursor_front_mut(),
+            pred: filter,
+        }
+    }
+
+    /// Sorts the list.
+    ///
+    /// This sort is stable (i.e. does not reorder equal elements) and `O(n log n)`
+    /// worst-case. It neither moves nor allocates elements but rearranges the
+    /// links between the nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<i32> = vec![5, 4, 1, 3, 2].into_iter().collect();
+    ///
+    /// list.sort();
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+    /// ```
+    #[inline]
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(|a, b| a.cmp(b));
+    }
+
+    /// Sorts the list with a comparator function.
+    ///
+    /// This sort is stable (i.e. does not reorder equal elements) and `O(n log n)`
+    /// worst-case. It neither moves nor allocates elements but rearranges the
+    /// links between the nodes.
+    ///
+    /// If the comparator function panics, the list contains all of its elements
+    /// in an unspecified order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<i32> = vec![5, 4, 1, 3, 2].into_iter().collect();
+    ///
+    /// list.sort_by(|a, b| b.cmp(a));
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![5, 4, 3, 2, 1]);
+    /// ```
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        // Bottom-up merge sort, merging pairs of runs of doubling width
+        let len = self.len;
+        let mut width = 1;
+        while width < len {
+            let mut position = (0, self.head);
+            let mut remaining = len;
+            while remaining > width {
+                let right_len = (remaining - width).min(width);
+                position = self.merge_runs(position, width, right_len, &mut compare);
+                remaining -= width + right_len;
+            }
+            width *= 2;
+        }
+    }
+
+    /// Sorts the list with a key extraction function.
+    ///
+    /// This sort is stable (i.e. does not reorder equal elements) and `O(n log n)`
+    /// worst-case. It neither moves nor allocates elements but rearranges the
+    /// links between the nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<i32> = vec![-5, 4, 1, -3, 2].into_iter().collect();
+    ///
+    /// list.sort_by_key(|k| k.abs());
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![1, 2, -3, 4, -5]);
+    /// ```
+    #[inline]
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)));
+    }
+
+    /// Merges all elements of the sorted list `other` into the sorted list `self`,
+    /// so that the merged list is sorted as well.
+    ///
+    /// The merge is stable, elements of `self` are placed in front of equal
+    /// elements of `other`. The elements of the shorter list are moved into the
+    /// arena of the longer one, the merge itself only relinks nodes. After this
+    /// operation, `other` becomes empty.
+    ///
+    /// If either list is not sorted, the order of the merged elements is
+    /// unspecified.
+    ///
+    /// This operation should compute in O(n + m) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<u32> = vec![1, 4, 6].into_iter().collect();
+    /// let mut other: ArenaXorLinkedList<u32> = vec![2, 3, 5, 7].into_iter().collect();
+    ///
+    /// list.merge_sorted(&mut other);
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6, 7]);
+    /// assert!(other.is_empty());
+    /// ```
+    #[inline]
+    pub fn merge_sorted(&mut self, other: &mut Self)
+    where
+        T: Ord,
+    {
+        self.merge_sorted_by(other, |a, b| a.cmp(b));
+    }
+
+    /// Merges all elements of `other` into `self` with a comparator function.
+    /// Both lists have to be sorted with respect to the comparator, so that the
+    /// merged list is sorted as well.
+    ///
+    /// The merge is stable, elements of `self` are placed in front of equal
+    /// elements of `other`. The elements of the shorter list are moved into the
+    /// arena of the longer one, the merge itself only relinks nodes. After this
+    /// operation, `other` becomes empty.
+    ///
+    /// If the comparator function panics, the list contains the elements of both
+    /// lists in an unspecified order.
+    ///
+    /// This operation should compute in O(n + m) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<u32> = vec![6, 4, 1].into_iter().collect();
+    /// let mut other: ArenaXorLinkedList<u32> = vec![7, 5, 3, 2].into_iter().collect();
+    ///
+    /// list.merge_sorted_by(&mut other, |a, b| b.cmp(a));
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![7, 6, 5, 4, 3, 2, 1]);
+    /// ```
+    pub fn merge_sorted_by<F>(&mut self, other: &mut Self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let left_len = self.len;
+        let right_len = other.len;
+        self.append(other);
+        let first = (0, self.head);
+        self.merge_runs(first, left_len, right_len, &mut compare);
+    }
+
+    /// Consumes the list, creating two lists from it.
+    ///
+    /// The first list contains all elements for which `f` returns `true`, the
+    /// second one all elements for which it returns `false`. Both keep the
+    /// relative order of their elements. The elements are moved into the arenas
+    /// of the new lists.
+    ///
+    /// This operation should compute in O(n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let list: ArenaXorLinkedList<u32> = (1..7).collect();
+    ///
+    /// let (even, odd) = list.partition(|x| x % 2 == 0);
+    /// assert_eq!(even.iter().cloned().collect::<Vec<_>>(), vec![2, 4, 6]);
+    /// assert_eq!(odd.iter().cloned().collect::<Vec<_>>(), vec![1, 3, 5]);
+    /// ```
+    pub fn partition<F>(self, mut f: F) -> (Self, Self)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut left = Self::new();
+        let mut right = Self::new();
+        for elem in self {
+            if f(&elem) {
+                left.push_back(elem);
+            } else {
+                right.push_back(elem);
+            }
+        }
+        (left, right)
+    }
+
+    /// Consumes the list, splitting it into sublists between each pair of
+    /// adjacent elements `a` and `b` for which `pred(a, b)` returns `true`.
+    ///
+    /// The elements are moved into the arenas of the sublists. An empty list
+    /// results in no sublists.
+    ///
+    /// This operation should compute in O(n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let list: ArenaXorLinkedList<u32> = vec![1, 2, 3, 7, 8, 12].into_iter().collect();
+    ///
+    /// let runs = list.split_when(|a, b| b - a > 1);
+    /// let values: Vec<Vec<u32>> = runs.iter().map(|run| run.iter().cloned().collect()).collect();
+    /// assert_eq!(values, vec![vec![1, 2, 3], vec![7, 8], vec![12]]);
+    /// ```
+    pub fn split_when<F>(self, mut pred: F) -> Vec<Self>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        let mut lists = Vec::new();
+        let mut current = Self::new();
+        for elem in self {
+            if current.back().is_some_and(|tail| pred(tail, &elem)) {
+                lists.push(mem::take(&mut current));
+            }
+            current.push_back(elem);
+        }
+        if !current.is_empty() {
+            lists.push(current);
+        }
+        lists
+    }
+
+    /// Consumes the list, grouping consecutive elements which resolve to the
+    /// same key into sublists.
+    ///
+    /// Each group is returned together with the key of its elements. The key is
+    /// computed once per element. The elements are moved into the arenas of the
+    /// sublists.
+    ///
+    /// This operation should compute in O(n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let list: ArenaXorLinkedList<u32> = vec![10, 11, 20, 30, 31, 15].into_iter().collect();
+    ///
+    /// let groups = list.group_by(|x| x / 10);
+    /// let keys: Vec<u32> = groups.iter().map(|group| group.0).collect();
+    /// assert_eq!(keys, vec![1, 2, 3, 1]);
+    /// assert_eq!(groups[2].1.iter().cloned().collect::<Vec<_>>(), vec![30, 31]);
+    /// ```
+    pub fn group_by<K, F>(self, mut key: F) -> Vec<(K, Self)>
+    where
+        F: FnMut(&T) -> K,
+        K: PartialEq,
+    {
+        let mut groups: Vec<(K, Self)> = Vec::new();
+        for elem in self {
+            let elem_key = key(&elem);
+            match groups.last_mut() {
+                Some(group) if group.0 == elem_key => {
+                    group.1.push_back(elem);
+                    continue;
+                }
+                _ => {}
+            }
+            let mut group = Self::new();
+            group.push_back(elem);
+            groups.push((elem_key, group));
+        }
+        groups
+    }
+
+    /// Checks if the elements of the list are sorted.
+    ///
+    /// That is, for each element `a` and its following element `b`, `a <= b` must hold.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let list: ArenaXorLinkedList<i32> = vec![1, 2, 2, 9].into_iter().collect();
+    /// assert!(list.is_sorted());
+    ///
+    /// let list: ArenaXorLinkedList<i32> = vec![1, 3, 2, 4].into_iter().collect();
+    /// assert!(!list.is_sorted());
+    /// ```
+    pub fn is_sorted(&self) -> bool
+    where
+        T: PartialOrd,
+    {
+        self.iter().zip(self.iter().skip(1)).all(|(a, b)| a <= b)
+    }
+}
+
+impl<T> Drop for ArenaXorLinkedList<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            None
+        } else {
+            let node = &self.nodes[self.head as usize - 1];
+            let next = ArenaXorLinkedList::<T>::get_element(self.last_head, node.reference);
+            self.last_head = mem::replace(&mut self.head, next);
+            self.len -= 1;
+            // Linked nodes always hold an element
+            Some(unsafe { node.data.assume_init_ref() })
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            None
+        } else {
+            let node = &self.nodes[self.tail as usize - 1];
+            let prev = ArenaXorLinkedList::<T>::get_element(self.last_tail, node.reference);
+            self.last_tail = mem::replace(&mut self.tail, prev);
+            self.len -= 1;
+            Some(unsafe { node.data.assume_init_ref() })
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> Default for Iter<'a, T> {
+    /// Creates an empty `Iter`
+    #[inline]
+    fn default() -> Self {
+        Iter {
+            nodes: &[],
+            head: 0,
+            tail: 0,
+            last_head: 0,
+            last_tail: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            None
+        } else {
+            // Every linked slot is yielded at most once, so the returned
+            // references never alias
+            let node = unsafe { &mut *self.nodes.add(self.head as usize - 1) };
+            let next = ArenaXorLinkedList::<T>::get_element(self.last_head, node.reference);
+            self.last_head = mem::replace(&mut self.head, next);
+            self.len -= 1;
+            Some(unsafe { node.data.assume_init_mut() })
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            None
+        } else {
+            let node = unsafe { &mut *self.nodes.add(self.tail as usize - 1) };
+            let prev = ArenaXorLinkedList::<T>::get_element(self.last_tail, node.reference);
+            self.last_tail = mem::replace(&mut self.tail, prev);
+            self.len -= 1;
+            Some(unsafe { node.data.assume_init_mut() })
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<'a, T> Default for IterMut<'a, T> {
+    /// Creates an empty `IterMut`
+    #[inline]
+    fn default() -> Self {
+        IterMut {
+            nodes: NonNull::dangling().as_ptr(),
+            head: 0,
+            tail: 0,
+            last_head: 0,
+            last_tail: 0,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> FusedIterator for IterMut<'a, T> {}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> Default for IntoIter<T> {
+    /// Creates an empty `IntoIter`
+    #[inline]
+    fn default() -> Self {
+        ArenaXorLinkedList::new().into_iter()
+    }
+}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<'a, T> Drain<'a, T> {
+    /// Returns an iterator over the elements which have not been yielded yet.
+    fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            nodes: &self.list.nodes,
+            head: self.head,
+            tail: self.tail,
+            last_head: 0,
+            last_tail: 0,
+            len: self.len,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Drain<'a, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        match self.head {
+            0 => None,
+            node => {
+                self.head = ArenaXorLinkedList::<T>::get_element(0, self.list.node(node).reference);
+                match self.head {
+                    0 => self.tail = 0,
+                    head => self.list.node_mut(head).reference ^= node,
+                }
+                self.len -= 1;
+                Some(unsafe { self.list.release(node) })
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Drain<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        match self.tail {
+            0 => None,
+            node => {
+                self.tail = ArenaXorLinkedList::<T>::get_element(0, self.list.node(node).reference);
+                match self.tail {
+                    0 => self.head = 0,
+                    tail => self.list.node_mut(tail).reference ^= node,
+                }
+                self.len -= 1;
+                Some(unsafe { self.list.release(node) })
+            }
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Drain<'a, T> {}
+
+impl<'a, T> FusedIterator for Drain<'a, T> {}
+
+impl<'a, T> Drop for Drain<'a, T> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+impl<'a, T, F> Iterator for ExtractIf<'a, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while let Some(elem) = self.cursor.current() {
+            if (self.pred)(elem) {
+                return self.cursor.remove_current();
+            }
+            self.cursor.move_next();
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self
+            .cursor
+            .index()
+            .map(|index| self.cursor.list.len - index)
+            .unwrap_or(0);
+        (0, Some(remaining))
+    }
+}
+
+impl<'a, T, F> FusedIterator for ExtractIf<'a, T, F> where F: FnMut(&mut T) -> bool {}
+
+impl<'a, T> Cursor<'a, T> {
+    /// Returns the cursor position index within the `ArenaXorLinkedList`.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[inline]
+    pub fn index(&self) -> Option<usize> {
+        match self.current {
+            0 => None,
+            _ => Some(self.index),
+        }
+    }
+
+    /// Moves the cursor to the next element of the `ArenaXorLinkedList`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the `ArenaXorLinkedList`. If it is pointing to the last
+    /// element of the `ArenaXorLinkedList` then this will move it to the "ghost" non-element.
+    pub fn move_next(&mut self) {
+        match self.current {
+            0 => {
+                self.current = self.list.head;
+                self.prev = 0;
+                self.index = 0;
+            }
+            current => {
+                self.current = ArenaXorLinkedList::<T>::get_element(
+                    self.prev,
+                    self.list.node(current).reference,
+                );
+                self.prev = current;
+                self.index += 1;
+            }
+        }
+    }
+
+    /// Moves the cursor to the previous element of the `ArenaXorLinkedList`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the `ArenaXorLinkedList`. If it is pointing to the first
+    /// element of the `ArenaXorLinkedList` then this will move it to the "ghost" non-element.
+    pub fn move_prev(&mut self) {
+        match self.prev {
+            0 => {
+                self.current = 0;
+                self.prev = self.list.tail;
+                self.index = self.list.len;
+            }
+            prev => {
+                self.prev = ArenaXorLinkedList::<T>::get_element(
+                    self.current,
+                    self.list.node(prev).reference,
+                );
+                self.current = prev;
+                self.index -= 1;
+            }
+        }
+    }
+
+    /// Returns a reference to the element that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[inline]
+    pub fn current(&self) -> Option<&'a T> {
+        self.list.element(self.current)
+    }
+
+    /// Returns a reference to the next element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the first element of the `ArenaXorLinkedList`. If it is pointing to the last
+    /// element of the `ArenaXorLinkedList` then this returns `None`.
+    #[inline]
+    pub fn peek_next(&self) -> Option<&'a T> {
+        let next = match self.current {
+            0 => self.list.head,
+            current => {
+                ArenaXorLinkedList::<T>::get_element(self.prev, self.list.node(current).reference)
+            }
+        };
+        self.list.element(next)
+    }
+
+    /// Returns a reference to the previous element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the last element of the `ArenaXorLinkedList`. If it is pointing to the first
+    /// element of the `ArenaXorLinkedList` then this returns `None`.
+    #[inline]
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        self.list.element(self.prev)
+    }
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    /// Returns the cursor position index within the `ArenaXorLinkedList`.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[inline]
+    pub fn index(&self) -> Option<usize> {
+        match self.current {
+            0 => None,
+            _ => Some(self.index),
+        }
+    }
+
+    /// Moves the cursor to the next element of the `ArenaXorLinkedList`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the `ArenaXorLinkedList`. If it is pointing to the last
+    /// element of the `ArenaXorLinkedList` then this will move it to the "ghost" non-element.
+    pub fn move_next(&mut self) {
+        match self.current {
+            0 => {
+                self.current = self.list.head;
+                self.prev = 0;
+                self.index = 0;
+            }
+            current => {
+                self.current = self.next_node();
+                self.prev = current;
+                self.index += 1;
+            }
+        }
+    }
+
+    /// Moves the cursor to the previous element of the `ArenaXorLinkedList`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the `ArenaXorLinkedList`. If it is pointing to the first
+    /// element of the `ArenaXorLinkedList` then this will move it to the "ghost" non-element.
+    pub fn move_prev(&mut self) {
+        match self.prev {
+            0 => {
+                self.current = 0;
+                self.prev = self.list.tail;
+                self.index = self.list.len;
+            }
+            prev => {
+                self.prev = ArenaXorLinkedList::<T>::get_element(
+                    self.current,
+                    self.list.node(prev).reference,
+                );
+                self.current = prev;
+                self.index -= 1;
+            }
+        }
+    }
+
+    /// Returns a reference to the element that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[inline]
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.list.element_mut(self.current)
+    }
+
+    /// Returns a reference to the next element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the first element of the `ArenaXorLinkedList`. If it is pointing to the last
+    /// element of the `ArenaXorLinkedList` then this returns `None`.
+    #[inline]
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = self.next_node();
+        self.list.element_mut(next)
+    }
+
+    /// Returns a reference to the previous element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the last element of the `ArenaXorLinkedList`. If it is pointing to the first
+    /// element of the `ArenaXorLinkedList` then this returns `None`.
+    #[inline]
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        self.list.element_mut(self.prev)
+    }
+
+    /// Returns a read-only cursor pointing to the current element.
+    ///
+    /// The lifetime of the returned `Cursor` is bound to that of the
+    /// `CursorMut`, which means it cannot outlive the `CursorMut` and that the
+    /// `CursorMut` is frozen for the lifetime of the `Cursor`.
+    #[inline]
+    pub fn as_cursor(&self) -> Cursor<'_, T> {
+        Cursor {
+            index: self.index,
+            current: self.current,
+            prev: self.prev,
+            list: self.list,
+        }
+    }
+
+    /// Inserts a new element into the `ArenaXorLinkedList` after the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is
+    /// inserted at the front of the `ArenaXorLinkedList`.
+    ///
+    /// This operation should compute in O(1) time.
+    pub fn insert_after(&mut self, item: T) {
+        let node = self.list.allocate(item);
+        let next = self.next_node();
+        self.list.link_node(self.current, next, node);
+        if self.current == 0 {
+            // The ghost non-element follows the tail which may have changed
+            self.prev = self.list.tail;
+            self.index = self.list.len;
+        }
+    }
+
+    /// Inserts a new element into the `ArenaXorLinkedList` before the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is
+    /// inserted at the end of the `ArenaXorLinkedList`.
+    ///
+    /// This operation should compute in O(1) time.
+    pub fn insert_before(&mut self, item: T) {
+        let node = self.list.allocate(item);
+        self.list.link_node(self.prev, self.current, node);
+        self.prev = node;
+        self.index += 1;
+    }
+
+    /// Removes the current element from the `ArenaXorLinkedList`.
+    ///
+    /// The element that was removed is returned, and the cursor is
+    /// moved to point to the next element in the `ArenaXorLinkedList`.
+    ///
+    /// If the cursor is currently pointing to the "ghost" non-element then no element
+    /// is removed and `None` is returned.
+    ///
+    /// This operation should compute in O(1) time.
+    pub fn remove_current(&mut self) -> Option<T> {
+        match self.current {
+            0 => None,
+            current => {
+                let next = self.next_node();
+                self.list.unlink_node(self.prev, current, next);
+                self.current = next;
+                Some(unsafe { self.list.release(current) })
+            }
+        }
+    }
+
+    /// Inserts the elements from the given `ArenaXorLinkedList` after the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new elements are
+    /// inserted at the start of the `ArenaXorLinkedList`.
+    ///
+    /// The elements are moved into the arena of the list the cursor points into.
+    ///
+    /// This operation should compute in O(m) time, where `m` is the length of the
+    /// given list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<u32> = (0..2).collect();
+    /// let other: ArenaXorLinkedList<u32> = (10..12).collect();
+    ///
+    /// list.cursor_front_mut().splice_after(other);
+    ///
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&0));
+    /// assert_eq!(iter.next(), Some(&10));
+    /// assert_eq!(iter.next(), Some(&11));
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn splice_after(&mut self, list: ArenaXorLinkedList<T>) {
+        self.list.reserve(list.len);
+        // Inserting right after the current element reverses the order once more
+        for elem in list.into_iter().rev() {
+            self.insert_after(elem);
+        }
+    }
+
+    /// Inserts the elements from the given `ArenaXorLinkedList` before the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new elements are
+    /// inserted at the end of the `ArenaXorLinkedList`.
+    ///
+    /// The elements are moved into the arena of the list the cursor points into.
+    ///
+    /// This operation should compute in O(m) time, where `m` is the length of the
+    /// given list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<u32> = (0..2).collect();
+    /// let other: ArenaXorLinkedList<u32> = (10..12).collect();
+    ///
+    /// {
+    ///     let mut cursor = list.cursor_back_mut();
+    ///     cursor.splice_before(other);
+    ///     assert_eq!(cursor.index(), Some(3));
+    /// }
+    ///
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&0));
+    /// assert_eq!(iter.next(), Some(&10));
+    /// assert_eq!(iter.next(), Some(&11));
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn splice_before(&mut self, list: ArenaXorLinkedList<T>) {
+        self.list.reserve(list.len);
+        for elem in list {
+            self.insert_before(elem);
+        }
+    }
+
+    /// Splits the list into two after the current element. This will return a
+    /// new list consisting of everything after the cursor, with the original
+    /// list retaining everything before.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the entire contents
+    /// of the `ArenaXorLinkedList` are moved.
+    ///
+    /// The smaller part is moved into a new arena, see [`split_off`].
+    ///
+    /// This operation should compute in O(min(i, n - i)) time.
+    ///
+    /// [`split_off`]: struct.ArenaXorLinkedList.html#method.split_off
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<u32> = (0..4).collect();
+    ///
+    /// let splitted = list.cursor_front_mut().split_after();
+    ///
+    /// assert_eq!(list.len(), 1);
+    /// assert_eq!(splitted.len(), 3);
+    /// assert_eq!(splitted.front(), Some(&1));
+    /// ```
+    pub fn split_after(&mut self) -> ArenaXorLinkedList<T> {
+        if self.current == 0 {
+            self.prev = 0;
+            self.index = 0;
+            return mem::take(self.list);
+        }
+        let second_part = self.list.split_off(self.index + 1);
+        // The current element is the tail now, but it may have been moved
+        self.current = self.list.tail;
+        self.prev = self.list.tail_prev();
+        second_part
+    }
+
+    /// Splits the list into two before the current element. This will return a
+    /// new list consisting of everything before the cursor, with the original
+    /// list retaining everything after.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the entire contents
+    /// of the `ArenaXorLinkedList` are moved.
+    ///
+    /// The smaller part is moved into a new arena, see [`split_off`].
+    ///
+    /// This operation should compute in O(min(i, n - i)) time.
+    ///
+    /// [`split_off`]: struct.ArenaXorLinkedList.html#method.split_off
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ArenaXorLinkedList;
+    ///
+    /// let mut list: ArenaXorLinkedList<u32> = (0..4).collect();
+    ///
+    /// let splitted = list.cursor_back_mut().split_before();
+    ///
+    /// assert_eq!(list.len(), 1);
+    /// assert_eq!(splitted.len(), 3);
+    /// assert_eq!(splitted.back(), Some(&2));
+    /// ```
+    pub fn split_before(&mut self) -> ArenaXorLinkedList<T> {
+        if self.current == 0 {
+            self.prev = 0;
+            self.index = 0;
+            return mem::take(self.list);
+        }
+        let second_part = self.list.split_off(self.index);
+        let first_part = mem::replace(self.list, second_part);
+        // The current element is the head now, but it may have been moved
+        self.current = self.list.head;
+        self.prev = 0;
+        self.index = 0;
+        first_part
+    }
+
+    /// Returns the node following the current one, which is the head of the
+    /// `ArenaXorLinkedList` if the cursor is pointing to the "ghost" non-element.
+    #[inline]
+    fn next_node(&self) -> u32 {
+        match self.current {
+            0 => self.list.head,
+            current => {
+                ArenaXorLinkedList::<T>::get_element(self.prev, self.list.node(current).reference)
+            }
+        }
+    }
+}
+
+impl<T> FromIterator<T> for ArenaXorLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> IntoIterator for ArenaXorLinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Consumes the list into an iterator yielding elements by value
+    #[inline]
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ArenaXorLinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut ArenaXorLinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> Extend<T> for ArenaXorLinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let elements = iter.into_iter();
+        self.reserve(elements.size_hint().0);
+        for elt in elements {
+            self.push_back(elt);
+        }
+    }
+}
+
+impl<'a, T: 'a + Copy> Extend<&'a T> for ArenaXorLinkedList<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+impl<T: PartialEq> PartialEq for ArenaXorLinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other)
+    }
+}
+
+impl<T: Eq> Eq for ArenaXorLinkedList<T> {}
+
+impl<T: PartialOrd> PartialOrd for ArenaXorLinkedList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other)
+    }
+}
+
+impl<T: Ord> Ord for ArenaXorLinkedList<T> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other)
+    }
+}
+
+impl<T: Clone> Clone for ArenaXorLinkedList<T> {
+    /// Clones the elements in list order into a new arena without vacant slots
+    fn clone(&self) -> Self {
+        let mut list = Self::with_capacity(self.len);
+        list.extend(self.iter().cloned());
+        list
+    }
+}
+
+impl<T> Index<usize> for ArenaXorLinkedList<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("Out of bounds access")
+    }
+}
+
+impl<T> IndexMut<usize> for ArenaXorLinkedList<T> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("Out of bounds access")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArenaXorLinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: Hash> Hash for ArenaXorLinkedList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for elt in self {
+            elt.hash(state);
+        }
+    }
+}
+
+// Ensure that `ArenaXorLinkedList` and its read-only iterators are covariant in their type parameters
+#[allow(dead_code)]
+fn assert_covariance() {
+    fn a<'a>(x: ArenaXorLinkedList<&'static str>) -> ArenaXorLinkedList<&'a str> {
+        x
+    }
+    fn b<'i, 'a>(x: Iter<'i, &'static str>) -> Iter<'i, &'a str> {
+        x
+    }
+    fn c<'a>(x: IntoIter<&'static str>) -> IntoIter<&'a str> {
+        x
+    }
+    fn d<'i, 'a>(x: Cursor<'i, &'static str>) -> Cursor<'i, &'a str> {
+        x
+    }
+}
+
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArenaXorLinkedList, Cursor, CursorMut, Drain, IntoIter, Iter, IterMut};
+    use rand::{thread_rng, RngCore};
+    use std::cell::Cell;
+    use std::collections::HashMap;
+    use std::hash::Hash;
+    use std::panic;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    struct DropCounter(Rc<Cell<usize>>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    fn list_from<T: Clone>(v: &[T]) -> ArenaXorLinkedList<T> {
+        v.iter().cloned().collect()
+    }
+
+    fn check_links<T>(list: &ArenaXorLinkedList<T>) {
+        // every slot is either linked or part of the free list
+        let mut vacant = 0;
+        let mut free = list.free;
+        while free != 0 {
+            vacant += 1;
+            assert!(vacant <= list.nodes.len(), "free list contains a cycle");
+            free = list.node(free).reference;
+        }
+        assert_eq!(list.nodes.len(), list.len + vacant);
+
+        match (list.head, list.tail) {
+            (0, 0) => {
+                assert_eq!(0, list.len);
+                return;
+            }
+            (0, _) | (_, 0) => panic!("tail and head must both be zero or non-zero"),
+            _ => {}
+        }
+
+        let mut last = 0;
+        let mut link = list.head;
+        for _ in 0..(list.len - 1) {
+            let next = ArenaXorLinkedList::<T>::get_element(last, list.node(link).reference);
+            assert_ne!(next, 0, "next link is null, not good");
+            last = link;
+            link = next;
+        }
+        assert_eq!(link, list.tail);
+
+        last = 0;
+        for _ in 0..(list.len - 1) {
+            let prev = ArenaXorLinkedList::<T>::get_element(last, list.node(link).reference);
+            assert_ne!(prev, 0, "prev link is null, not good");
+            last = link;
+            link = prev;
+        }
+        assert_eq!(link, list.head);
+    }
+
+    #[test]
+    fn test_append() {
+        let mut m = list_from(&[1, 2, 3]);
+        let mut n = list_from(&[4, 5]);
+        m.append(&mut n);
+        check_links(&m);
+        check_links(&n);
+        assert!(n.is_empty());
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[1, 2, 3, 4, 5]);
+
+        let mut o = ArenaXorLinkedList::new();
+        o.append(&mut m);
+        check_links(&o);
+        assert_eq!(o.len(), 5);
+        assert!(m.is_empty());
+
+        // the shorter list is moved into the arena of the longer one
+        let mut p = list_from(&[0]);
+        let arena = o.nodes.as_ptr();
+        p.append(&mut o);
+        check_links(&p);
+        check_links(&o);
+        assert_eq!(p.nodes.as_ptr(), arena);
+        assert_eq!(p.iter().cloned().collect::<Vec<_>>(), &[0, 1, 2, 3, 4, 5]);
+        assert!(o.is_empty());
+    }
+
+    #[test]
+    fn test_split_off() {
+        let v = vec![1, 2, 3, 4, 5];
+        let m = list_from(&v);
+        for ix in 0..=v.len() {
+            let mut a = m.clone();
+            let b = a.split_off(ix);
+            check_links(&a);
+            check_links(&b);
+            assert_eq!(a.iter().cloned().collect::<Vec<_>>(), &v[..ix]);
+            assert_eq!(b.iter().cloned().collect::<Vec<_>>(), &v[ix..]);
+        }
+    }
+
+    #[test]
+    fn test_slot_reuse() {
+        let mut m = ArenaXorLinkedList::with_capacity(4);
+        for i in 0..100 {
+            m.push_back(i);
+            m.push_front(i);
+            m.pop_back();
+            if i % 2 == 0 {
+                m.pop_front();
+            }
+            check_links(&m);
+        }
+        assert_eq!(m.len(), 50);
+        assert_eq!(m.nodes.len(), 51);
+
+        m.reserve(10);
+        assert!(m.capacity() >= 60);
+        m.shrink_to_fit();
+        check_links(&m);
+        assert_eq!(m.nodes.len(), 50);
+        let expected: Vec<_> = (0..100).filter(|i| i % 2 == 1).rev().collect();
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut m = list_from(&[1, 2, 3, 4]);
+        for elt in m.iter_mut().rev().take(2) {
+            *elt *= 10;
+        }
+        assert_eq!(m.iter().rev().cloned().collect::<Vec<_>>(), &[40, 30, 2, 1]);
+    }
+
+    #[test]
+    fn test_drops() {
+        let drops = Rc::new(Cell::new(0));
+        let mut m = ArenaXorLinkedList::new();
+        for _ in 0..8 {
+            m.push_back(DropCounter(Rc::clone(&drops)));
+        }
+        drop(m.pop_front());
+        drop(m.remove(2));
+        assert_eq!(drops.get(), 2);
+        // vacant slots are not dropped again
+        m.drain(1..3).next();
+        assert_eq!(drops.get(), 4);
+        let mut iter = m.into_iter();
+        drop(iter.next_back());
+        assert_eq!(drops.get(), 5);
+        drop(iter);
+        assert_eq!(drops.get(), 8);
+
+        let mut n = ArenaXorLinkedList::new();
+        n.push_back(DropCounter(Rc::clone(&drops)));
+        n.clear();
+        assert_eq!(drops.get(), 9);
+        n.push_back(DropCounter(Rc::clone(&drops)));
+        n.push_back(DropCounter(Rc::clone(&drops)));
+        n.shrink_to_fit();
+        assert_eq!(drops.get(), 9);
+        drop(n);
+        assert_eq!(drops.get(), 11);
+    }
+
+    #[test]
+    fn test_cursor_move_peek() {
+        let m = list_from(&[1, 2, 3, 4, 5, 6]);
+        let mut front = m.cursor_front();
+        assert_eq!(front.current(), Some(&1));
+        assert_eq!(front.peek_next(), Some(&2));
+        assert_eq!(front.peek_prev(), None);
+        front.move_prev();
+        assert_eq!(front.current(), None);
+        assert_eq!(front.peek_next(), Some(&1));
+        assert_eq!(front.peek_prev(), Some(&6));
+        assert_eq!(front.index(), None);
+
+        let mut back = m.cursor_back();
+        assert_eq!(back.current(), Some(&6));
+        assert_eq!(back.index(), Some(5));
+        back.move_prev();
+        back.move_prev();
+        assert_eq!(back.current(), Some(&4));
+        assert_eq!(back.peek_next(), Some(&5));
+        assert_eq!(back.peek_prev(), Some(&3));
+        assert_eq!(back.index(), Some(3));
+
+        let mut backwards = Vec::new();
+        let mut rev = m.cursor_back();
+        while let Some(elt) = rev.current() {
+            backwards.push(*elt);
+            rev.move_prev();
+        }
+        assert_eq!(backwards, &[6, 5, 4, 3, 2, 1]);
+
+        let empty = ArenaXorLinkedList::<u32>::new();
+        let mut ghost = empty.cursor_front();
+        assert_eq!(ghost.current(), None);
+        ghost.move_next();
+        assert_eq!(ghost.index(), None);
+        assert_eq!(empty.cursor_back().current(), None);
+    }
+
+    #[test]
+    fn test_cursor_mut_insert_and_remove() {
+        let mut m = list_from(&[1, 2, 3, 4, 5]);
+        {
+            let mut cursor = m.cursor_front_mut();
+            cursor.move_next();
+            cursor.insert_before(7);
+            cursor.insert_after(8);
+            assert_eq!(cursor.index(), Some(2));
+            assert_eq!(cursor.remove_current(), Some(2));
+            assert_eq!(cursor.current(), Some(&mut 8));
+            cursor.move_prev();
+            cursor.move_prev();
+            cursor.move_prev();
+            assert_eq!(cursor.index(), None);
+            cursor.insert_after(9);
+            cursor.insert_before(10);
+            assert_eq!(cursor.remove_current(), None);
+            assert_eq!(cursor.as_cursor().peek_prev(), Some(&10));
+        }
+        check_links(&m);
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            &[9, 1, 7, 8, 3, 4, 5, 10]
+        );
+        {
+            let mut cursor = m.cursor_back_mut();
+            while cursor.remove_current().is_some() {
+                cursor.move_prev();
+            }
+            assert_eq!(cursor.index(), None);
+        }
+        check_links(&m);
+        assert!(m.is_empty());
+    }
+
+    #[test]
+    fn test_cursor_mut_splice() {
+        let mut m = list_from(&[1, 2, 3, 4, 5, 6]);
+        {
+            let mut cursor = m.cursor_front_mut();
+            cursor.move_next();
+            cursor.splice_before(list_from(&[7, 8]));
+            assert_eq!(cursor.index(), Some(3));
+            cursor.splice_after(list_from(&[9]));
+            cursor.splice_after(ArenaXorLinkedList::new());
+            cursor.splice_before(ArenaXorLinkedList::new());
+            assert_eq!(cursor.current(), Some(&mut 2));
+            assert_eq!(cursor.peek_prev(), Some(&mut 8));
+            assert_eq!(cursor.peek_next(), Some(&mut 9));
+            cursor.move_prev();
+            cursor.move_prev();
+            cursor.move_prev();
+            cursor.move_prev();
+            assert_eq!(cursor.index(), None);
+            cursor.splice_after(list_from(&[10, 11]));
+            cursor.splice_before(list_from(&[12, 13]));
+            assert_eq!(cursor.index(), None);
+            assert_eq!(cursor.peek_prev(), Some(&mut 13));
+        }
+        check_links(&m);
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            &[10, 11, 1, 7, 8, 2, 9, 3, 4, 5, 6, 12, 13]
+        );
+    }
+
+    #[test]
+    fn test_cursor_mut_split() {
+        let v = vec![1, 2, 3, 4, 5];
+        for ix in 0..=v.len() {
+            let mut a = list_from(&v);
+            let (b, c) = {
+                let mut cursor = a.cursor_front_mut();
+                for _ in 0..ix {
+                    cursor.move_next();
+                }
+                let after = cursor.split_after();
+                assert_eq!(cursor.current().cloned(), v.get(ix).cloned());
+                (after, cursor.split_before())
+            };
+            check_links(&a);
+            check_links(&b);
+            check_links(&c);
+            if ix == v.len() {
+                assert_eq!(b.iter().cloned().collect::<Vec<_>>(), v);
+                assert!(a.is_empty() && c.is_empty());
+            } else {
+                assert_eq!(c.iter().cloned().collect::<Vec<_>>(), &v[..ix]);
+                assert_eq!(a.iter().cloned().collect::<Vec<_>>(), &v[ix..=ix]);
+                assert_eq!(b.iter().cloned().collect::<Vec<_>>(), &v[ix + 1..]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_and_index() {
+        let v = vec![1, 2, 3, 4, 5, 6, 7];
+        let mut m = list_from(&v);
+        for (i, elt) in v.iter().enumerate() {
+            assert_eq!(m.get(i), Some(elt));
+            assert_eq!(m[i], *elt);
+        }
+        assert_eq!(m.get(v.len()), None);
+        for i in 0..v.len() {
+            m[i] *= 10;
+            if let Some(elt) = m.get_mut(i) {
+                *elt += 1;
+            }
+        }
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            &[11, 21, 31, 41, 51, 61, 71]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_out_of_bounds() {
+        let m = list_from(&[1, 2, 3]);
+        let _ = m[3];
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut m = ArenaXorLinkedList::new();
+        let mut v = Vec::new();
+        for i in 0..16 {
+            let at = (i * 7) % (v.len() + 1);
+            m.insert(at, i);
+            v.insert(at, i);
+            check_links(&m);
+        }
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), v);
+        assert_eq!(m.remove(v.len()), None);
+        while !v.is_empty() {
+            let at = (v.len() * 2) / 3;
+            assert_eq!(m.remove(at), Some(v.remove(at)));
+            check_links(&m);
+        }
+        assert!(m.is_empty());
+    }
+
+    #[test]
+    fn test_sort() {
+        for len in 0..64 {
+            let v: Vec<(u8, usize)> = (0..len)
+                .map(|i| ((thread_rng().next_u32() % 8) as u8, i))
+                .collect();
+            let mut m = list_from(&v);
+            m.sort_by_key(|&(key, _)| key);
+            check_links(&m);
+
+            // the index breaks ties, thus a stable sort yields the fully sorted list
+            let mut sorted = v.clone();
+            sorted.sort();
+            assert_eq!(m.iter().cloned().collect::<Vec<_>>(), sorted);
+            assert!(m.is_sorted());
+
+            m.sort_by(|a, b| b.cmp(a));
+            check_links(&m);
+            sorted.reverse();
+            assert_eq!(m.iter().cloned().collect::<Vec<_>>(), sorted);
+        }
+    }
+
+    #[test]
+    fn test_merge_sorted() {
+        for len in 0..32 {
+            let split = (thread_rng().next_u32() as usize) % (len + 1);
+            let v: Vec<(u8, usize)> = (0..len)
+                .map(|i| ((thread_rng().next_u32() % 8) as u8, i))
+                .collect();
+            let mut left: Vec<_> = v[..split].to_vec();
+            let mut right: Vec<_> = v[split..].to_vec();
+            left.sort();
+            right.sort_by_key(|&(key, _)| key);
+
+            let mut m = list_from(&left);
+            let mut n = list_from(&right);
+            m.merge_sorted_by(&mut n, |a, b| a.0.cmp(&b.0));
+            check_links(&m);
+            check_links(&n);
+            assert!(n.is_empty());
+
+            // elements of the left list precede equal elements of the right one
+            let mut merged = left.clone();
+            merged.extend(right.iter().cloned());
+            merged.sort_by_key(|&(key, _)| key);
+            assert_eq!(m.iter().cloned().collect::<Vec<_>>(), merged);
+        }
+    }
+
+    #[test]
+    fn test_retain_and_dedup() {
+        let mut m: ArenaXorLinkedList<u32> = (0..20).collect();
+        m.retain(|&x| x % 3 != 0);
+        check_links(&m);
+        m.retain_mut(|x| {
+            *x *= 2;
+            *x > 20
+        });
+        check_links(&m);
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            &[22, 26, 28, 32, 34, 38]
+        );
+
+        let mut n = list_from(&[1, 1, 2, 3, 3, 3, 1, 4, 4]);
+        n.dedup();
+        check_links(&n);
+        assert_eq!(n.iter().cloned().collect::<Vec<_>>(), &[1, 2, 3, 1, 4]);
+
+        // removed elements are passed first, the retained ones may be updated
+        let mut o = list_from(&[1, 2, 3, 10, 11, 20]);
+        o.dedup_by(|a, b| {
+            if *a - *b == 1 {
+                *b = *a;
+                true
+            } else {
+                false
+            }
+        });
+        check_links(&o);
+        assert_eq!(o.iter().cloned().collect::<Vec<_>>(), &[3, 11, 20]);
+    }
+
+    #[test]
+    fn test_partition_and_groups() {
+        let (even, odd) = list_from(&[1, 2, 3, 4, 5, 6, 7]).partition(|x| x % 2 == 0);
+        check_links(&even);
+        check_links(&odd);
+        assert_eq!(even.iter().cloned().collect::<Vec<_>>(), &[2, 4, 6]);
+        assert_eq!(odd.iter().cloned().collect::<Vec<_>>(), &[1, 3, 5, 7]);
+
+        let runs = list_from(&[1, 2, 3, 7, 8, 12]).split_when(|a, b| b - a > 1);
+        let values: Vec<Vec<i32>> = runs.iter().map(|r| r.iter().cloned().collect()).collect();
+        assert_eq!(values, vec![vec![1, 2, 3], vec![7, 8], vec![12]]);
+
+        let groups = list_from(&[10, 11, 20, 30, 31, 32, 15]).group_by(|x| x / 10);
+        assert_eq!(
+            groups.iter().map(|g| g.0).collect::<Vec<_>>(),
+            &[1, 2, 3, 1]
+        );
+        assert_eq!(
+            groups[2].1.iter().cloned().collect::<Vec<_>>(),
+            &[30, 31, 32]
+        );
+    }
+
+    #[test]
+    fn test_extract_if() {
+        let mut m: ArenaXorLinkedList<u32> = (0..10).collect();
+        {
+            let mut iter = m.extract_if(|x| *x % 4 == 0 || *x == 9);
+            assert_eq!(iter.next(), Some(0));
+            assert_eq!(iter.next(), Some(4));
+            assert_eq!(iter.size_hint(), (0, Some(5)));
+        }
+        check_links(&m);
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            &[1, 2, 3, 5, 6, 7, 8, 9]
+        );
+
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            m.extract_if(|x| {
+                assert!(*x != 6);
+                *x % 2 == 1
+            })
+            .for_each(drop);
+        }));
+        assert!(result.is_err());
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[2, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_drain() {
+        let v: Vec<u32> = (0..8).collect();
+        for start in 0..=v.len() {
+            for end in start..=v.len() {
+                let mut m = list_from(&v);
+                {
+                    let mut drain = m.drain(start..end);
+                    assert_eq!(drain.len(), end - start);
+                    if end - start > 1 {
+                        assert_eq!(drain.next(), Some(v[start]));
+                        assert_eq!(drain.next_back(), Some(v[end - 1]));
+                    }
+                }
+                check_links(&m);
+                let mut expected = v.clone();
+                expected.drain(start..end);
+                assert_eq!(m.iter().cloned().collect::<Vec<_>>(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut m = list_from(&[1, 2, 3, 4]);
+        m.reverse();
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[4, 3, 2, 1]);
+        m.push_back(0);
+        let tail = m.split_off(3);
+        assert_eq!(tail.iter().cloned().collect::<Vec<_>>(), &[1, 0]);
+        m.append(&mut tail.reversed());
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[4, 3, 2, 0, 1]);
+    }
+
+    #[test]
+    fn test_traits_without_debug() {
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        struct NoDebug(u32);
+
+        fn assert_send<T: Send>() {}
+        fn assert_sync<T: Sync>() {}
+        fn assert_clone<T: Clone>() {}
+        fn assert_ord<T: Ord>() {}
+        fn assert_hash<T: Hash>() {}
+        fn assert_default<T: Default>() {}
+
+        assert_send::<ArenaXorLinkedList<NoDebug>>();
+        assert_sync::<ArenaXorLinkedList<NoDebug>>();
+        assert_clone::<ArenaXorLinkedList<NoDebug>>();
+        assert_ord::<ArenaXorLinkedList<NoDebug>>();
+        assert_hash::<ArenaXorLinkedList<NoDebug>>();
+        assert_default::<ArenaXorLinkedList<NoDebug>>();
+
+        assert_default::<Iter<NoDebug>>();
+        assert_send::<IterMut<NoDebug>>();
+        assert_default::<IterMut<NoDebug>>();
+        assert_clone::<IntoIter<NoDebug>>();
+        assert_default::<IntoIter<NoDebug>>();
+        assert_send::<Cursor<NoDebug>>();
+        assert_clone::<Cursor<NoDebug>>();
+        assert_send::<CursorMut<NoDebug>>();
+        assert_sync::<Drain<NoDebug>>();
+
+        let mut map = HashMap::new();
+        map.insert(list_from(&[NoDebug(1), NoDebug(2)]), 1);
+        assert_eq!(map.get(&list_from(&[NoDebug(1), NoDebug(2)])), Some(&1));
+    }
+
+    #[test]
+    fn test_fuzz() {
+        // Miri is orders of magnitude slower, so only do a few rounds there
+        let rounds = if cfg!(miri) { 2 } else { 25 };
+        for _ in 0..rounds {
+            fuzz_test(3);
+            fuzz_test(16);
+            fuzz_test(189);
+        }
+    }
+
+    fn fuzz_test(sz: i32) {
+        let mut m: ArenaXorLinkedList<_> = ArenaXorLinkedList::new();
+        let mut v = vec![];
+        for i in 0..sz {
+            check_links(&m);
+            let r: u8 = thread_rng().next_u32() as u8;
+            match r % 8 {
+                0 => {
+                    m.pop_back();
+                    v.pop();
+                }
+                1 => {
+                    if !v.is_empty() {
+                        m.pop_front();
+                        v.remove(0);
+                    }
+                }
+                2 | 4 => {
+                    m.push_front(-i);
+                    v.insert(0, -i);
+                }
+                6 => {
+                    let at = thread_rng().next_u32() as usize % (v.len() + 1);
+                    m.insert(at, i);
+                    v.insert(at, i);
+                }
+                7 => {
+                    let at = thread_rng().next_u32() as usize % (v.len() + 1);
+                    assert_eq!(
+                        m.remove(at),
+                        if at < v.len() {
+                            Some(v.remove(at))
+                        } else {
+                            None
+                        }
+                    );
+                }
+                _ => {
+                    m.push_back(i);
+                    v.push(i);
+                }
+            }
+        }
+
+        check_links(&m);
+        assert_eq!(m.into_iter().collect::<Vec<_>>(), v);
+    }
+}
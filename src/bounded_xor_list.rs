@@ -0,0 +1,542 @@
+//! A doubly-linked list with a maximum length
+//!
+//! The `BoundedXorList` is a [`XorLinkedList`] which never grows beyond a
+//! given number of elements. What happens when an element is pushed onto a
+//! full list is decided by its [`OverflowPolicy`]. Evicted elements are handed
+//! to an optional callback before they are dropped.
+//!
+//! The nodes of evicted elements are reused by the following insertion, so a
+//! full list does not allocate while elements are pushed.
+//!
+//! [`XorLinkedList`]: ../xor_linked_list/struct.XorLinkedList.html
+//! [`OverflowPolicy`]: enum.OverflowPolicy.html
+
+use std::{error::Error, fmt};
+use xor_linked_list::{self, Iter, IterMut};
+use XorLinkedList;
+
+/// Decides what happens when an element is pushed onto a full `BoundedXorList`
+///
+/// The oldest element is the one at the opposite end of the list, the newest
+/// one is the element at the end the new element is pushed to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OverflowPolicy {
+    /// Evicts the element at the opposite end of the list
+    EvictOldest,
+    /// Evicts the element at the end the new element is pushed to
+    EvictNewest,
+    /// Rejects the new element
+    Reject,
+}
+
+/// The error returned when an element is pushed onto a full `BoundedXorList`
+/// with the `Reject` policy
+///
+/// The rejected element can be recovered with [`into_inner`].
+///
+/// [`into_inner`]: #method.into_inner
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError<T> {
+    value: T,
+}
+
+impl<T> CapacityError<T> {
+    /// Returns the rejected element
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> fmt::Debug for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CapacityError").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("list is at its maximum length")
+    }
+}
+
+impl<T> Error for CapacityError<T> {}
+
+/// A doubly-linked list with a maximum length
+///
+/// Pushing onto a full `BoundedXorList` either evicts an element or rejects
+/// the new one, depending on its [`OverflowPolicy`]. Evicted elements are
+/// passed to the callback set with [`set_evict_callback`].
+///
+/// [`OverflowPolicy`]: enum.OverflowPolicy.html
+/// [`set_evict_callback`]: #method.set_evict_callback
+pub struct BoundedXorList<T> {
+    list: XorLinkedList<T>,
+    max_len: usize,
+    policy: OverflowPolicy,
+    on_evict: Option<Box<dyn FnMut(T) + Send>>,
+}
+
+// private methods
+impl<T> BoundedXorList<T> {
+    /// Hands the given element to the eviction callback, if there is one.
+    fn evict(&mut self, elem: T) {
+        if let Some(ref mut on_evict) = self.on_evict {
+            on_evict(elem);
+        }
+    }
+
+    /// Makes room for a new element according to the policy if the list is
+    /// full. Returns the element to insert, or `None` if it has been evicted
+    /// right away.
+    fn make_room(&mut self, value: T, push_back: bool) -> Result<Option<T>, CapacityError<T>> {
+        if self.list.len() < self.max_len {
+            return Ok(Some(value));
+        }
+        let evicted = match (self.policy, push_back) {
+            (OverflowPolicy::Reject, _) => return Err(CapacityError { value }),
+            (OverflowPolicy::EvictOldest, true) | (OverflowPolicy::EvictNewest, false) => {
+                self.list.pop_front()
+            }
+            (OverflowPolicy::EvictOldest, false) | (OverflowPolicy::EvictNewest, true) => {
+                self.list.pop_back()
+            }
+        };
+        match evicted {
+            Some(elem) => {
+                self.evict(elem);
+                Ok(Some(value))
+            }
+            // a list without room evicts every new element right away
+            None => {
+                self.evict(value);
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl<T> BoundedXorList<T> {
+    /// Creates an empty `BoundedXorList` holding at most `max_len` elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::bounded_xor_list::{BoundedXorList, OverflowPolicy};
+    ///
+    /// let list: BoundedXorList<u32> = BoundedXorList::new(3, OverflowPolicy::EvictOldest);
+    /// assert_eq!(list.max_len(), 3);
+    /// ```
+    #[inline]
+    pub fn new(max_len: usize, policy: OverflowPolicy) -> Self {
+        BoundedXorList {
+            list: XorLinkedList::new(),
+            max_len,
+            policy,
+            on_evict: None,
+        }
+    }
+
+    /// Sets the callback which is called with every evicted element
+    ///
+    /// A previously set callback is replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::mpsc;
+    /// use rust_utils::bounded_xor_list::{BoundedXorList, OverflowPolicy};
+    ///
+    /// let (sender, receiver) = mpsc::channel();
+    /// let mut list = BoundedXorList::new(2, OverflowPolicy::EvictOldest);
+    /// list.set_evict_callback(move |elem| sender.send(elem).unwrap());
+    ///
+    /// list.extend(0..4);
+    /// assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![0, 1]);
+    /// ```
+    pub fn set_evict_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut(T) + Send + 'static,
+    {
+        self.on_evict = Some(Box::new(callback));
+    }
+
+    /// Returns the maximum number of elements of the list
+    #[inline]
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Returns the policy applied when pushing onto a full list
+    #[inline]
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    /// Returns `true` if the list holds its maximum number of elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::bounded_xor_list::{BoundedXorList, OverflowPolicy};
+    ///
+    /// let mut list = BoundedXorList::new(1, OverflowPolicy::Reject);
+    /// assert!(!list.is_full());
+    ///
+    /// list.push_back(1);
+    /// assert!(list.is_full());
+    /// ```
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.list.len() >= self.max_len
+    }
+
+    /// Returns `true` if the list is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Returns the length of the list
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Removes all elements from the list. The elements are dropped without
+    /// being passed to the eviction callback.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.list.clear();
+    }
+
+    /// Returns `true` if the list contains an element equal to the given value
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq<T>,
+    {
+        self.list.contains(x)
+    }
+
+    /// Provides a forward iterator
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.list.iter()
+    }
+
+    /// Provides a forward iterator with mutable references
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.list.iter_mut()
+    }
+
+    /// Provides a reference to the front element, or `None` if the list is
+    /// empty
+    #[inline]
+    pub fn front(&self) -> Option<&T> {
+        self.list.front()
+    }
+
+    /// Provides a mutable reference to the front element, or `None` if the list
+    /// is empty
+    #[inline]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.list.front_mut()
+    }
+
+    /// Provides a reference to the back element, or `None` if the list is
+    /// empty
+    #[inline]
+    pub fn back(&self) -> Option<&T> {
+        self.list.back()
+    }
+
+    /// Provides a mutable reference to the back element, or `None` if the list
+    /// is empty
+    #[inline]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.list.back_mut()
+    }
+
+    /// Adds an element first in the list, applying the overflow policy if the
+    /// list is full.
+    ///
+    /// With the `Reject` policy, an element which does not fit is returned
+    /// within the error.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::bounded_xor_list::{BoundedXorList, OverflowPolicy};
+    ///
+    /// let mut list = BoundedXorList::new(2, OverflowPolicy::Reject);
+    /// assert!(list.try_push_front(1).is_ok());
+    /// assert!(list.try_push_front(2).is_ok());
+    /// assert_eq!(list.try_push_front(3).unwrap_err().into_inner(), 3);
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![2, 1]);
+    /// ```
+    pub fn try_push_front(&mut self, value: T) -> Result<(), CapacityError<T>> {
+        if let Some(elem) = self.make_room(value, false)? {
+            self.list.push_front(elem);
+        }
+        Ok(())
+    }
+
+    /// Appends an element to the back of the list, applying the overflow policy
+    /// if the list is full.
+    ///
+    /// With the `Reject` policy, an element which does not fit is returned
+    /// within the error.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::bounded_xor_list::{BoundedXorList, OverflowPolicy};
+    ///
+    /// let mut list = BoundedXorList::new(2, OverflowPolicy::EvictNewest);
+    /// assert!(list.try_push_back(1).is_ok());
+    /// assert!(list.try_push_back(2).is_ok());
+    /// assert!(list.try_push_back(3).is_ok());
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![1, 3]);
+    /// ```
+    pub fn try_push_back(&mut self, value: T) -> Result<(), CapacityError<T>> {
+        if let Some(elem) = self.make_room(value, true)? {
+            self.list.push_back(elem);
+        }
+        Ok(())
+    }
+
+    /// Adds an element first in the list, applying the overflow policy if the
+    /// list is full.
+    ///
+    /// Unlike [`try_push_front`], an element rejected by the `Reject` policy is
+    /// passed to the eviction callback.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// [`try_push_front`]: #method.try_push_front
+    pub fn push_front(&mut self, value: T) {
+        if let Err(err) = self.try_push_front(value) {
+            self.evict(err.into_inner());
+        }
+    }
+
+    /// Appends an element to the back of the list, applying the overflow policy
+    /// if the list is full.
+    ///
+    /// Unlike [`try_push_back`], an element rejected by the `Reject` policy is
+    /// passed to the eviction callback.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::bounded_xor_list::{BoundedXorList, OverflowPolicy};
+    ///
+    /// let mut list = BoundedXorList::new(3, OverflowPolicy::EvictOldest);
+    /// for i in 0..10 {
+    ///     list.push_back(i);
+    /// }
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![7, 8, 9]);
+    /// ```
+    ///
+    /// [`try_push_back`]: #method.try_push_back
+    pub fn push_back(&mut self, value: T) {
+        if let Err(err) = self.try_push_back(value) {
+            self.evict(err.into_inner());
+        }
+    }
+
+    /// Removes the first element and returns it, or `None` if the list is
+    /// empty.
+    #[inline]
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    /// Removes the last element and returns it, or `None` if the list is
+    /// empty.
+    #[inline]
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+
+    /// Provides a reference to the underlying `XorLinkedList`
+    #[inline]
+    pub fn as_list(&self) -> &XorLinkedList<T> {
+        &self.list
+    }
+
+    /// Consumes the bounded list and returns the underlying `XorLinkedList`
+    #[inline]
+    pub fn into_list(self) -> XorLinkedList<T> {
+        self.list
+    }
+}
+
+impl<T> IntoIterator for BoundedXorList<T> {
+    type Item = T;
+    type IntoIter = xor_linked_list::IntoIter<T>;
+
+    /// Consumes the list into an iterator yielding elements by value
+    #[inline]
+    fn into_iter(self) -> xor_linked_list::IntoIter<T> {
+        self.list.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a BoundedXorList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut BoundedXorList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> Extend<T> for BoundedXorList<T> {
+    /// Pushes all elements to the back of the list, see [`push_back`]
+    ///
+    /// [`push_back`]: #method.push_back
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elt in iter {
+            self.push_back(elt);
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for BoundedXorList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BoundedXorList")
+            .field("list", &self.list)
+            .field("max_len", &self.max_len)
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+// The eviction callback is only accessed through `&mut self`
+unsafe impl<T: Sync> Sync for BoundedXorList<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundedXorList, OverflowPolicy};
+    use std::sync::{Arc, Mutex};
+    use std::vec::Vec;
+
+    fn bounded(
+        max_len: usize,
+        policy: OverflowPolicy,
+    ) -> (BoundedXorList<i32>, Arc<Mutex<Vec<i32>>>) {
+        let evicted = Arc::new(Mutex::new(Vec::new()));
+        let mut list = BoundedXorList::new(max_len, policy);
+        let sink = Arc::clone(&evicted);
+        list.set_evict_callback(move |elem| sink.lock().unwrap().push(elem));
+        (list, evicted)
+    }
+
+    fn contents(list: &BoundedXorList<i32>) -> Vec<i32> {
+        list.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_evict_oldest() {
+        let (mut m, evicted) = bounded(3, OverflowPolicy::EvictOldest);
+        m.extend(1..6);
+        assert_eq!(contents(&m), &[3, 4, 5]);
+        assert_eq!(*evicted.lock().unwrap(), &[1, 2]);
+
+        m.push_front(0);
+        assert_eq!(contents(&m), &[0, 3, 4]);
+        assert_eq!(*evicted.lock().unwrap(), &[1, 2, 5]);
+        assert!(m.is_full());
+        assert_eq!(m.len(), 3);
+    }
+
+    #[test]
+    fn test_evict_newest() {
+        let (mut m, evicted) = bounded(3, OverflowPolicy::EvictNewest);
+        m.extend(1..6);
+        assert_eq!(contents(&m), &[1, 2, 5]);
+        assert_eq!(*evicted.lock().unwrap(), &[3, 4]);
+
+        assert!(m.try_push_front(0).is_ok());
+        assert_eq!(contents(&m), &[0, 2, 5]);
+        assert_eq!(*evicted.lock().unwrap(), &[3, 4, 1]);
+    }
+
+    #[test]
+    fn test_reject() {
+        let (mut m, evicted) = bounded(2, OverflowPolicy::Reject);
+        assert!(m.try_push_back(1).is_ok());
+        assert!(m.try_push_front(0).is_ok());
+        let err = m.try_push_back(2).unwrap_err();
+        assert_eq!(format!("{}", err), "list is at its maximum length");
+        assert_eq!(err.into_inner(), 2);
+        assert_eq!(m.try_push_front(3).unwrap_err().into_inner(), 3);
+        assert!(evicted.lock().unwrap().is_empty());
+
+        // rejected elements of the infallible methods go to the callback
+        m.push_back(4);
+        m.push_front(5);
+        assert_eq!(contents(&m), &[0, 1]);
+        assert_eq!(*evicted.lock().unwrap(), &[4, 5]);
+
+        m.pop_front();
+        assert!(m.try_push_back(6).is_ok());
+        assert_eq!(contents(&m), &[1, 6]);
+    }
+
+    #[test]
+    fn test_zero_length() {
+        let (mut m, evicted) = bounded(0, OverflowPolicy::EvictOldest);
+        m.push_back(1);
+        assert!(m.try_push_front(2).is_ok());
+        assert!(m.is_empty());
+        assert_eq!(*evicted.lock().unwrap(), &[1, 2]);
+
+        let (mut n, _) = bounded(0, OverflowPolicy::Reject);
+        assert_eq!(n.try_push_back(1).unwrap_err().into_inner(), 1);
+    }
+
+    #[test]
+    fn test_node_reuse() {
+        let mut m = BoundedXorList::new(4, OverflowPolicy::EvictOldest);
+        m.extend(0..4);
+        let capacity = m.as_list().capacity();
+        m.extend(4..100);
+        assert_eq!(m.as_list().capacity(), capacity);
+        assert_eq!(
+            m.into_list().into_iter().collect::<Vec<_>>(),
+            &[96, 97, 98, 99]
+        );
+    }
+
+    #[test]
+    fn test_without_callback() {
+        let mut m = BoundedXorList::new(2, OverflowPolicy::EvictNewest);
+        m.extend(vec![
+            String::from("a"),
+            String::from("b"),
+            String::from("c"),
+        ]);
+        assert_eq!(m.front().map(|s| s.as_str()), Some("a"));
+        assert_eq!(m.back().map(|s| s.as_str()), Some("c"));
+        assert_eq!(m.policy(), OverflowPolicy::EvictNewest);
+        assert_eq!(m.max_len(), 2);
+    }
+}
@@ -7,7 +7,14 @@
 //! The Key must implement `Ord` to allow quick
 //! lookup.
 
-use std::collections::BTreeMap;
+use alloc::{self, Allocator, Global};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    marker::PhantomData,
+    mem,
+    ptr::{self, NonNull},
+};
 
 /// A TrieMap with owned nodes.
 ///
@@ -17,24 +24,42 @@ use std::collections::BTreeMap;
 ///
 /// The Key must implement `Ord` to allow quick
 /// lookup.
-#[derive(Debug, Clone)]
-pub struct BTrieMap<K: Ord + Clone, V> {
-    children: BTreeMap<K, Box<BTrieMap<K, V>>>,
+///
+/// The nodes of the trie are allocated by the allocator `A`, which is stored
+/// once per trie. The entries of the `BTreeMap` holding the children of a node
+/// still use the global allocator, as `BTreeMap` is not generic over its
+/// allocator on stable Rust.
+pub struct BTrieMap<K: Ord + Clone, V, A: Allocator = Global> {
+    root: Node<K, V>,
+    alloc: A,
+}
+
+struct Node<K, V> {
+    children: BTreeMap<K, NonNull<Node<K, V>>>,
     value: Option<V>,
+    marker: PhantomData<Box<Node<K, V>>>,
 }
 
-impl<'a, K: 'a + Ord + Clone, V> Default for BTrieMap<K, V> {
+impl<K: Ord + Clone, V> Default for BTrieMap<K, V> {
     fn default() -> Self {
         Self::new()
     }
 }
 
 // private methods
-impl<'a, K: 'a + Ord + Clone, V> BTrieMap<K, V> {
+impl<'a, K: 'a + Ord + Clone, V> Node<K, V> {
+    fn new() -> Self {
+        Node {
+            children: BTreeMap::new(),
+            value: None,
+            marker: PhantomData,
+        }
+    }
+
     fn get_node<I: Iterator<Item = &'a K>>(&self, mut iter: I) -> Option<&Self> {
         if let Some(key) = iter.next() {
-            if let Some(node) = self.children.get(&key) {
-                return node.get_node(iter);
+            if let Some(node) = self.children.get(key) {
+                return unsafe { node.as_ref() }.get_node(iter);
             } else {
                 return None;
             }
@@ -42,13 +67,17 @@ impl<'a, K: 'a + Ord + Clone, V> BTrieMap<K, V> {
         Some(self)
     }
 
-    fn get_or_create_node<I: Iterator<Item = &'a K>>(&mut self, mut iter: I) -> &mut Self {
+    fn get_or_create_node<I, A>(&mut self, mut iter: I, alloc: &A) -> &mut Self
+    where
+        I: Iterator<Item = &'a K>,
+        A: Allocator,
+    {
         if let Some(key) = iter.next() {
-            if !self.children.contains_key(&key) {
-                self.children.insert(key.clone(), Box::new(Self::new()));
-            }
-            let node = self.children.get_mut(&key).unwrap();
-            return node.get_or_create_node(iter);
+            let mut node = *self
+                .children
+                .entry(key.clone())
+                .or_insert_with(|| Self::allocate(Self::new(), alloc));
+            return unsafe { node.as_mut() }.get_or_create_node(iter, alloc);
         }
         self
     }
@@ -58,12 +87,69 @@ impl<'a, K: 'a + Ord + Clone, V> BTrieMap<K, V> {
             vector.push(value);
         }
         for node in self.children.values() {
-            node.get_values(vector);
+            unsafe { node.as_ref() }.get_values(vector);
+        }
+    }
+
+    fn allocate<A: Allocator>(node: Self, alloc: &A) -> NonNull<Self> {
+        let ptr = alloc::allocate::<Self, A>(alloc);
+        unsafe { ptr.as_ptr().write(node) };
+        ptr
+    }
+
+    fn clone_in<A: Allocator>(&self, alloc: &A) -> Self
+    where
+        V: Clone,
+    {
+        Node {
+            children: self
+                .children
+                .iter()
+                .map(|(key, node)| {
+                    let copy = unsafe { node.as_ref() }.clone_in(alloc);
+                    (key.clone(), Self::allocate(copy, alloc))
+                })
+                .collect(),
+            value: self.value.clone(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Drops and deallocates all descendants of the node.
+    unsafe fn free_children<A: Allocator>(&mut self, alloc: &A) {
+        for (_, node) in mem::take(&mut self.children) {
+            (*node.as_ptr()).free_children(alloc);
+            ptr::drop_in_place(node.as_ptr());
+            alloc::deallocate(alloc, node);
         }
     }
 }
 
-impl<'a, K: 'a + Ord + Clone, V> BTrieMap<K, V> {
+// Formats the children of a node as a map from their keys to the nodes
+struct Children<'a, K: 'a, V: 'a>(&'a BTreeMap<K, NonNull<Node<K, V>>>);
+
+impl<'a, K: fmt::Debug, V: fmt::Debug> fmt::Debug for Children<'a, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.0
+                    .iter()
+                    .map(|(key, node)| (key, unsafe { node.as_ref() })),
+            )
+            .finish()
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Node<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Node")
+            .field("children", &Children(&self.children))
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl<K: Ord + Clone, V> BTrieMap<K, V> {
     /// Creates an empty `BTrieMap`
     ///
     /// # Examples
@@ -74,12 +160,48 @@ impl<'a, K: 'a + Ord + Clone, V> BTrieMap<K, V> {
     /// let trie: BTrieMap<u8, bool> = BTrieMap::new();
     /// ```
     pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<'a, K: 'a + Ord + Clone, V, A: Allocator> BTrieMap<K, V, A> {
+    /// Creates an empty `BTrieMap` whose nodes are allocated by `alloc`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::{alloc::Global, BTrieMap};
+    ///
+    /// let trie: BTrieMap<u8, bool> = BTrieMap::new_in(Global);
+    /// ```
+    pub fn new_in(alloc: A) -> Self {
         BTrieMap {
-            children: BTreeMap::new(),
-            value: None,
+            root: Node::new(),
+            alloc,
         }
     }
 
+    /// Creates an empty `BTrieMap` whose nodes are allocated by `alloc`. This is
+    /// the same as [`new_in`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::{alloc::Global, BTrieMap};
+    ///
+    /// let trie: BTrieMap<u8, bool> = BTrieMap::with_allocator(Global);
+    /// ```
+    ///
+    /// [`new_in`]: #method.new_in
+    pub fn with_allocator(alloc: A) -> Self {
+        Self::new_in(alloc)
+    }
+
+    /// Returns a reference to the underlying allocator
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
     /// Inserts a given value into the `BTrieMap`. An existing Value with the given
     /// key will be overridden
     ///
@@ -93,7 +215,7 @@ impl<'a, K: 'a + Ord + Clone, V> BTrieMap<K, V> {
     /// trie.insert("Test".as_bytes(), true);
     /// ```
     pub fn insert<I: IntoIterator<Item = &'a K>>(&mut self, key: I, value: V) {
-        let node = self.get_or_create_node(key.into_iter());
+        let node = self.root.get_or_create_node(key.into_iter(), &self.alloc);
         node.value = Some(value);
     }
 
@@ -115,7 +237,8 @@ impl<'a, K: 'a + Ord + Clone, V> BTrieMap<K, V> {
     /// assert_eq!(trie.contains("Test4".as_bytes()), false);
     /// ```
     pub fn contains<I: IntoIterator<Item = &'a K>>(&self, key: I) -> bool {
-        self.get_node(key.into_iter())
+        self.root
+            .get_node(key.into_iter())
             .map(|node| node.value.is_some())
             .unwrap_or(false)
     }
@@ -136,7 +259,8 @@ impl<'a, K: 'a + Ord + Clone, V> BTrieMap<K, V> {
     /// assert_eq!(Some(&true), trie.get("Test".as_bytes()));
     /// ```
     pub fn get<I: IntoIterator<Item = &'a K>>(&self, key: I) -> Option<&V> {
-        self.get_node(key.into_iter())
+        self.root
+            .get_node(key.into_iter())
             .and_then(|node| node.value.as_ref())
     }
 
@@ -157,13 +281,43 @@ impl<'a, K: 'a + Ord + Clone, V> BTrieMap<K, V> {
     /// ```
     pub fn get_with_prefix<I: IntoIterator<Item = &'a K>>(&self, prefix: I) -> Vec<&V> {
         let mut vec = Vec::new();
-        if let Some(node) = self.get_node(prefix.into_iter()) {
+        if let Some(node) = self.root.get_node(prefix.into_iter()) {
             node.get_values(&mut vec);
         }
         vec
     }
 }
 
+impl<K: Ord + Clone, V, A: Allocator> Drop for BTrieMap<K, V, A> {
+    fn drop(&mut self) {
+        unsafe { self.root.free_children(&self.alloc) }
+    }
+}
+
+impl<K: Ord + Clone, V: Clone, A: Allocator + Clone> Clone for BTrieMap<K, V, A> {
+    fn clone(&self) -> Self {
+        BTrieMap {
+            root: self.root.clone_in(&self.alloc),
+            alloc: self.alloc.clone(),
+        }
+    }
+}
+
+impl<K: Ord + Clone + fmt::Debug, V: fmt::Debug, A: Allocator + fmt::Debug> fmt::Debug
+    for BTrieMap<K, V, A>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BTrieMap")
+            .field("root", &self.root)
+            .field("alloc", &self.alloc)
+            .finish()
+    }
+}
+
+unsafe impl<K: Ord + Clone + Send, V: Send, A: Allocator + Send> Send for BTrieMap<K, V, A> {}
+
+unsafe impl<K: Ord + Clone + Sync, V: Sync, A: Allocator + Sync> Sync for BTrieMap<K, V, A> {}
+
 // Ensure that `BTrieMap` and its read-only iterators are covariant in their type parameters
 #[allow(dead_code)]
 fn assert_covariance() {
@@ -174,6 +328,10 @@ fn assert_covariance() {
 
 #[cfg(test)]
 mod tests {
+    use alloc::Global;
+    #[cfg(feature = "allocator-api2")]
+    use bumpalo::Bump;
+    use std::rc::Rc;
     use BTrieMap;
 
     #[test]
@@ -200,4 +358,35 @@ mod tests {
         trie.insert("deal".as_bytes(), false);
         assert_eq!(vec![&false, &true], trie.get_with_prefix("de".as_bytes()));
     }
+
+    #[test]
+    fn test_clone_and_drop() {
+        let value = Rc::new(());
+        {
+            let mut trie = BTrieMap::with_allocator(Global);
+            trie.insert("dog".as_bytes(), Rc::clone(&value));
+            trie.insert("do".as_bytes(), Rc::clone(&value));
+            trie.insert("deer".as_bytes(), Rc::clone(&value));
+            let copy = trie.clone();
+            assert_eq!(Rc::strong_count(&value), 7);
+            drop(trie);
+            assert_eq!(copy.get_with_prefix("do".as_bytes()).len(), 2);
+            assert!(
+                format!("{:?}", copy).starts_with("BTrieMap { root: Node { children: {100: Node")
+            );
+        }
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[cfg(feature = "allocator-api2")]
+    #[test]
+    fn test_allocator() {
+        let bump = Bump::new();
+        let mut trie = BTrieMap::new_in(&bump);
+        trie.insert("dog".as_bytes(), true);
+        trie.insert("deal".as_bytes(), false);
+        let copy = trie.clone();
+        assert_eq!(vec![&false, &true], copy.get_with_prefix("d".as_bytes()));
+        assert!(bump.allocated_bytes() > 0);
+    }
 }
@@ -0,0 +1,580 @@
+//! A byte buffer made up of chunks
+//!
+//! The `ChunkedBuffer` stores its bytes in a [`XorLinkedList`] of `Vec<u8>`
+//! chunks, so that buffers can be joined with [`append`] and split with
+//! [`split_to`] without copying their contents. Bytes are written at the back
+//! and read from the front through the `std::io` traits.
+//!
+//! [`XorLinkedList`]: ../xor_linked_list/struct.XorLinkedList.html
+//! [`append`]: struct.ChunkedBuffer.html#method.append
+//! [`split_to`]: struct.ChunkedBuffer.html#method.split_to
+
+use std::{
+    cmp, fmt,
+    io::{self, BufRead, IoSlice, Read, Write},
+    iter::FusedIterator,
+    mem,
+};
+use xor_linked_list;
+use XorLinkedList;
+
+// the minimal capacity of a chunk allocated by `write`
+const CHUNK_SIZE: usize = 4096;
+
+/// A byte buffer made up of chunks
+///
+/// Written bytes are appended to the last chunk as long as it has spare
+/// capacity, otherwise a new chunk is allocated. Chunks added with [`push`] or
+/// [`append`] are linked without copying. Reading consumes bytes from the front
+/// and releases every chunk which has been read completely.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{Read, Write};
+/// use rust_utils::ChunkedBuffer;
+///
+/// let mut buf = ChunkedBuffer::new();
+/// buf.push(b"Hello".to_vec());
+/// buf.write_all(b", World!").unwrap();
+///
+/// let mut hello = buf.split_to(5);
+/// let mut text = String::new();
+/// hello.read_to_string(&mut text).unwrap();
+/// assert_eq!(text, "Hello");
+/// assert_eq!(buf.remaining(), 8);
+/// ```
+///
+/// [`push`]: #method.push
+/// [`append`]: #method.append
+#[derive(Clone, Default)]
+pub struct ChunkedBuffer {
+    // never holds a chunk without unread bytes
+    chunks: XorLinkedList<Chunk>,
+    len: usize,
+}
+
+// A chunk of bytes of which the first `start` bytes have been read. Keeping the
+// offset per chunk lets buffers be joined without moving any bytes.
+#[derive(Clone)]
+struct Chunk {
+    bytes: Vec<u8>,
+    start: usize,
+}
+
+impl Chunk {
+    #[inline]
+    fn new(bytes: Vec<u8>) -> Self {
+        Chunk { bytes, start: 0 }
+    }
+
+    #[inline]
+    fn unread(&self) -> &[u8] {
+        &self.bytes[self.start..]
+    }
+}
+
+/// An iterator over the unread bytes of a `ChunkedBuffer` as `IoSlice`s.
+///
+/// This `struct` is created by the [`io_slices`] method on [`ChunkedBuffer`].
+/// See its documentation for more.
+///
+/// [`io_slices`]: struct.ChunkedBuffer.html#method.io_slices
+/// [`ChunkedBuffer`]: struct.ChunkedBuffer.html
+pub struct IoSlices<'a> {
+    chunks: xor_linked_list::Iter<'a, Chunk>,
+}
+
+impl<'a> Clone for IoSlices<'a> {
+    fn clone(&self) -> Self {
+        IoSlices {
+            chunks: self.chunks.clone(),
+        }
+    }
+}
+
+impl<'a> fmt::Debug for IoSlices<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IoSlices").field(&self.chunks.len()).finish()
+    }
+}
+
+impl ChunkedBuffer {
+    /// Creates an empty `ChunkedBuffer`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ChunkedBuffer;
+    ///
+    /// let buf = ChunkedBuffer::new();
+    /// assert_eq!(buf.remaining(), 0);
+    /// ```
+    #[inline]
+    pub fn new() -> Self {
+        ChunkedBuffer {
+            chunks: XorLinkedList::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of unread bytes
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if all bytes have been read
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of chunks holding unread bytes
+    #[inline]
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Removes all bytes from the buffer
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.len = 0;
+    }
+
+    /// Adds the given bytes as a new chunk at the back of the buffer without
+    /// copying them.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::ChunkedBuffer;
+    ///
+    /// let mut buf = ChunkedBuffer::new();
+    /// buf.push(vec![1, 2]);
+    /// buf.push(vec![3]);
+    ///
+    /// assert_eq!(buf.remaining(), 3);
+    /// assert_eq!(buf.chunk_count(), 2);
+    /// ```
+    pub fn push(&mut self, chunk: Vec<u8>) {
+        if !chunk.is_empty() {
+            self.len += chunk.len();
+            self.chunks.push_back(Chunk::new(chunk));
+        }
+    }
+
+    /// Moves all chunks of `other` to the back of the buffer without copying
+    /// them, leaving `other` empty.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use rust_utils::ChunkedBuffer;
+    ///
+    /// let mut buf = ChunkedBuffer::new();
+    /// let mut other = ChunkedBuffer::new();
+    /// buf.write_all(b"abc").unwrap();
+    /// other.write_all(b"def").unwrap();
+    ///
+    /// buf.append(&mut other);
+    /// assert_eq!(buf.remaining(), 6);
+    /// assert!(other.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut Self) {
+        self.chunks.append(&mut other.chunks);
+        self.len += mem::replace(&mut other.len, 0);
+    }
+
+    /// Splits the buffer into two at the given byte offset. Returns a new
+    /// buffer holding the first `at` unread bytes, while `self` keeps the rest.
+    ///
+    /// Only a chunk which is split in its middle is copied partially, all other
+    /// chunks are moved without copying.
+    ///
+    /// This operation should compute in O(n) time, where `n` is the number of
+    /// chunks split off.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > remaining`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::BufRead;
+    /// use rust_utils::ChunkedBuffer;
+    ///
+    /// let mut buf = ChunkedBuffer::new();
+    /// buf.push(b"abc".to_vec());
+    /// buf.push(b"def".to_vec());
+    ///
+    /// let mut head = buf.split_to(4);
+    /// assert_eq!(head.fill_buf().unwrap(), b"abc");
+    /// assert_eq!(head.remaining(), 4);
+    /// assert_eq!(buf.fill_buf().unwrap(), b"ef");
+    /// ```
+    pub fn split_to(&mut self, at: usize) -> Self {
+        assert!(at <= self.len, "Cannot split off at a nonexistent index");
+        if at == 0 {
+            return ChunkedBuffer::new();
+        }
+        // move to the chunk containing the last byte to split off and split
+        // the list right behind it
+        let mut offset = at;
+        let mut cursor = self.chunks.cursor_front_mut();
+        while let Some(chunk) = cursor.current() {
+            let available = chunk.bytes.len() - chunk.start;
+            if offset <= available {
+                break;
+            }
+            offset -= available;
+            cursor.move_next();
+        }
+        let mut rest = cursor.split_after();
+        if let Some(last) = cursor.current() {
+            let end = last.start + offset;
+            if end < last.bytes.len() {
+                rest.push_front(Chunk::new(last.bytes.split_off(end)));
+            }
+        }
+        let head = ChunkedBuffer {
+            chunks: mem::replace(&mut self.chunks, rest),
+            len: at,
+        };
+        self.len -= at;
+        head
+    }
+
+    /// Provides an iterator over the unread bytes as `IoSlice`s, one for every
+    /// chunk, which can be passed to `Write::write_vectored`. The bytes are not
+    /// consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{BufRead, IoSlice, Write};
+    /// use rust_utils::ChunkedBuffer;
+    ///
+    /// let mut buf = ChunkedBuffer::new();
+    /// buf.push(b"abc".to_vec());
+    /// buf.push(b"def".to_vec());
+    ///
+    /// let mut out = Vec::new();
+    /// let slices: Vec<IoSlice> = buf.io_slices().collect();
+    /// let written = out.write_vectored(&slices).unwrap();
+    /// buf.consume(written);
+    ///
+    /// assert_eq!(out, b"abcdef");
+    /// assert!(buf.is_empty());
+    /// ```
+    #[inline]
+    pub fn io_slices(&self) -> IoSlices<'_> {
+        IoSlices {
+            chunks: self.chunks.iter(),
+        }
+    }
+}
+
+impl Read for ChunkedBuffer {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut read = 0;
+        while read < buf.len() && !self.is_empty() {
+            let amt = {
+                let src = self.fill_buf()?;
+                let amt = cmp::min(src.len(), buf.len() - read);
+                buf[read..read + amt].copy_from_slice(&src[..amt]);
+                amt
+            };
+            self.consume(amt);
+            read += amt;
+        }
+        Ok(read)
+    }
+}
+
+impl BufRead for ChunkedBuffer {
+    /// Returns the unread bytes of the first chunk.
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.chunks.front().map_or(&[], Chunk::unread))
+    }
+
+    /// Marks the given number of bytes as read. Unlike required by `BufRead`,
+    /// `amt` may exceed the bytes returned by `fill_buf` and then consumes the
+    /// following chunks as well, e.g. after a vectored write. It is limited to
+    /// the number of remaining bytes.
+    fn consume(&mut self, amt: usize) {
+        let mut left = cmp::min(amt, self.len);
+        self.len -= left;
+        while left > 0 {
+            let front = match self.chunks.front_mut() {
+                Some(front) => front,
+                None => return,
+            };
+            let available = front.bytes.len() - front.start;
+            if left < available {
+                front.start += left;
+                return;
+            }
+            left -= available;
+            self.chunks.pop_front();
+        }
+    }
+}
+
+impl Write for ChunkedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut rest = buf;
+        if let Some(last) = self.chunks.back_mut() {
+            let fits = cmp::min(last.bytes.capacity() - last.bytes.len(), buf.len());
+            last.bytes.extend_from_slice(&buf[..fits]);
+            rest = &buf[fits..];
+        }
+        if !rest.is_empty() {
+            let mut chunk = Vec::with_capacity(cmp::max(CHUNK_SIZE, rest.len()));
+            chunk.extend_from_slice(rest);
+            self.chunks.push_back(Chunk::new(chunk));
+        }
+        self.len += buf.len();
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl From<Vec<u8>> for ChunkedBuffer {
+    fn from(chunk: Vec<u8>) -> Self {
+        let mut buf = ChunkedBuffer::new();
+        buf.push(chunk);
+        buf
+    }
+}
+
+impl Extend<Vec<u8>> for ChunkedBuffer {
+    fn extend<I: IntoIterator<Item = Vec<u8>>>(&mut self, iter: I) {
+        for chunk in iter {
+            self.push(chunk);
+        }
+    }
+}
+
+impl fmt::Debug for ChunkedBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ChunkedBuffer")
+            .field("remaining", &self.len)
+            .field("chunks", &self.chunks.len())
+            .finish()
+    }
+}
+
+impl<'a> Iterator for IoSlices<'a> {
+    type Item = IoSlice<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<IoSlice<'a>> {
+        self.chunks.next().map(|chunk| IoSlice::new(chunk.unread()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for IoSlices<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<IoSlice<'a>> {
+        self.chunks
+            .next_back()
+            .map(|chunk| IoSlice::new(chunk.unread()))
+    }
+}
+
+impl<'a> ExactSizeIterator for IoSlices<'a> {}
+
+impl<'a> FusedIterator for IoSlices<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChunkedBuffer, CHUNK_SIZE};
+    use rand::{thread_rng, RngCore};
+    use std::cmp;
+    use std::collections::VecDeque;
+    use std::io::{BufRead, IoSlice, Read, Write};
+    use std::vec::Vec;
+
+    fn contents(buf: &ChunkedBuffer) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for slice in buf.io_slices() {
+            bytes.extend_from_slice(&slice);
+        }
+        assert_eq!(bytes.len(), buf.remaining());
+        assert!(buf.chunks.iter().all(|chunk| !chunk.unread().is_empty()));
+        bytes
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let mut buf = ChunkedBuffer::new();
+        buf.write_all(b"first line\nsecond").unwrap();
+        buf.write_all(&[b'!'; CHUNK_SIZE]).unwrap();
+        assert_eq!(buf.chunk_count(), 2);
+        assert_eq!(buf.remaining(), 17 + CHUNK_SIZE);
+
+        let mut line = String::new();
+        buf.read_line(&mut line).unwrap();
+        assert_eq!(line, "first line\n");
+        let mut word = [0; 8];
+        buf.read_exact(&mut word).unwrap();
+        assert_eq!(&word, b"second!!");
+        assert_eq!(buf.remaining(), CHUNK_SIZE - 2);
+
+        let mut rest = Vec::new();
+        assert_eq!(buf.read_to_end(&mut rest).unwrap(), CHUNK_SIZE - 2);
+        assert!(buf.is_empty());
+        assert_eq!(buf.chunk_count(), 0);
+        assert_eq!(buf.read(&mut word).unwrap(), 0);
+        assert_eq!(buf.fill_buf().unwrap(), b"");
+    }
+
+    #[test]
+    fn test_append() {
+        let mut buf = ChunkedBuffer::from(b"abc".to_vec());
+        let mut other: ChunkedBuffer = ChunkedBuffer::new();
+        other.extend(vec![b"def".to_vec(), Vec::new(), b"ghi".to_vec()]);
+        let first = other.chunks.front().unwrap().bytes.as_ptr();
+        let last = other.chunks.back().unwrap().bytes.as_ptr();
+        other.consume(1);
+
+        buf.append(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(other.chunk_count(), 0);
+        assert_eq!(contents(&buf), b"abcefghi");
+        // no chunk has been copied, not even the partially read one
+        let mut chunks = buf.chunks.iter().map(|chunk| chunk.bytes.as_ptr());
+        assert_eq!(chunks.nth(1), Some(first));
+        assert_eq!(chunks.next(), Some(last));
+
+        buf.consume(4);
+        other.append(&mut buf);
+        assert_eq!(contents(&other), b"fghi");
+        assert_eq!(other.chunk_count(), 2);
+    }
+
+    #[test]
+    fn test_split_to() {
+        let mut buf = ChunkedBuffer::new();
+        buf.extend(vec![b"abc".to_vec(), b"def".to_vec(), b"ghi".to_vec()]);
+        buf.consume(1);
+
+        assert!(buf.split_to(0).is_empty());
+        let head = buf.split_to(2);
+        assert_eq!(contents(&head), b"bc");
+        assert_eq!(head.chunk_count(), 1);
+        assert_eq!(contents(&buf), b"defghi");
+
+        let middle = buf.split_to(4);
+        assert_eq!(contents(&middle), b"defg");
+        assert_eq!(contents(&buf), b"hi");
+        assert_eq!(buf.chunk_count(), 1);
+
+        let tail = buf.split_to(2);
+        assert_eq!(contents(&tail), b"hi");
+        assert!(buf.is_empty());
+        assert_eq!(buf.chunk_count(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_to_out_of_bounds() {
+        let mut buf = ChunkedBuffer::from(b"abc".to_vec());
+        buf.consume(1);
+        buf.split_to(3);
+    }
+
+    #[test]
+    fn test_io_slices() {
+        let mut buf = ChunkedBuffer::new();
+        buf.extend(vec![b"abc".to_vec(), b"de".to_vec(), b"f".to_vec()]);
+        buf.consume(1);
+
+        let slices: Vec<IoSlice> = buf.io_slices().rev().collect();
+        let lens: Vec<usize> = slices.iter().map(|slice| slice.len()).collect();
+        assert_eq!(lens, &[1, 2, 2]);
+        let mut iter = buf.io_slices();
+        assert_eq!(iter.len(), 3);
+        iter.next();
+        assert_eq!(&*iter.next_back().unwrap(), b"f");
+        assert_eq!(&*iter.next_back().unwrap(), b"de");
+        assert!(iter.next().is_none());
+
+        buf.consume(3);
+        assert_eq!(buf.fill_buf().unwrap(), b"e");
+        buf.consume(10);
+        assert!(buf.is_empty());
+        assert_eq!(buf.io_slices().count(), 0);
+    }
+
+    #[test]
+    fn test_fuzz() {
+        let rounds = if cfg!(miri) { 2 } else { 25 };
+        for _ in 0..rounds {
+            fuzz_test(3);
+            fuzz_test(16);
+            fuzz_test(189);
+        }
+    }
+
+    fn fuzz_test(sz: usize) {
+        let mut buf = ChunkedBuffer::new();
+        let mut v: VecDeque<u8> = VecDeque::new();
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            let n = rng.next_u32() as usize % sz;
+            let bytes: Vec<u8> = (0..n).map(|_| rng.next_u32() as u8).collect();
+            match rng.next_u32() % 6 {
+                0 => {
+                    let mut read = vec![0; n];
+                    let amt = buf.read(&mut read).unwrap();
+                    let expected: Vec<u8> = v.drain(..amt).collect();
+                    assert_eq!(&read[..amt], &expected[..]);
+                    assert!(amt == n || v.is_empty());
+                }
+                1 => {
+                    let at = cmp::min(n, v.len());
+                    let mut head = buf.split_to(at);
+                    let expected: Vec<u8> = v.drain(..at).collect();
+                    assert_eq!(contents(&head), expected);
+                    if rng.next_u32().is_multiple_of(2) {
+                        head.append(&mut buf);
+                        buf = head;
+                        v = expected.into_iter().chain(v).collect();
+                    }
+                }
+                2 => {
+                    buf.consume(n);
+                    let at = cmp::min(n, v.len());
+                    v.drain(..at);
+                }
+                3 => {
+                    let mut other = ChunkedBuffer::from(bytes.clone());
+                    buf.append(&mut other);
+                    v.extend(bytes);
+                }
+                _ => {
+                    buf.write_all(&bytes).unwrap();
+                    v.extend(bytes);
+                }
+            }
+            assert_eq!(contents(&buf), v.iter().cloned().collect::<Vec<u8>>());
+        }
+    }
+}
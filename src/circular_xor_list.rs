@@ -0,0 +1,1306 @@
+//! A circular doubly-linked list with owned nodes
+//!
+//! The `CircularXorList` allows pushing and popping elements at either end
+//! in constant time. The nodes form a ring: the tail links back to the head
+//! through the XOR reference, so the logical start of the list can be rotated
+//! in constant time without touching any node.
+//!
+//! Almost always it is better to use `Vec` or [`VecDeque`] instead of
+//! [`LinkedList`]. In general, array-based containers are faster,
+//! more memory efficient and make better use of CPU cache.
+//!
+//! [`LinkedList`]: ../linked_list/struct.LinkedList.html
+//! [`VecDeque`]: ../vec_deque/struct.VecDeque.html
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::{FromIterator, FusedIterator},
+    marker::PhantomData,
+    ptr::{self, NonNull},
+};
+
+/// A circular doubly-linked list with owned nodes
+///
+/// The `CircularXorList` allows pushing and popping elements at either end
+/// in constant time. As the tail is linked to the head, moving elements from
+/// one end to the other is a constant time rotation of the logical start,
+/// which makes the list a good fit for round-robin scheduling.
+///
+/// Almost always it is better to use `Vec` or `VecDeque` instead of
+/// `CircularXorList`. In general, array-based containers are faster,
+/// more memory efficient and make better use of CPU cache.
+pub struct CircularXorList<T> {
+    head: Option<NonNull<Node<T>>>,
+    tail: Option<NonNull<Node<T>>>,
+    len: usize,
+    marker: PhantomData<Box<Node<T>>>,
+}
+
+// The reference of the head includes the tail and vice versa. A single node
+// is its own neighbour on both sides.
+struct Node<T> {
+    reference: usize,
+    data: T,
+}
+
+/// An iterator over the elements of a `CircularXorList`.
+///
+/// This `struct` is created by the [`iter`] method on [`CircularXorList`]. See its
+/// documentation for more.
+///
+/// [`iter`]: struct.CircularXorList.html#method.iter
+/// [`CircularXorList`]: struct.CircularXorList.html
+pub struct Iter<'a, T: 'a> {
+    head: Option<NonNull<Node<T>>>,
+    tail: Option<NonNull<Node<T>>>,
+    last_head: Option<NonNull<Node<T>>>,
+    last_tail: Option<NonNull<Node<T>>>,
+    len: usize,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            last_head: self.last_head,
+            last_tail: self.last_tail,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: 'a + fmt::Debug> fmt::Debug for Iter<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Iter").field(&self.len).finish()
+    }
+}
+
+/// A mutable iterator over the elements of a `CircularXorList`.
+///
+/// This `struct` is created by the [`iter_mut`] method on [`CircularXorList`]. See its
+/// documentation for more.
+///
+/// [`iter_mut`]: struct.CircularXorList.html#method.iter_mut
+/// [`CircularXorList`]: struct.CircularXorList.html
+pub struct IterMut<'a, T: 'a> {
+    head: Option<NonNull<Node<T>>>,
+    tail: Option<NonNull<Node<T>>>,
+    last_head: Option<NonNull<Node<T>>>,
+    last_tail: Option<NonNull<Node<T>>>,
+    len: usize,
+    marker: PhantomData<&'a mut Node<T>>,
+}
+
+impl<'a, T: 'a + fmt::Debug> fmt::Debug for IterMut<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IterMut").field(&self.len).finish()
+    }
+}
+
+/// An owning iterator over the elements of a `CircularXorList`.
+///
+/// This `struct` is created by the [`into_iter`] method on
+/// [`CircularXorList`][`CircularXorList`] (provided by the `IntoIterator` trait).
+/// See its documentation for more.
+///
+/// [`into_iter`]: struct.CircularXorList.html#method.into_iter
+/// [`CircularXorList`]: struct.CircularXorList.html
+pub struct IntoIter<T> {
+    list: CircularXorList<T>,
+}
+
+impl<T: Clone> Clone for IntoIter<T> {
+    fn clone(&self) -> Self {
+        IntoIter {
+            list: self.list.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for IntoIter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.list).finish()
+    }
+}
+
+/// An endless iterator over the elements of a `CircularXorList`.
+///
+/// After the back element, the iterator continues with the front element
+/// again. It only returns `None` if the list is empty.
+///
+/// This `struct` is created by the [`cycle_iter`] method on [`CircularXorList`].
+/// See its documentation for more.
+///
+/// [`cycle_iter`]: struct.CircularXorList.html#method.cycle_iter
+/// [`CircularXorList`]: struct.CircularXorList.html
+pub struct CycleIter<'a, T: 'a> {
+    current: Option<NonNull<Node<T>>>,
+    prev: Option<NonNull<Node<T>>>,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+impl<'a, T> Clone for CycleIter<'a, T> {
+    fn clone(&self) -> Self {
+        CycleIter {
+            current: self.current,
+            prev: self.prev,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: 'a + fmt::Debug> fmt::Debug for CycleIter<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("CycleIter")
+            .field(&self.current.map(|node| unsafe { &node.as_ref().data }))
+            .finish()
+    }
+}
+
+impl<T> Node<T> {
+    fn new(data: T) -> Node<T> {
+        Node { reference: 0, data }
+    }
+
+    /// Combines the addresses of both neighbours into a node reference. The
+    /// provenance of both nodes is exposed, so that `get_element` is allowed to
+    /// recreate pointers to them later on.
+    #[inline]
+    fn calculate_reference(previous: NonNull<Node<T>>, next: NonNull<Node<T>>) -> usize {
+        previous.as_ptr().expose_provenance() ^ next.as_ptr().expose_provenance()
+    }
+
+    /// Recreates the pointer to the other neighbour of a node from its reference
+    /// using the provenance exposed by `calculate_reference`.
+    #[inline]
+    fn get_element(previous_or_next: NonNull<Node<T>>, reference: usize) -> NonNull<Node<T>> {
+        let other = previous_or_next.as_ptr().addr();
+        NonNull::new(ptr::with_exposed_provenance_mut(other ^ reference))
+            .expect("nodes of a ring always have neighbours")
+    }
+}
+
+// private methods
+impl<T> CircularXorList<T> {
+    /// Links the given node in between the two adjacent nodes `prev` and `next`,
+    /// which are the same node if the ring consists of a single node.
+    #[inline]
+    unsafe fn link_node(
+        mut prev: NonNull<Node<T>>,
+        mut next: NonNull<Node<T>>,
+        mut node: NonNull<Node<T>>,
+    ) {
+        node.as_mut().reference = Node::calculate_reference(prev, next);
+        prev.as_mut().reference ^= Node::calculate_reference(next, node);
+        next.as_mut().reference ^= Node::calculate_reference(prev, node);
+    }
+
+    /// Unlinks the given node which resides in between `prev` and `next` and
+    /// connects both neighbours with each other. The ring must consist of at
+    /// least two nodes.
+    #[inline]
+    unsafe fn unlink_node(
+        mut prev: NonNull<Node<T>>,
+        node: NonNull<Node<T>>,
+        mut next: NonNull<Node<T>>,
+    ) {
+        prev.as_mut().reference ^= Node::calculate_reference(node, next);
+        next.as_mut().reference ^= Node::calculate_reference(node, prev);
+    }
+
+    /// Links a new node holding the given element in between the tail and the
+    /// head and returns it.
+    fn push_node(&mut self, data: T) -> NonNull<Node<T>> {
+        let node = NonNull::from(Box::leak(Box::new(Node::new(data))));
+        match (self.tail, self.head) {
+            (Some(tail), Some(head)) => unsafe { Self::link_node(tail, head, node) },
+            _ => unsafe {
+                // A single node is its own neighbour on both sides. The reference is
+                // zero either way, but the provenance of the node has to be exposed
+                // as well, as `get_element` recreates the node from its address.
+                (*node.as_ptr()).reference = Node::calculate_reference(node, node);
+                self.head = Some(node);
+                self.tail = Some(node);
+            },
+        }
+        self.len += 1;
+        node
+    }
+
+    /// Unlinks the given node, which is either the head or the tail, and moves
+    /// its element out. `prev` and `next` are the neighbours of the node.
+    unsafe fn pop_node(
+        &mut self,
+        prev: NonNull<Node<T>>,
+        node: NonNull<Node<T>>,
+        next: NonNull<Node<T>>,
+    ) -> T {
+        if self.len == 1 {
+            self.head = None;
+            self.tail = None;
+        } else {
+            Self::unlink_node(prev, node, next);
+            if self.head == Some(node) {
+                self.head = Some(next);
+            } else {
+                self.tail = Some(prev);
+            }
+        }
+        self.len -= 1;
+        Box::from_raw(node.as_ptr()).data
+    }
+}
+
+impl<T> Default for CircularXorList<T> {
+    /// Creates an empty `CircularXorList<T>`
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> CircularXorList<T> {
+    /// Creates an empty `CircularXorList`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let list: CircularXorList<u32> = CircularXorList::new();
+    /// ```
+    #[inline]
+    pub fn new() -> Self {
+        CircularXorList {
+            head: None,
+            tail: None,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Provides a forward iterator, which visits every element once
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let mut list: CircularXorList<u32> = CircularXorList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(1);
+    /// list.push_back(2);
+    ///
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&0));
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next(), Some(&2));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            last_head: self.tail,
+            last_tail: self.head,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Provides a forward iterator with mutable references, which visits every
+    /// element once
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let mut list: CircularXorList<u32> = CircularXorList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(1);
+    /// list.push_back(2);
+    ///
+    /// for element in list.iter_mut() {
+    ///     *element += 10;
+    /// }
+    ///
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&10));
+    /// assert_eq!(iter.next(), Some(&11));
+    /// assert_eq!(iter.next(), Some(&12));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            head: self.head,
+            tail: self.tail,
+            last_head: self.tail,
+            last_tail: self.head,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Provides an endless iterator, which starts at the front element and
+    /// continues with the front element again after the back element
+    ///
+    /// The iterator only returns `None` if the list is empty. It follows the
+    /// links of the ring and never allocates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let list: CircularXorList<u32> = (0..3).collect();
+    ///
+    /// let cycled: Vec<u32> = list.cycle_iter().take(7).cloned().collect();
+    /// assert_eq!(cycled, vec![0, 1, 2, 0, 1, 2, 0]);
+    /// ```
+    #[inline]
+    pub fn cycle_iter(&self) -> CycleIter<'_, T> {
+        CycleIter {
+            current: self.head,
+            prev: self.tail,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns `true` if the `CircularXorList` is empty
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let mut dl = CircularXorList::new();
+    /// assert!(dl.is_empty());
+    ///
+    /// dl.push_front("foo");
+    /// assert!(!dl.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// Returns the length of the `CircularXorList`
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let mut dl = CircularXorList::new();
+    ///
+    /// dl.push_front(2);
+    /// assert_eq!(dl.len(), 1);
+    ///
+    /// dl.push_front(1);
+    /// assert_eq!(dl.len(), 2);
+    ///
+    /// dl.push_back(3);
+    /// assert_eq!(dl.len(), 3);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Removes all elements from the `CircularXorList`
+    ///
+    /// This operation should compute in O(n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let mut dl = CircularXorList::new();
+    ///
+    /// dl.push_front(2);
+    /// dl.push_front(1);
+    /// assert_eq!(dl.len(), 2);
+    /// assert_eq!(dl.front(), Some(&1));
+    ///
+    /// dl.clear();
+    /// assert_eq!(dl.len(), 0);
+    /// assert_eq!(dl.front(), None);
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Returns `true` if the `CircularXorList` contains an element equal to the
+    /// given value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let list: CircularXorList<u32> = (0..3).collect();
+    ///
+    /// assert_eq!(list.contains(&0), true);
+    /// assert_eq!(list.contains(&10), false);
+    /// ```
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq<T>,
+    {
+        self.iter().any(|e| e == x)
+    }
+
+    /// Provides a reference to the front element, or `None` if the list is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let mut dl = CircularXorList::new();
+    /// assert_eq!(dl.front(), None);
+    ///
+    /// dl.push_front(1);
+    /// assert_eq!(dl.front(), Some(&1));
+    /// ```
+    #[inline]
+    pub fn front(&self) -> Option<&T> {
+        unsafe { self.head.as_ref().map(|node| &node.as_ref().data) }
+    }
+
+    /// Provides a mutable reference to the front element, or `None` if the list
+    /// is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let mut dl = CircularXorList::new();
+    /// assert_eq!(dl.front(), None);
+    ///
+    /// dl.push_front(1);
+    /// assert_eq!(dl.front(), Some(&1));
+    ///
+    /// match dl.front_mut() {
+    ///     None => {},
+    ///     Some(x) => *x = 5,
+    /// }
+    /// assert_eq!(dl.front(), Some(&5));
+    /// ```
+    #[inline]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.head.as_mut().map(|node| &mut node.as_mut().data) }
+    }
+
+    /// Provides a reference to the back element, or `None` if the list is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let mut dl = CircularXorList::new();
+    /// assert_eq!(dl.back(), None);
+    ///
+    /// dl.push_back(1);
+    /// assert_eq!(dl.back(), Some(&1));
+    /// ```
+    #[inline]
+    pub fn back(&self) -> Option<&T> {
+        unsafe { self.tail.as_ref().map(|node| &node.as_ref().data) }
+    }
+
+    /// Provides a mutable reference to the back element, or `None` if the list
+    /// is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let mut dl = CircularXorList::new();
+    /// assert_eq!(dl.back(), None);
+    ///
+    /// dl.push_back(1);
+    /// assert_eq!(dl.back(), Some(&1));
+    ///
+    /// match dl.back_mut() {
+    ///     None => {},
+    ///     Some(x) => *x = 5,
+    /// }
+    /// assert_eq!(dl.back(), Some(&5));
+    /// ```
+    #[inline]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.tail.as_mut().map(|node| &mut node.as_mut().data) }
+    }
+
+    /// Adds an element first in the list.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let mut dl = CircularXorList::new();
+    ///
+    /// dl.push_front(2);
+    /// assert_eq!(dl.front().unwrap(), &2);
+    ///
+    /// dl.push_front(1);
+    /// assert_eq!(dl.front().unwrap(), &1);
+    /// ```
+    pub fn push_front(&mut self, data: T) {
+        let node = self.push_node(data);
+        self.head = Some(node);
+    }
+
+    /// Removes the first element and returns it, or `None` if the list is
+    /// empty.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let mut d = CircularXorList::new();
+    /// assert_eq!(d.pop_front(), None);
+    ///
+    /// d.push_front(1);
+    /// d.push_front(3);
+    /// assert_eq!(d.pop_front(), Some(3));
+    /// assert_eq!(d.pop_front(), Some(1));
+    /// assert_eq!(d.pop_front(), None);
+    /// ```
+    pub fn pop_front(&mut self) -> Option<T> {
+        match (self.tail, self.head) {
+            (Some(tail), Some(head)) => unsafe {
+                let next = Node::get_element(tail, head.as_ref().reference);
+                Some(self.pop_node(tail, head, next))
+            },
+            _ => None,
+        }
+    }
+
+    /// Appends an element to the back of a list
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let mut d = CircularXorList::new();
+    /// d.push_back(1);
+    /// d.push_back(3);
+    /// assert_eq!(3, *d.back().unwrap());
+    /// ```
+    pub fn push_back(&mut self, data: T) {
+        let node = self.push_node(data);
+        self.tail = Some(node);
+    }
+
+    /// Removes the last element from a list and returns it, or `None` if
+    /// it is empty.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let mut d = CircularXorList::new();
+    /// assert_eq!(d.pop_back(), None);
+    /// d.push_back(1);
+    /// d.push_back(3);
+    /// assert_eq!(d.pop_back(), Some(3));
+    /// ```
+    pub fn pop_back(&mut self) -> Option<T> {
+        match (self.tail, self.head) {
+            (Some(tail), Some(head)) => unsafe {
+                let prev = Node::get_element(head, tail.as_ref().reference);
+                Some(self.pop_node(prev, tail, head))
+            },
+            _ => None,
+        }
+    }
+
+    /// Moves the logical start of the list one element forward, so that the
+    /// front element becomes the back element
+    ///
+    /// This is equivalent to `pop_front` followed by `push_back`, but no node
+    /// is touched.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let mut list: CircularXorList<u32> = (0..3).collect();
+    ///
+    /// list.rotate_forward();
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![1, 2, 0]);
+    /// ```
+    #[inline]
+    pub fn rotate_forward(&mut self) {
+        if let (Some(tail), Some(head)) = (self.tail, self.head) {
+            self.head = Some(Node::get_element(tail, unsafe { head.as_ref().reference }));
+            self.tail = Some(head);
+        }
+    }
+
+    /// Moves the logical start of the list one element backward, so that the
+    /// back element becomes the front element
+    ///
+    /// This is equivalent to `pop_back` followed by `push_front`, but no node
+    /// is touched.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let mut list: CircularXorList<u32> = (0..3).collect();
+    ///
+    /// list.rotate_backward();
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![2, 0, 1]);
+    /// ```
+    #[inline]
+    pub fn rotate_backward(&mut self) {
+        if let (Some(tail), Some(head)) = (self.tail, self.head) {
+            self.tail = Some(Node::get_element(head, unsafe { tail.as_ref().reference }));
+            self.head = Some(tail);
+        }
+    }
+
+    /// Rotates the list `n` places to the left, so that the element at index
+    /// `n % len` becomes the front element
+    ///
+    /// The logical start is moved in whichever direction requires fewer steps.
+    ///
+    /// This operation should compute in O(min(n % len, len - n % len)) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let mut list: CircularXorList<u32> = (0..5).collect();
+    ///
+    /// list.rotate_left(2);
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![2, 3, 4, 0, 1]);
+    ///
+    /// list.rotate_left(9);
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![1, 2, 3, 4, 0]);
+    /// ```
+    pub fn rotate_left(&mut self, n: usize) {
+        if self.len == 0 {
+            return;
+        }
+        let steps = n % self.len;
+        if steps <= self.len - steps {
+            for _ in 0..steps {
+                self.rotate_forward();
+            }
+        } else {
+            for _ in steps..self.len {
+                self.rotate_backward();
+            }
+        }
+    }
+
+    /// Rotates the list `n` places to the right, so that the element at index
+    /// `len - n % len` becomes the front element
+    ///
+    /// The logical start is moved in whichever direction requires fewer steps.
+    ///
+    /// This operation should compute in O(min(n % len, len - n % len)) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::CircularXorList;
+    ///
+    /// let mut list: CircularXorList<u32> = (0..5).collect();
+    ///
+    /// list.rotate_right(2);
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![3, 4, 0, 1, 2]);
+    /// ```
+    pub fn rotate_right(&mut self, n: usize) {
+        if self.len != 0 {
+            let steps = n % self.len;
+            self.rotate_left(self.len - steps);
+        }
+    }
+}
+
+impl<T> Drop for CircularXorList<T> {
+    fn drop(&mut self) {
+        if let (Some(tail), Some(head)) = (self.tail, self.head) {
+            let mut prev = tail;
+            let mut current = head;
+            for _ in 0..self.len {
+                unsafe {
+                    let next = Node::get_element(prev, current.as_ref().reference);
+                    prev = current;
+                    current = next;
+                    drop(Box::from_raw(prev.as_ptr()));
+                }
+            }
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+        match (self.last_head, self.head) {
+            (Some(last_head), Some(node)) => unsafe {
+                self.len -= 1;
+                self.last_head = self.head;
+                self.head = Some(Node::get_element(last_head, node.as_ref().reference));
+                Some(&(*node.as_ptr()).data)
+            },
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+        match (self.last_tail, self.tail) {
+            (Some(last_tail), Some(node)) => unsafe {
+                self.len -= 1;
+                self.last_tail = self.tail;
+                self.tail = Some(Node::get_element(last_tail, node.as_ref().reference));
+                Some(&(*node.as_ptr()).data)
+            },
+            _ => None,
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> Default for Iter<'a, T> {
+    /// Creates an empty `circular_xor_list::Iter`
+    #[inline]
+    fn default() -> Self {
+        Iter {
+            head: None,
+            tail: None,
+            last_head: None,
+            last_tail: None,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            return None;
+        }
+        match (self.last_head, self.head) {
+            (Some(last_head), Some(node)) => unsafe {
+                self.len -= 1;
+                self.last_head = self.head;
+                self.head = Some(Node::get_element(last_head, node.as_ref().reference));
+                Some(&mut (*node.as_ptr()).data)
+            },
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            return None;
+        }
+        match (self.last_tail, self.tail) {
+            (Some(last_tail), Some(node)) => unsafe {
+                self.len -= 1;
+                self.last_tail = self.tail;
+                self.tail = Some(Node::get_element(last_tail, node.as_ref().reference));
+                Some(&mut (*node.as_ptr()).data)
+            },
+            _ => None,
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<'a, T> Default for IterMut<'a, T> {
+    /// Creates an empty `circular_xor_list::IterMut`
+    #[inline]
+    fn default() -> Self {
+        IterMut {
+            head: None,
+            tail: None,
+            last_head: None,
+            last_tail: None,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> FusedIterator for IterMut<'a, T> {}
+
+impl<'a, T> Iterator for CycleIter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        match (self.prev, self.current) {
+            (Some(prev), Some(node)) => unsafe {
+                self.prev = self.current;
+                self.current = Some(Node::get_element(prev, node.as_ref().reference));
+                Some(&(*node.as_ptr()).data)
+            },
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.current.is_some() {
+            (usize::MAX, None)
+        } else {
+            (0, Some(0))
+        }
+    }
+}
+
+impl<'a, T> Default for CycleIter<'a, T> {
+    /// Creates an empty `circular_xor_list::CycleIter`
+    #[inline]
+    fn default() -> Self {
+        CycleIter {
+            current: None,
+            prev: None,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> FusedIterator for CycleIter<'a, T> {}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> Default for IntoIter<T> {
+    /// Creates an empty `circular_xor_list::IntoIter`
+    #[inline]
+    fn default() -> Self {
+        CircularXorList::new().into_iter()
+    }
+}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> FromIterator<T> for CircularXorList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> IntoIterator for CircularXorList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Consumes the list into an iterator yielding elements by value
+    #[inline]
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a CircularXorList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut CircularXorList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> Extend<T> for CircularXorList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elt in iter {
+            self.push_back(elt);
+        }
+    }
+}
+
+impl<'a, T: 'a + Copy> Extend<&'a T> for CircularXorList<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+impl<T: PartialEq> PartialEq for CircularXorList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other)
+    }
+}
+
+impl<T: Eq> Eq for CircularXorList<T> {}
+
+impl<T: PartialOrd> PartialOrd for CircularXorList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other)
+    }
+}
+
+impl<T: Ord> Ord for CircularXorList<T> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other)
+    }
+}
+
+impl<T: Clone> Clone for CircularXorList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CircularXorList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: Hash> Hash for CircularXorList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for elt in self {
+            elt.hash(state);
+        }
+    }
+}
+
+// Ensure that `CircularXorList` and its read-only iterators are covariant in their type parameters
+#[allow(dead_code)]
+fn assert_covariance() {
+    fn a<'a>(x: CircularXorList<&'static str>) -> CircularXorList<&'a str> {
+        x
+    }
+    fn b<'i, 'a>(x: Iter<'i, &'static str>) -> Iter<'i, &'a str> {
+        x
+    }
+    fn c<'a>(x: IntoIter<&'static str>) -> IntoIter<&'a str> {
+        x
+    }
+    fn d<'i, 'a>(x: CycleIter<'i, &'static str>) -> CycleIter<'i, &'a str> {
+        x
+    }
+}
+
+unsafe impl<T: Send> Send for CircularXorList<T> {}
+
+unsafe impl<T: Sync> Sync for CircularXorList<T> {}
+
+unsafe impl<'a, T: Sync> Send for Iter<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
+
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
+
+unsafe impl<'a, T: Sync> Send for CycleIter<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for CycleIter<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{CircularXorList, Node};
+    use rand::{thread_rng, RngCore};
+    use std::cell::Cell;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    struct DropCounter(Rc<Cell<usize>>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    fn check_links<T>(list: &CircularXorList<T>) {
+        let (tail, head) = match (list.tail, list.head) {
+            (None, None) => {
+                assert_eq!(0, list.len);
+                return;
+            }
+            (Some(tail), Some(head)) => (tail, head),
+            _ => panic!("tail and head must both be set or unset"),
+        };
+
+        // walking once around the ring in either direction ends where it started
+        let mut prev = tail;
+        let mut current = head;
+        for _ in 0..list.len {
+            let next = Node::get_element(prev, unsafe { current.as_ref().reference });
+            prev = current;
+            current = next;
+        }
+        assert_eq!((prev, current), (tail, head));
+
+        let mut next = head;
+        current = tail;
+        for _ in 0..list.len {
+            let prev_node = Node::get_element(next, unsafe { current.as_ref().reference });
+            next = current;
+            current = prev_node;
+        }
+        assert_eq!((next, current), (head, tail));
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let mut m = CircularXorList::new();
+        check_links(&m);
+        m.push_back(1);
+        check_links(&m);
+        m.push_front(0);
+        check_links(&m);
+        m.push_back(2);
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(m.iter().rev().cloned().collect::<Vec<_>>(), vec![2, 1, 0]);
+        assert_eq!(m.pop_back(), Some(2));
+        check_links(&m);
+        assert_eq!(m.pop_front(), Some(0));
+        check_links(&m);
+        assert_eq!(m.front(), m.back());
+        assert_eq!(m.pop_front(), Some(1));
+        check_links(&m);
+        assert_eq!(m.pop_back(), None);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut m: CircularXorList<_> = (0..5).collect();
+        m.rotate_forward();
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), vec![1, 2, 3, 4, 0]);
+        m.rotate_backward();
+        m.rotate_backward();
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), vec![4, 0, 1, 2, 3]);
+        m.rotate_left(4);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), vec![3, 4, 0, 1, 2]);
+        m.rotate_right(13);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+        m.rotate_right(0);
+        m.rotate_left(5);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+
+        // rotated lists keep working for pushes and pops on both ends
+        m.rotate_left(2);
+        m.push_back(5);
+        m.push_front(6);
+        check_links(&m);
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            vec![6, 2, 3, 4, 0, 1, 5]
+        );
+
+        let mut single: CircularXorList<_> = (0..1).collect();
+        single.rotate_forward();
+        single.rotate_right(3);
+        check_links(&single);
+        assert_eq!(single.front(), Some(&0));
+
+        let mut empty = CircularXorList::<u32>::new();
+        empty.rotate_forward();
+        empty.rotate_backward();
+        empty.rotate_left(3);
+        empty.rotate_right(3);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_cycle_iter() {
+        let m: CircularXorList<_> = (0..3).collect();
+        let mut cycle = m.cycle_iter();
+        assert_eq!(cycle.size_hint(), (usize::MAX, None));
+        assert_eq!(
+            cycle.by_ref().take(8).cloned().collect::<Vec<_>>(),
+            vec![0, 1, 2, 0, 1, 2, 0, 1]
+        );
+        assert_eq!(cycle.next(), Some(&2));
+
+        let empty = CircularXorList::<u32>::new();
+        assert_eq!(empty.cycle_iter().next(), None);
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut m: CircularXorList<_> = (1..5).collect();
+        for elt in m.iter_mut().rev().take(2) {
+            *elt *= 10;
+        }
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[1, 2, 30, 40]);
+    }
+
+    #[test]
+    fn test_drops() {
+        let drops = Rc::new(Cell::new(0));
+        {
+            let mut m = CircularXorList::new();
+            for _ in 0..5 {
+                m.push_back(DropCounter(Rc::clone(&drops)));
+            }
+            m.rotate_left(3);
+            m.pop_front();
+            assert_eq!(drops.get(), 1);
+        }
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn test_fuzz() {
+        let rounds = if cfg!(miri) { 2 } else { 25 };
+        for _ in 0..rounds {
+            fuzz_test(3);
+            fuzz_test(16);
+            fuzz_test(189);
+        }
+    }
+
+    fn fuzz_test(sz: i32) {
+        let mut m: CircularXorList<_> = CircularXorList::new();
+        let mut v = VecDeque::new();
+        for i in 0..sz {
+            check_links(&m);
+            let r = thread_rng().next_u32() as usize;
+            match r % 8 {
+                0 => {
+                    assert_eq!(m.pop_back(), v.pop_back());
+                }
+                1 => {
+                    assert_eq!(m.pop_front(), v.pop_front());
+                }
+                2 => {
+                    if !v.is_empty() {
+                        let n = (r >> 8) % (2 * v.len());
+                        m.rotate_left(n);
+                        v.rotate_left(n % v.len());
+                    }
+                }
+                3 => {
+                    if !v.is_empty() {
+                        let n = (r >> 8) % (2 * v.len());
+                        m.rotate_right(n);
+                        v.rotate_right(n % v.len());
+                    }
+                }
+                4 | 5 => {
+                    m.push_front(-i);
+                    v.push_front(-i);
+                }
+                _ => {
+                    m.push_back(i);
+                    v.push_back(i);
+                }
+            }
+        }
+
+        check_links(&m);
+        assert!(m.iter().eq(v.iter()));
+        assert!(m.iter().rev().eq(v.iter().rev()));
+        assert_eq!(m.into_iter().collect::<VecDeque<_>>(), v);
+    }
+}
@@ -0,0 +1,1327 @@
+//! A doubly-linked list with generational handles to its elements
+//!
+//! The `HandleList` allows pushing and popping elements at either end
+//! in constant time. Every insertion returns a [`Handle`] to the element,
+//! which can later be used to access, remove or insert next to it in constant
+//! time as well. Like in [`ArenaXorLinkedList`], all nodes live in a single
+//! `Vec` and refer to each other by their slot indices.
+//!
+//! A handle has to be usable without knowing any neighbour of its node, which
+//! a XOR linked list cannot provide (see [removing nodes from the middle]).
+//! Instead of storing the XOR of both neighbours next to one of them, which
+//! takes the same space, each node stores the slots of both neighbours.
+//! Slots carry a generation which is bumped whenever their element is removed,
+//! so handles to removed elements are detected instead of referring to
+//! whatever element reuses the slot.
+//!
+//! Almost always it is better to use `Vec` or [`VecDeque`] instead of
+//! [`LinkedList`]. In general, array-based containers are faster,
+//! more memory efficient and make better use of CPU cache.
+//!
+//! [`Handle`]: struct.Handle.html
+//! [`ArenaXorLinkedList`]: ../arena_xor_linked_list/struct.ArenaXorLinkedList.html
+//! [removing nodes from the middle]: ../xor_linked_list/index.html#removing-nodes-from-the-middle
+//! [`LinkedList`]: ../linked_list/struct.LinkedList.html
+//! [`VecDeque`]: ../vec_deque/struct.VecDeque.html
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::{FromIterator, FusedIterator},
+    marker::PhantomData,
+    mem,
+};
+
+/// A doubly-linked list with generational handles to its elements
+///
+/// The `HandleList` allows pushing and popping elements at either end
+/// in constant time. Elements may also be accessed, removed or used as an
+/// insertion point in constant time through the [`Handle`] returned when they
+/// were inserted.
+///
+/// Handles of removed elements are detected and rejected. Handles are not tied
+/// to their list though, using a handle with a different list than the one it
+/// was created by returns an unspecified element or `None`.
+///
+/// Almost always it is better to use `Vec` or `VecDeque` instead of
+/// `HandleList`. In general, array-based containers are faster,
+/// more memory efficient and make better use of CPU cache.
+///
+/// [`Handle`]: struct.Handle.html
+pub struct HandleList<T> {
+    nodes: Vec<Node<T>>,
+    head: usize,
+    tail: usize,
+    free: usize,
+    len: usize,
+}
+
+/// A handle to an element of a `HandleList`
+///
+/// A handle is created by inserting an element and stays valid until the element
+/// is removed from the list. It consists of the slot of the element and the
+/// generation of that slot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+    link: usize,
+    generation: usize,
+}
+
+// Links are slot indices shifted by one, which leaves `0` to denote no node.
+// Occupied nodes store the links of their neighbours, vacant nodes store the
+// link of the next vacant node in `next`. The generation of a slot is bumped
+// whenever it is vacated.
+#[derive(Clone)]
+struct Node<T> {
+    prev: usize,
+    next: usize,
+    generation: usize,
+    data: Option<T>,
+}
+
+/// An iterator over the elements of a `HandleList`.
+///
+/// This `struct` is created by the [`iter`] method on [`HandleList`]. See its
+/// documentation for more.
+///
+/// [`iter`]: struct.HandleList.html#method.iter
+/// [`HandleList`]: struct.HandleList.html
+pub struct Iter<'a, T: 'a> {
+    nodes: &'a [Node<T>],
+    head: usize,
+    tail: usize,
+    len: usize,
+}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter {
+            nodes: self.nodes,
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+        }
+    }
+}
+
+impl<'a, T: 'a + fmt::Debug> fmt::Debug for Iter<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Iter").field(&self.len).finish()
+    }
+}
+
+/// A mutable iterator over the elements of a `HandleList`.
+///
+/// This `struct` is created by the [`iter_mut`] method on [`HandleList`]. See its
+/// documentation for more.
+///
+/// [`iter_mut`]: struct.HandleList.html#method.iter_mut
+/// [`HandleList`]: struct.HandleList.html
+pub struct IterMut<'a, T: 'a> {
+    nodes: *mut Node<T>,
+    head: usize,
+    tail: usize,
+    len: usize,
+    marker: PhantomData<&'a mut [Node<T>]>,
+}
+
+impl<'a, T: 'a + fmt::Debug> fmt::Debug for IterMut<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IterMut").field(&self.len).finish()
+    }
+}
+
+/// An owning iterator over the elements of a `HandleList`.
+///
+/// This `struct` is created by the [`into_iter`] method on
+/// [`HandleList`][`HandleList`] (provided by the `IntoIterator` trait).
+/// See its documentation for more.
+///
+/// [`into_iter`]: struct.HandleList.html#method.into_iter
+/// [`HandleList`]: struct.HandleList.html
+pub struct IntoIter<T> {
+    list: HandleList<T>,
+}
+
+impl<T: fmt::Debug> fmt::Debug for IntoIter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.list).finish()
+    }
+}
+
+impl<T> Node<T> {
+    #[inline]
+    fn data(&self) -> &T {
+        self.data.as_ref().expect("linked node must be occupied")
+    }
+
+    #[inline]
+    fn data_mut(&mut self) -> &mut T {
+        self.data.as_mut().expect("linked node must be occupied")
+    }
+}
+
+// private methods
+impl<T> HandleList<T> {
+    #[inline]
+    fn node(&self, link: usize) -> &Node<T> {
+        &self.nodes[link - 1]
+    }
+
+    #[inline]
+    fn node_mut(&mut self, link: usize) -> &mut Node<T> {
+        &mut self.nodes[link - 1]
+    }
+
+    /// Returns the link of the node the handle refers to, or `None` if the
+    /// element of the handle has been removed.
+    #[inline]
+    fn resolve(&self, handle: Handle) -> Option<usize> {
+        match self.nodes.get(handle.link.wrapping_sub(1)) {
+            Some(node) if node.generation == handle.generation && node.data.is_some() => {
+                Some(handle.link)
+            }
+            _ => None,
+        }
+    }
+
+    /// Stores the given element in a vacant slot, growing the arena if there is
+    /// none, and returns its link.
+    fn allocate(&mut self, data: T) -> usize {
+        match self.free {
+            0 => {
+                self.nodes.push(Node {
+                    prev: 0,
+                    next: 0,
+                    generation: 0,
+                    data: Some(data),
+                });
+                self.nodes.len()
+            }
+            link => {
+                let free = mem::replace(&mut self.node_mut(link).next, 0);
+                self.free = free;
+                self.node_mut(link).data = Some(data);
+                link
+            }
+        }
+    }
+
+    /// Takes the element out of the given slot, invalidates all handles to it
+    /// and adds the slot to the free list.
+    fn release(&mut self, link: usize) -> T {
+        let free = mem::replace(&mut self.free, link);
+        let node = self.node_mut(link);
+        node.prev = 0;
+        node.next = free;
+        node.generation = node.generation.wrapping_add(1);
+        node.data.take().expect("linked node must be occupied")
+    }
+
+    /// Stores the given element and links it between the adjacent nodes `prev`
+    /// and `next`.
+    fn link_between(&mut self, prev: usize, next: usize, data: T) -> Handle {
+        let link = self.allocate(data);
+        {
+            let node = self.node_mut(link);
+            node.prev = prev;
+            node.next = next;
+        }
+        match prev {
+            0 => self.head = link,
+            _ => self.node_mut(prev).next = link,
+        }
+        match next {
+            0 => self.tail = link,
+            _ => self.node_mut(next).prev = link,
+        }
+        self.len += 1;
+        Handle {
+            link,
+            generation: self.node(link).generation,
+        }
+    }
+
+    /// Unlinks the given linked node and returns its element.
+    fn unlink(&mut self, link: usize) -> T {
+        let (prev, next) = {
+            let node = self.node(link);
+            (node.prev, node.next)
+        };
+        match prev {
+            0 => self.head = next,
+            _ => self.node_mut(prev).next = next,
+        }
+        match next {
+            0 => self.tail = prev,
+            _ => self.node_mut(next).prev = prev,
+        }
+        self.len -= 1;
+        self.release(link)
+    }
+
+    /// Returns a handle to the given linked node.
+    #[inline]
+    fn handle(&self, link: usize) -> Handle {
+        Handle {
+            link,
+            generation: self.node(link).generation,
+        }
+    }
+}
+
+impl<T> Default for HandleList<T> {
+    /// Creates an empty `HandleList<T>`
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> HandleList<T> {
+    /// Creates an empty `HandleList`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let list: HandleList<u32> = HandleList::new();
+    /// ```
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates an empty `HandleList` with space for at least `capacity`
+    /// elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let list: HandleList<u32> = HandleList::with_capacity(10);
+    /// assert!(list.capacity() >= 10);
+    /// ```
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        HandleList {
+            nodes: Vec::with_capacity(capacity),
+            head: 0,
+            tail: 0,
+            free: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements the list can hold without reallocating
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut list = HandleList::with_capacity(10);
+    /// list.push_back(1);
+    /// assert!(list.capacity() >= 10);
+    /// ```
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.nodes.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` more elements to be inserted
+    /// without reallocating. Vacant slots of removed elements are taken into
+    /// account.
+    ///
+    /// Vacant slots are never released, as they keep the generation needed to
+    /// detect handles of removed elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut list = HandleList::new();
+    /// list.push_back(1);
+    /// list.reserve(10);
+    /// assert!(list.capacity() >= 11);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        let vacant = self.nodes.len() - self.len;
+        if additional > vacant {
+            self.nodes.reserve(additional - vacant);
+        }
+    }
+
+    /// Provides a forward iterator
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut list: HandleList<u32> = HandleList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(1);
+    /// list.push_back(2);
+    ///
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&0));
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next(), Some(&2));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            nodes: &self.nodes,
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+        }
+    }
+
+    /// Provides a forward iterator with mutable references
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut list: HandleList<u32> = HandleList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(1);
+    /// list.push_back(2);
+    ///
+    /// for element in list.iter_mut() {
+    ///     *element += 10;
+    /// }
+    ///
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&10));
+    /// assert_eq!(iter.next(), Some(&11));
+    /// assert_eq!(iter.next(), Some(&12));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            nodes: self.nodes.as_mut_ptr(),
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns `true` if the `HandleList` is empty
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut dl = HandleList::new();
+    /// assert!(dl.is_empty());
+    ///
+    /// dl.push_front("foo");
+    /// assert!(!dl.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.head == 0
+    }
+
+    /// Returns the length of the `HandleList`
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut dl = HandleList::new();
+    ///
+    /// dl.push_front(2);
+    /// assert_eq!(dl.len(), 1);
+    ///
+    /// dl.push_front(1);
+    /// assert_eq!(dl.len(), 2);
+    ///
+    /// dl.push_back(3);
+    /// assert_eq!(dl.len(), 3);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Removes all elements from the `HandleList`, keeping the allocated
+    /// capacity. All handles to the removed elements become invalid.
+    ///
+    /// This operation should compute in O(n) time
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut dl = HandleList::new();
+    ///
+    /// let handle = dl.push_front(2);
+    /// dl.push_front(1);
+    /// assert_eq!(dl.len(), 2);
+    /// assert_eq!(dl.front(), Some(&1));
+    ///
+    /// dl.clear();
+    /// assert_eq!(dl.len(), 0);
+    /// assert_eq!(dl.front(), None);
+    /// assert_eq!(dl.get(handle), None);
+    /// ```
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    /// Returns `true` if the `HandleList` contains an element equal to the
+    /// given value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut list: HandleList<u32> = HandleList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(1);
+    /// list.push_back(2);
+    ///
+    /// assert_eq!(list.contains(&0), true);
+    /// assert_eq!(list.contains(&10), false);
+    /// ```
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq<T>,
+    {
+        self.iter().any(|e| e == x)
+    }
+
+    /// Provides a reference to the front element, or `None` if the list is
+    /// empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut dl = HandleList::new();
+    /// assert_eq!(dl.front(), None);
+    ///
+    /// dl.push_front(1);
+    /// assert_eq!(dl.front(), Some(&1));
+    /// ```
+    #[inline]
+    pub fn front(&self) -> Option<&T> {
+        match self.head {
+            0 => None,
+            head => Some(self.node(head).data()),
+        }
+    }
+
+    /// Provides a mutable reference to the front element, or `None` if the list
+    /// is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut dl = HandleList::new();
+    /// assert_eq!(dl.front(), None);
+    ///
+    /// dl.push_front(1);
+    /// assert_eq!(dl.front(), Some(&1));
+    ///
+    /// match dl.front_mut() {
+    ///     None => {},
+    ///     Some(x) => *x = 5,
+    /// }
+    /// assert_eq!(dl.front(), Some(&5));
+    /// ```
+    #[inline]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        match self.head {
+            0 => None,
+            head => Some(self.node_mut(head).data_mut()),
+        }
+    }
+
+    /// Provides a reference to the back element, or `None` if the list is
+    /// empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut dl = HandleList::new();
+    /// assert_eq!(dl.back(), None);
+    ///
+    /// dl.push_back(1);
+    /// assert_eq!(dl.back(), Some(&1));
+    /// ```
+    #[inline]
+    pub fn back(&self) -> Option<&T> {
+        match self.tail {
+            0 => None,
+            tail => Some(self.node(tail).data()),
+        }
+    }
+
+    /// Provides a mutable reference to the back element, or `None` if the list
+    /// is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut dl = HandleList::new();
+    /// assert_eq!(dl.back(), None);
+    ///
+    /// dl.push_back(1);
+    /// assert_eq!(dl.back(), Some(&1));
+    ///
+    /// match dl.back_mut() {
+    ///     None => {},
+    ///     Some(x) => *x = 5,
+    /// }
+    /// assert_eq!(dl.back(), Some(&5));
+    /// ```
+    #[inline]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        match self.tail {
+            0 => None,
+            tail => Some(self.node_mut(tail).data_mut()),
+        }
+    }
+
+    /// Returns a handle to the front element, or `None` if the list is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut dl = HandleList::new();
+    /// assert_eq!(dl.front_handle(), None);
+    ///
+    /// let handle = dl.push_front(1);
+    /// assert_eq!(dl.front_handle(), Some(handle));
+    /// ```
+    #[inline]
+    pub fn front_handle(&self) -> Option<Handle> {
+        match self.head {
+            0 => None,
+            head => Some(self.handle(head)),
+        }
+    }
+
+    /// Returns a handle to the back element, or `None` if the list is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut dl = HandleList::new();
+    /// assert_eq!(dl.back_handle(), None);
+    ///
+    /// let handle = dl.push_back(1);
+    /// assert_eq!(dl.back_handle(), Some(handle));
+    /// ```
+    #[inline]
+    pub fn back_handle(&self) -> Option<Handle> {
+        match self.tail {
+            0 => None,
+            tail => Some(self.handle(tail)),
+        }
+    }
+
+    /// Adds an element first in the list and returns a handle to it.
+    ///
+    /// This operation should compute in amortized O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut dl = HandleList::new();
+    ///
+    /// let handle = dl.push_front(2);
+    /// assert_eq!(dl.front().unwrap(), &2);
+    ///
+    /// dl.push_front(1);
+    /// assert_eq!(dl.front().unwrap(), &1);
+    /// assert_eq!(dl.get(handle), Some(&2));
+    /// ```
+    pub fn push_front(&mut self, data: T) -> Handle {
+        let head = self.head;
+        self.link_between(0, head, data)
+    }
+
+    /// Removes the first element and returns it, or `None` if the list is
+    /// empty.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut d = HandleList::new();
+    /// assert_eq!(d.pop_front(), None);
+    ///
+    /// d.push_front(1);
+    /// d.push_front(3);
+    /// assert_eq!(d.pop_front(), Some(3));
+    /// assert_eq!(d.pop_front(), Some(1));
+    /// assert_eq!(d.pop_front(), None);
+    /// ```
+    pub fn pop_front(&mut self) -> Option<T> {
+        match self.head {
+            0 => None,
+            head => Some(self.unlink(head)),
+        }
+    }
+
+    /// Appends an element to the back of a list and returns a handle to it.
+    ///
+    /// This operation should compute in amortized O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut d = HandleList::new();
+    /// let handle = d.push_back(1);
+    /// d.push_back(3);
+    /// assert_eq!(3, *d.back().unwrap());
+    /// assert_eq!(d.get(handle), Some(&1));
+    /// ```
+    pub fn push_back(&mut self, data: T) -> Handle {
+        let tail = self.tail;
+        self.link_between(tail, 0, data)
+    }
+
+    /// Removes the last element from a list and returns it, or `None` if
+    /// it is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut d = HandleList::new();
+    /// assert_eq!(d.pop_back(), None);
+    /// d.push_back(1);
+    /// d.push_back(3);
+    /// assert_eq!(d.pop_back(), Some(3));
+    /// ```
+    pub fn pop_back(&mut self) -> Option<T> {
+        match self.tail {
+            0 => None,
+            tail => Some(self.unlink(tail)),
+        }
+    }
+
+    /// Provides a reference to the element of the given handle, or `None` if
+    /// the element has been removed
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut dl = HandleList::new();
+    /// let handle = dl.push_back(1);
+    /// assert_eq!(dl.get(handle), Some(&1));
+    ///
+    /// dl.pop_back();
+    /// assert_eq!(dl.get(handle), None);
+    /// ```
+    #[inline]
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.resolve(handle).map(|link| self.node(link).data())
+    }
+
+    /// Provides a mutable reference to the element of the given handle, or
+    /// `None` if the element has been removed
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut dl = HandleList::new();
+    /// let handle = dl.push_back(1);
+    ///
+    /// if let Some(x) = dl.get_mut(handle) {
+    ///     *x = 5;
+    /// }
+    /// assert_eq!(dl.front(), Some(&5));
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        match self.resolve(handle) {
+            Some(link) => Some(self.node_mut(link).data_mut()),
+            None => None,
+        }
+    }
+
+    /// Removes the element of the given handle and returns it, or `None` if the
+    /// element has already been removed
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut dl = HandleList::new();
+    /// dl.push_back(1);
+    /// let handle = dl.push_back(2);
+    /// dl.push_back(3);
+    ///
+    /// assert_eq!(dl.remove(handle), Some(2));
+    /// assert_eq!(dl.remove(handle), None);
+    /// assert_eq!(dl.iter().cloned().collect::<Vec<_>>(), vec![1, 3]);
+    /// ```
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        self.resolve(handle).map(|link| self.unlink(link))
+    }
+
+    /// Inserts an element directly after the element of the given handle and
+    /// returns a handle to it.
+    ///
+    /// If the element of the handle has been removed, the given element is
+    /// returned as an error instead.
+    ///
+    /// This operation should compute in amortized O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut dl = HandleList::new();
+    /// let handle = dl.push_back(1);
+    /// dl.push_back(3);
+    ///
+    /// let inserted = dl.insert_after(handle, 2).unwrap();
+    /// assert_eq!(dl.get(inserted), Some(&2));
+    /// assert_eq!(dl.iter().cloned().collect::<Vec<_>>(), vec![1, 2, 3]);
+    ///
+    /// dl.remove(handle);
+    /// assert_eq!(dl.insert_after(handle, 4), Err(4));
+    /// ```
+    pub fn insert_after(&mut self, handle: Handle, data: T) -> Result<Handle, T> {
+        match self.resolve(handle) {
+            Some(link) => {
+                let next = self.node(link).next;
+                Ok(self.link_between(link, next, data))
+            }
+            None => Err(data),
+        }
+    }
+
+    /// Inserts an element directly before the element of the given handle and
+    /// returns a handle to it.
+    ///
+    /// If the element of the handle has been removed, the given element is
+    /// returned as an error instead.
+    ///
+    /// This operation should compute in amortized O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::HandleList;
+    ///
+    /// let mut dl = HandleList::new();
+    /// dl.push_back(1);
+    /// let handle = dl.push_back(3);
+    ///
+    /// let inserted = dl.insert_before(handle, 2).unwrap();
+    /// assert_eq!(dl.get(inserted), Some(&2));
+    /// assert_eq!(dl.iter().cloned().collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// ```
+    pub fn insert_before(&mut self, handle: Handle, data: T) -> Result<Handle, T> {
+        match self.resolve(handle) {
+            Some(link) => {
+                let prev = self.node(link).prev;
+                Ok(self.link_between(prev, link, data))
+            }
+            None => Err(data),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            None
+        } else {
+            let node = &self.nodes[self.head - 1];
+            self.head = node.next;
+            self.len -= 1;
+            Some(node.data())
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            None
+        } else {
+            let node = &self.nodes[self.tail - 1];
+            self.tail = node.prev;
+            self.len -= 1;
+            Some(node.data())
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            None
+        } else {
+            // Every linked slot is yielded at most once, so the returned
+            // references never alias
+            let node = unsafe { &mut *self.nodes.add(self.head - 1) };
+            self.head = node.next;
+            self.len -= 1;
+            Some(node.data_mut())
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            None
+        } else {
+            let node = unsafe { &mut *self.nodes.add(self.tail - 1) };
+            self.tail = node.prev;
+            self.len -= 1;
+            Some(node.data_mut())
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<'a, T> FusedIterator for IterMut<'a, T> {}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> FromIterator<T> for HandleList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> IntoIterator for HandleList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Consumes the list into an iterator yielding elements by value
+    #[inline]
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a HandleList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut HandleList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> Extend<T> for HandleList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let elements = iter.into_iter();
+        self.reserve(elements.size_hint().0);
+        for elt in elements {
+            self.push_back(elt);
+        }
+    }
+}
+
+impl<'a, T: 'a + Copy> Extend<&'a T> for HandleList<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+impl<T: PartialEq> PartialEq for HandleList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other)
+    }
+}
+
+impl<T: Eq> Eq for HandleList<T> {}
+
+impl<T: PartialOrd> PartialOrd for HandleList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other)
+    }
+}
+
+impl<T: Ord> Ord for HandleList<T> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other)
+    }
+}
+
+impl<T: Clone> Clone for HandleList<T> {
+    /// Clones the list slot by slot, so handles of `self` refer to the same
+    /// elements in the clone
+    fn clone(&self) -> Self {
+        HandleList {
+            nodes: self.nodes.clone(),
+            head: self.head,
+            tail: self.tail,
+            free: self.free,
+            len: self.len,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for HandleList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: Hash> Hash for HandleList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for elt in self {
+            elt.hash(state);
+        }
+    }
+}
+
+// Ensure that `HandleList` and its read-only iterators are covariant in their type parameters
+#[allow(dead_code)]
+fn assert_covariance() {
+    fn a<'a>(x: HandleList<&'static str>) -> HandleList<&'a str> {
+        x
+    }
+    fn b<'i, 'a>(x: Iter<'i, &'static str>) -> Iter<'i, &'a str> {
+        x
+    }
+    fn c<'a>(x: IntoIter<&'static str>) -> IntoIter<&'a str> {
+        x
+    }
+}
+
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Handle, HandleList};
+    use rand::{thread_rng, RngCore};
+    use std::vec::Vec;
+
+    fn list_from<T: Clone>(v: &[T]) -> HandleList<T> {
+        v.iter().cloned().collect()
+    }
+
+    fn check_links<T>(list: &HandleList<T>) {
+        match (list.head, list.tail) {
+            (0, 0) => {
+                assert_eq!(0, list.len);
+                return;
+            }
+            (0, _) | (_, 0) => panic!("tail and head must both be zero or non-zero"),
+            _ => {}
+        }
+
+        let mut last = 0;
+        let mut link = list.head;
+        for _ in 0..(list.len - 1) {
+            assert_eq!(list.node(link).prev, last, "prev link is out of sync");
+            let next = list.node(link).next;
+            assert_ne!(next, 0, "next link is null, not good");
+            last = link;
+            link = next;
+        }
+        assert_eq!(list.node(link).prev, last, "prev link is out of sync");
+        assert_eq!(list.node(link).next, 0, "next link of the tail is not null");
+        assert_eq!(link, list.tail);
+
+        // every slot is either linked or part of the free list
+        let mut vacant = 0;
+        let mut free = list.free;
+        while free != 0 {
+            assert!(list.node(free).data.is_none());
+            vacant += 1;
+            free = list.node(free).next;
+        }
+        assert_eq!(list.nodes.len(), list.len + vacant);
+    }
+
+    #[test]
+    fn test_handles() {
+        let mut m = HandleList::new();
+        let one = m.push_back(1);
+        let two = m.push_back(2);
+        let zero = m.push_front(0);
+        check_links(&m);
+        assert_eq!(m.get(zero), Some(&0));
+        assert_eq!(m.get(one), Some(&1));
+        assert_eq!(m.get(two), Some(&2));
+        assert_eq!(m.front_handle(), Some(zero));
+        assert_eq!(m.back_handle(), Some(two));
+
+        *m.get_mut(one).unwrap() = 10;
+        assert_eq!(m.remove(one), Some(10));
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[0, 2]);
+
+        // the handle stays stale after its slot is reused
+        let three = m.push_back(3);
+        assert_eq!(three.link, one.link);
+        assert_eq!(m.get(one), None);
+        assert_eq!(m.get_mut(one), None);
+        assert_eq!(m.remove(one), None);
+        assert_eq!(m.insert_after(one, 4), Err(4));
+        assert_eq!(m.insert_before(one, 4), Err(4));
+        assert_eq!(m.get(three), Some(&3));
+        check_links(&m);
+
+        // handles of a different list are never out of bounds
+        let foreign = HandleList::<i32>::new().front_handle();
+        assert_eq!(foreign, None);
+        let unknown = Handle {
+            link: 100,
+            generation: 0,
+        };
+        assert_eq!(m.get(unknown), None);
+        assert_eq!(m.remove(unknown), None);
+    }
+
+    #[test]
+    fn test_remove_ends() {
+        let mut m = HandleList::new();
+        let handles: Vec<Handle> = (0..4).map(|i| m.push_back(i)).collect();
+        assert_eq!(m.remove(handles[0]), Some(0));
+        check_links(&m);
+        assert_eq!(m.remove(handles[3]), Some(3));
+        check_links(&m);
+        assert_eq!(m.front(), Some(&1));
+        assert_eq!(m.back(), Some(&2));
+        assert_eq!(m.remove(handles[1]), Some(1));
+        assert_eq!(m.remove(handles[2]), Some(2));
+        check_links(&m);
+        assert!(m.is_empty());
+        assert_eq!(m.front_handle(), None);
+    }
+
+    #[test]
+    fn test_insert_next_to() {
+        let mut m = HandleList::new();
+        let first = m.push_back(1);
+        let last = m.insert_after(first, 5).unwrap();
+        check_links(&m);
+        let middle = m.insert_before(last, 3).unwrap();
+        m.insert_after(middle, 4).unwrap();
+        m.insert_before(middle, 2).unwrap();
+        m.insert_before(first, 0).unwrap();
+        m.insert_after(last, 6).unwrap();
+        check_links(&m);
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            &[0, 1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(
+            m.iter().rev().cloned().collect::<Vec<_>>(),
+            &[6, 5, 4, 3, 2, 1, 0]
+        );
+    }
+
+    #[test]
+    fn test_clear_and_clone() {
+        let mut m = HandleList::new();
+        let one = m.push_back(1);
+        let two = m.push_back(2);
+
+        let mut n = m.clone();
+        check_links(&n);
+        assert_eq!(n.get(two), Some(&2));
+        assert_eq!(n.remove(one), Some(1));
+        assert_eq!(m.get(one), Some(&1));
+
+        m.clear();
+        check_links(&m);
+        assert!(m.is_empty());
+        assert_eq!(m.get(one), None);
+        assert_eq!(m.get(two), None);
+        m.push_back(3);
+        m.push_back(4);
+        assert_eq!(m.get(one), None);
+        assert_eq!(m.get(two), None);
+        assert_eq!(m.nodes.len(), 2);
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut m = list_from(&[1, 2, 3, 4]);
+        for elt in m.iter_mut().rev().take(2) {
+            *elt *= 10;
+        }
+        assert_eq!(m.iter().rev().cloned().collect::<Vec<_>>(), &[40, 30, 2, 1]);
+    }
+
+    #[test]
+    fn test_fuzz() {
+        let rounds = if cfg!(miri) { 2 } else { 25 };
+        for _ in 0..rounds {
+            fuzz_test(3);
+            fuzz_test(16);
+            fuzz_test(189);
+        }
+    }
+
+    fn fuzz_test(sz: i32) {
+        let mut m: HandleList<_> = HandleList::new();
+        let mut v: Vec<(Handle, i32)> = vec![];
+        let mut removed = vec![];
+        for i in 0..sz {
+            check_links(&m);
+            let r = thread_rng().next_u32() as usize;
+            match r % 8 {
+                0 => {
+                    if let Some(entry) = v.pop() {
+                        assert_eq!(m.pop_back(), Some(entry.1));
+                        removed.push(entry.0);
+                    }
+                }
+                1 | 2 => {
+                    if !v.is_empty() {
+                        let entry = v.remove(r / 8 % v.len());
+                        assert_eq!(m.remove(entry.0), Some(entry.1));
+                        removed.push(entry.0);
+                    }
+                }
+                3 => {
+                    if !v.is_empty() {
+                        let at = r / 8 % v.len();
+                        let handle = m.insert_after(v[at].0, i).ok().unwrap();
+                        v.insert(at + 1, (handle, i));
+                    }
+                }
+                4 => {
+                    let handle = m.push_front(-i);
+                    v.insert(0, (handle, -i));
+                }
+                _ => {
+                    let handle = m.push_back(i);
+                    v.push((handle, i));
+                }
+            }
+        }
+
+        check_links(&m);
+        for entry in &v {
+            assert_eq!(m.get(entry.0), Some(&entry.1));
+        }
+        for handle in removed {
+            assert_eq!(m.get(handle), None);
+        }
+        let values: Vec<_> = v.iter().map(|entry| entry.1).collect();
+        assert_eq!(m.into_iter().collect::<Vec<_>>(), values);
+    }
+}
@@ -0,0 +1,3393 @@
+//! An intrusive doubly-linked list
+//!
+//! The `IntrusiveXorList` does not allocate nodes for its elements. Instead,
+//! the XOR link of an element lives in a [`XorLink`] field of the element
+//! itself. An [`Adapter`] tells the list where to find that field and which
+//! kind of pointer, e.g. `Pin<Box<T>>` or `&'a T`, is used to hand the elements
+//! over to the list.
+//!
+//! As every membership has its own link field, an element may be part of
+//! several lists at once, one per `XorLink` field, without any allocation.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::marker::PhantomData;
+//! use std::mem;
+//! use std::pin::Pin;
+//! use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+//!
+//! struct Task {
+//!     id: u32,
+//!     all: XorLink,
+//!     ready: XorLink,
+//! }
+//!
+//! impl Task {
+//!     fn new(id: u32) -> Self {
+//!         Task { id, all: XorLink::new(), ready: XorLink::new() }
+//!     }
+//! }
+//!
+//! struct AllTasks<'a>(PhantomData<&'a Task>);
+//!
+//! unsafe impl<'a> Adapter for AllTasks<'a> {
+//!     type Value = Task;
+//!     type Pointer = &'a Task;
+//!     const LINK_OFFSET: usize = mem::offset_of!(Task, all);
+//! }
+//!
+//! struct ReadyTasks<'a>(PhantomData<&'a Task>);
+//!
+//! unsafe impl<'a> Adapter for ReadyTasks<'a> {
+//!     type Value = Task;
+//!     type Pointer = &'a Task;
+//!     const LINK_OFFSET: usize = mem::offset_of!(Task, ready);
+//! }
+//!
+//! let tasks: Vec<Task> = (0..4).map(Task::new).collect();
+//!
+//! let mut all: IntrusiveXorList<AllTasks> = IntrusiveXorList::new();
+//! let mut ready: IntrusiveXorList<ReadyTasks> = IntrusiveXorList::new();
+//! for task in &tasks {
+//!     all.push_back(task);
+//!     if task.id % 2 == 1 {
+//!         ready.push_front(task);
+//!     }
+//! }
+//!
+//! assert_eq!(all.iter().map(|task| task.id).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+//! assert_eq!(ready.iter().map(|task| task.id).collect::<Vec<_>>(), vec![3, 1]);
+//! ```
+//!
+//! [`XorLink`]: struct.XorLink.html
+//! [`Adapter`]: trait.Adapter.html
+
+use std::{
+    cell::Cell,
+    fmt,
+    iter::FusedIterator,
+    marker::PhantomData,
+    mem,
+    ops::Deref,
+    pin::Pin,
+    ptr::{self, NonNull},
+};
+
+/// The link of an element of an `IntrusiveXorList`
+///
+/// A `XorLink` is embedded as a field into the type of the elements. Every
+/// `XorLink` field allows the element to be part of one list at a time.
+/// Cloning a `XorLink` results in a new, unlinked `XorLink`.
+pub struct XorLink {
+    reference: Cell<usize>,
+    linked: Cell<bool>,
+}
+
+impl XorLink {
+    /// Creates a new, unlinked `XorLink`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::intrusive_xor_list::XorLink;
+    ///
+    /// let link = XorLink::new();
+    /// assert!(!link.is_linked());
+    /// ```
+    #[inline]
+    pub const fn new() -> Self {
+        XorLink {
+            reference: Cell::new(0),
+            linked: Cell::new(false),
+        }
+    }
+
+    /// Returns `true` if the element of this link is currently part of a list
+    #[inline]
+    pub fn is_linked(&self) -> bool {
+        self.linked.get()
+    }
+}
+
+impl Default for XorLink {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for XorLink {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for XorLink {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("XorLink").field(&self.is_linked()).finish()
+    }
+}
+
+/// A pointer type which can hand over the ownership of an element to an
+/// `IntrusiveXorList`
+///
+/// # Safety
+///
+/// `into_raw` must return a pointer which stays valid and whose target is not
+/// moved until it is passed to `from_raw`. `from_raw` must restore the pointer
+/// which was converted by `into_raw`.
+pub unsafe trait LinkedPointer: Deref {
+    /// Converts the pointer into a raw pointer to its target
+    fn into_raw(self) -> *const Self::Target;
+
+    /// Restores a pointer from a raw pointer returned by `into_raw`
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by `into_raw` of the same pointer type and
+    /// must not have been restored before.
+    unsafe fn from_raw(ptr: *const Self::Target) -> Self;
+}
+
+unsafe impl<T> LinkedPointer for &T {
+    #[inline]
+    fn into_raw(self) -> *const T {
+        self
+    }
+
+    #[inline]
+    unsafe fn from_raw(ptr: *const T) -> Self {
+        &*ptr
+    }
+}
+
+unsafe impl<T> LinkedPointer for Pin<Box<T>> {
+    #[inline]
+    fn into_raw(self) -> *const T {
+        // the element is never moved out of the box, which keeps it pinned
+        Box::into_raw(unsafe { Pin::into_inner_unchecked(self) })
+    }
+
+    #[inline]
+    unsafe fn from_raw(ptr: *const T) -> Self {
+        Pin::new_unchecked(Box::from_raw(ptr.cast_mut()))
+    }
+}
+
+/// Describes how the elements of an `IntrusiveXorList` are linked
+///
+/// An adapter names the type of the elements, the pointer used to add them to
+/// the list and the offset of the `XorLink` field used by the list.
+///
+/// # Safety
+///
+/// `LINK_OFFSET` must be the offset of a field of type `XorLink` within `Value`,
+/// as returned by `mem::offset_of!`.
+pub unsafe trait Adapter {
+    /// The type of the elements
+    type Value;
+
+    /// The pointer to the elements, which is owned by the list while they are
+    /// linked
+    type Pointer: LinkedPointer<Target = Self::Value>;
+
+    /// The offset of the `XorLink` field within `Value`
+    const LINK_OFFSET: usize;
+}
+
+type Link<A> = Option<NonNull<<A as Adapter>::Value>>;
+
+/// An intrusive doubly-linked list
+///
+/// The `IntrusiveXorList` allows pushing and popping elements at either end
+/// in constant time. The links of the elements are stored in the elements
+/// themselves, so linking an element never allocates.
+///
+/// Pushing an element which is already part of a list through the same link
+/// panics.
+pub struct IntrusiveXorList<A: Adapter> {
+    head: Link<A>,
+    tail: Link<A>,
+    len: usize,
+    marker: PhantomData<A::Pointer>,
+}
+
+/// An iterator over the elements of an `IntrusiveXorList`.
+///
+/// This `struct` is created by the [`iter`] method on [`IntrusiveXorList`]. See its
+/// documentation for more.
+///
+/// [`iter`]: struct.IntrusiveXorList.html#method.iter
+/// [`IntrusiveXorList`]: struct.IntrusiveXorList.html
+pub struct Iter<'a, A: 'a + Adapter> {
+    head: Link<A>,
+    tail: Link<A>,
+    last_head: Link<A>,
+    last_tail: Link<A>,
+    len: usize,
+    marker: PhantomData<&'a A::Value>,
+}
+
+impl<'a, A: Adapter> Clone for Iter<'a, A> {
+    fn clone(&self) -> Self {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            last_head: self.last_head,
+            last_tail: self.last_tail,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, A: Adapter> fmt::Debug for Iter<'a, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Iter").field(&self.len).finish()
+    }
+}
+
+/// An owning iterator over the elements of an `IntrusiveXorList`.
+///
+/// This `struct` is created by the [`into_iter`] method on
+/// [`IntrusiveXorList`][`IntrusiveXorList`] (provided by the `IntoIterator` trait).
+/// See its documentation for more.
+///
+/// [`into_iter`]: struct.IntrusiveXorList.html#method.into_iter
+/// [`IntrusiveXorList`]: struct.IntrusiveXorList.html
+pub struct IntoIter<A: Adapter> {
+    list: IntrusiveXorList<A>,
+}
+
+impl<A: Adapter> fmt::Debug for IntoIter<A>
+where
+    A::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.list).finish()
+    }
+}
+
+/// A cursor over an `IntrusiveXorList`.
+///
+/// A `Cursor` is like an iterator, except that it can freely seek back-and-forth. As a link
+/// only stores the combination of both of its neighbours, the cursor keeps track of the
+/// previous element in addition to the current one.
+///
+/// Cursors always rest between two elements in the list, and index in a logically circular way.
+/// To accommodate this, there is a "ghost" non-element that yields `None` between the head and
+/// tail of the list.
+///
+/// This `struct` is created by the [`cursor_front`] and [`cursor_back`] methods on
+/// [`IntrusiveXorList`]. See their documentation for more.
+///
+/// [`cursor_front`]: struct.IntrusiveXorList.html#method.cursor_front
+/// [`cursor_back`]: struct.IntrusiveXorList.html#method.cursor_back
+/// [`IntrusiveXorList`]: struct.IntrusiveXorList.html
+pub struct Cursor<'a, A: 'a + Adapter> {
+    index: usize,
+    current: Link<A>,
+    prev: Link<A>,
+    list: &'a IntrusiveXorList<A>,
+}
+
+impl<'a, A: Adapter> Clone for Cursor<'a, A> {
+    fn clone(&self) -> Self {
+        Cursor {
+            index: self.index,
+            current: self.current,
+            prev: self.prev,
+            list: self.list,
+        }
+    }
+}
+
+impl<'a, A: Adapter> fmt::Debug for Cursor<'a, A>
+where
+    A::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Cursor")
+            .field(&self.list)
+            .field(&self.index())
+            .finish()
+    }
+}
+
+/// A cursor over an `IntrusiveXorList` with editing operations.
+///
+/// A `CursorMut` is like an iterator, except that it can freely seek back-and-forth, and can
+/// safely mutate the list during iteration. As a link only stores the combination of both of
+/// its neighbours, the cursor keeps track of the previous element in addition to the current
+/// one.
+///
+/// Cursors always rest between two elements in the list, and index in a logically circular way.
+/// To accommodate this, there is a "ghost" non-element that yields `None` between the head and
+/// tail of the list.
+///
+/// This `struct` is created by the [`cursor_front_mut`] and [`cursor_back_mut`] methods on
+/// [`IntrusiveXorList`]. See their documentation for more.
+///
+/// [`cursor_front_mut`]: struct.IntrusiveXorList.html#method.cursor_front_mut
+/// [`cursor_back_mut`]: struct.IntrusiveXorList.html#method.cursor_back_mut
+/// [`IntrusiveXorList`]: struct.IntrusiveXorList.html
+pub struct CursorMut<'a, A: 'a + Adapter> {
+    index: usize,
+    current: Link<A>,
+    prev: Link<A>,
+    list: &'a mut IntrusiveXorList<A>,
+}
+
+impl<'a, A: Adapter> fmt::Debug for CursorMut<'a, A>
+where
+    A::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("CursorMut")
+            .field(&self.list)
+            .field(&self.index())
+            .finish()
+    }
+}
+
+// private methods
+impl<A: Adapter> IntrusiveXorList<A> {
+    /// Returns the link embedded into the given element.
+    #[inline]
+    unsafe fn link<'b>(node: NonNull<A::Value>) -> &'b XorLink {
+        &*node.as_ptr().byte_add(A::LINK_OFFSET).cast::<XorLink>()
+    }
+
+    /// Combines the addresses of both neighbours into a link reference. The
+    /// provenance of both elements is exposed, so that `get_element` is allowed
+    /// to recreate pointers to them later on.
+    #[inline]
+    fn calculate_reference(previous: Link<A>, next: Link<A>) -> usize {
+        let pr = previous.map_or(0, |i| i.as_ptr().expose_provenance());
+        let ne = next.map_or(0, |i| i.as_ptr().expose_provenance());
+        pr ^ ne
+    }
+
+    /// Recreates the pointer to the other neighbour of an element from its
+    /// reference using the provenance exposed by `calculate_reference`.
+    #[inline]
+    fn get_element(previous_or_next: Link<A>, reference: usize) -> Link<A> {
+        let other = previous_or_next.map_or(0, |i| i.as_ptr().addr());
+        NonNull::new(ptr::with_exposed_provenance_mut(other ^ reference))
+    }
+
+    /// Returns the neighbour of `node` which is not `previous_or_next`.
+    #[inline]
+    unsafe fn neighbour(previous_or_next: Link<A>, node: NonNull<A::Value>) -> Link<A> {
+        Self::get_element(previous_or_next, Self::link(node).reference.get())
+    }
+
+    /// Takes over the ownership of the element behind the given pointer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element is already linked.
+    fn acquire(entry: A::Pointer) -> NonNull<A::Value> {
+        let node = NonNull::from(&*entry);
+        assert!(
+            unsafe { !Self::link(node).is_linked() },
+            "element is already linked into a list"
+        );
+        NonNull::new(entry.into_raw().cast_mut()).expect("pointers are never null")
+    }
+
+    /// Toggles the given combination of neighbour addresses in the reference of
+    /// the link of `node`.
+    #[inline]
+    unsafe fn toggle_reference(node: NonNull<A::Value>, reference: usize) {
+        let link = Self::link(node);
+        link.reference.set(link.reference.get() ^ reference);
+    }
+
+    /// Links the given element in between the two adjacent elements `prev` and
+    /// `next`. `None` denotes the position before the head or after the tail.
+    unsafe fn link_node(&mut self, prev: Link<A>, next: Link<A>, node: NonNull<A::Value>) {
+        let link = Self::link(node);
+        link.reference.set(Self::calculate_reference(prev, next));
+        link.linked.set(true);
+        match prev {
+            Some(prev_node) => {
+                Self::toggle_reference(prev_node, Self::calculate_reference(next, Some(node)))
+            }
+            None => self.head = Some(node),
+        }
+        match next {
+            Some(next_node) => {
+                Self::toggle_reference(next_node, Self::calculate_reference(prev, Some(node)))
+            }
+            None => self.tail = Some(node),
+        }
+        self.len += 1;
+    }
+
+    /// Unlinks the given element which resides in between `prev` and `next`,
+    /// connects both neighbours with each other and returns the pointer to the
+    /// element.
+    unsafe fn unlink_node(
+        &mut self,
+        prev: Link<A>,
+        node: NonNull<A::Value>,
+        next: Link<A>,
+    ) -> A::Pointer {
+        match prev {
+            Some(prev_node) => {
+                Self::toggle_reference(prev_node, Self::calculate_reference(Some(node), next))
+            }
+            None => self.head = next,
+        }
+        match next {
+            Some(next_node) => {
+                Self::toggle_reference(next_node, Self::calculate_reference(Some(node), prev))
+            }
+            None => self.tail = prev,
+        }
+        self.len -= 1;
+        let link = Self::link(node);
+        link.reference.set(0);
+        link.linked.set(false);
+        A::Pointer::from_raw(node.as_ptr())
+    }
+
+    /// Returns the element at the given index together with the element in front of it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at >= len`.
+    fn node_at(&self, at: usize) -> (NonNull<A::Value>, Link<A>) {
+        let len = self.len();
+        assert!(at < len, "Cannot access a nonexistent index");
+
+        // Below, we move towards the `at`th element, either from the start or the end,
+        // depending on which would be faster
+        let cursor = if at <= len - 1 - at {
+            let mut cursor = self.cursor_front();
+            for _ in 0..at {
+                cursor.move_next();
+            }
+            cursor
+        } else {
+            let mut cursor = self.cursor_back();
+            for _ in 0..len - 1 - at {
+                cursor.move_prev();
+            }
+            cursor
+        };
+        (
+            cursor.current.expect("`at` is within the list"),
+            cursor.prev,
+        )
+    }
+
+    /// Moves all elements of `other` in between the two adjacent elements `prev` and
+    /// `next`. `None` denotes the position before the head or after the tail. After
+    /// this operation, `other` becomes empty.
+    unsafe fn splice_between(&mut self, prev: Link<A>, next: Link<A>, other: &mut Self) {
+        let (other_head, other_tail) = match (other.head.take(), other.tail.take()) {
+            (Some(head), Some(tail)) => (head, tail),
+            _ => return,
+        };
+
+        // The ends of `other` have no outer neighbours yet, which also holds if
+        // both ends are the same element
+        Self::toggle_reference(other_head, Self::calculate_reference(prev, None));
+        Self::toggle_reference(other_tail, Self::calculate_reference(next, None));
+        match prev {
+            Some(prev_node) => {
+                Self::toggle_reference(prev_node, Self::calculate_reference(next, Some(other_head)))
+            }
+            None => self.head = Some(other_head),
+        }
+        match next {
+            Some(next_node) => {
+                Self::toggle_reference(next_node, Self::calculate_reference(prev, Some(other_tail)))
+            }
+            None => self.tail = Some(other_tail),
+        }
+        self.len += mem::replace(&mut other.len, 0);
+    }
+
+    /// Splits the list after the given element, which is the `at - 1`th element of
+    /// the list and follows `prev`. Returns everything after the given element.
+    unsafe fn split_after_node(
+        &mut self,
+        node: NonNull<A::Value>,
+        prev: Link<A>,
+        at: usize,
+    ) -> Self {
+        let next = match Self::neighbour(prev, node) {
+            Some(next) => next,
+            None => return Self::new(),
+        };
+        Self::toggle_reference(node, Self::calculate_reference(Some(next), None));
+        Self::toggle_reference(next, Self::calculate_reference(Some(node), None));
+        let second_part = IntrusiveXorList {
+            head: Some(next),
+            tail: self.tail,
+            len: self.len - at,
+            marker: PhantomData,
+        };
+        self.tail = Some(node);
+        self.len = at;
+        second_part
+    }
+}
+
+impl<A: Adapter> Default for IntrusiveXorList<A> {
+    /// Creates an empty `IntrusiveXorList<A>`
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Adapter> IntrusiveXorList<A> {
+    /// Creates an empty `IntrusiveXorList`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::mem;
+    /// use std::pin::Pin;
+    /// use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    ///
+    /// struct Entry {
+    ///     link: XorLink,
+    /// }
+    ///
+    /// struct EntryAdapter;
+    ///
+    /// unsafe impl Adapter for EntryAdapter {
+    ///     type Value = Entry;
+    ///     type Pointer = Pin<Box<Entry>>;
+    ///     const LINK_OFFSET: usize = mem::offset_of!(Entry, link);
+    /// }
+    ///
+    /// let list: IntrusiveXorList<EntryAdapter> = IntrusiveXorList::new();
+    /// ```
+    #[inline]
+    pub fn new() -> Self {
+        IntrusiveXorList {
+            head: None,
+            tail: None,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Moves all elements from `other` to the end of the list.
+    ///
+    /// This reuses all the links from `other` and moves them into `self`. After
+    /// this operation, `other` becomes empty.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::marker::PhantomData;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter<'a>(PhantomData<&'a Task>);
+    /// #
+    /// # unsafe impl<'a> Adapter for TaskAdapter<'a> {
+    /// #     type Value = Task;
+    /// #     type Pointer = &'a Task;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Task {
+    /// #     Task { id, link: XorLink::new() }
+    /// # }
+    /// #
+    /// let tasks: Vec<Task> = (0..4).map(task).collect();
+    ///
+    /// let mut list1: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list1.push_back(&tasks[0]);
+    ///
+    /// let mut list2: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list2.push_back(&tasks[1]);
+    /// list2.push_back(&tasks[2]);
+    ///
+    /// list1.append(&mut list2);
+    ///
+    /// assert_eq!(list1.iter().map(|task| task.id).collect::<Vec<_>>(), vec![0, 1, 2]);
+    /// assert!(list2.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut Self) {
+        match (self.tail, other.head) {
+            (None, _) => mem::swap(self, other),
+            (Some(_), None) => {}
+            (Some(tail), Some(other_head)) => unsafe {
+                Self::toggle_reference(tail, Self::calculate_reference(None, other.head));
+                Self::toggle_reference(other_head, Self::calculate_reference(None, self.tail));
+                self.tail = other.tail.take();
+                self.len += mem::replace(&mut other.len, 0);
+                other.head = None;
+            },
+        }
+    }
+
+    /// Provides a forward iterator
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::marker::PhantomData;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter<'a>(PhantomData<&'a Task>);
+    /// #
+    /// # unsafe impl<'a> Adapter for TaskAdapter<'a> {
+    /// #     type Value = Task;
+    /// #     type Pointer = &'a Task;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Task {
+    /// #     Task { id, link: XorLink::new() }
+    /// # }
+    /// #
+    /// let tasks: Vec<Task> = (0..3).map(task).collect();
+    ///
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.extend(&tasks);
+    ///
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next().map(|task| task.id), Some(0));
+    /// assert_eq!(iter.next_back().map(|task| task.id), Some(2));
+    /// assert_eq!(iter.next().map(|task| task.id), Some(1));
+    /// assert!(iter.next().is_none());
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, A> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            last_head: None,
+            last_tail: None,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Provides a cursor at the front element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    /// list.push_back(task(1));
+    ///
+    /// let mut cursor = list.cursor_front();
+    /// assert_eq!(cursor.current().map(|task| task.id), Some(0));
+    /// cursor.move_next();
+    /// assert_eq!(cursor.current().map(|task| task.id), Some(1));
+    /// cursor.move_next();
+    /// assert!(cursor.current().is_none());
+    /// ```
+    #[inline]
+    pub fn cursor_front(&self) -> Cursor<'_, A> {
+        Cursor {
+            index: 0,
+            current: self.head,
+            prev: None,
+            list: self,
+        }
+    }
+
+    /// Provides a cursor at the back element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    /// list.push_back(task(1));
+    ///
+    /// let cursor = list.cursor_back();
+    /// assert_eq!(cursor.current().map(|task| task.id), Some(1));
+    /// assert_eq!(cursor.index(), Some(1));
+    /// assert_eq!(cursor.peek_prev().map(|task| task.id), Some(0));
+    /// ```
+    #[inline]
+    pub fn cursor_back(&self) -> Cursor<'_, A> {
+        Cursor {
+            index: self.len.saturating_sub(1),
+            current: self.tail,
+            prev: self
+                .tail
+                .and_then(|tail| unsafe { Self::neighbour(None, tail) }),
+            list: self,
+        }
+    }
+
+    /// Provides a cursor with editing operations at the front element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    /// list.push_back(task(2));
+    ///
+    /// list.cursor_front_mut().insert_after(task(1));
+    ///
+    /// assert_eq!(list.iter().map(|task| task.id).collect::<Vec<_>>(), vec![0, 1, 2]);
+    /// ```
+    #[inline]
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, A> {
+        CursorMut {
+            index: 0,
+            current: self.head,
+            prev: None,
+            list: self,
+        }
+    }
+
+    /// Provides a cursor with editing operations at the back element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    /// list.push_back(task(2));
+    ///
+    /// list.cursor_back_mut().insert_before(task(1));
+    ///
+    /// assert_eq!(list.iter().map(|task| task.id).collect::<Vec<_>>(), vec![0, 1, 2]);
+    /// ```
+    #[inline]
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, A> {
+        CursorMut {
+            index: self.len.saturating_sub(1),
+            current: self.tail,
+            prev: self
+                .tail
+                .and_then(|tail| unsafe { Self::neighbour(None, tail) }),
+            list: self,
+        }
+    }
+
+    /// Returns `true` if the `IntrusiveXorList` is empty
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// assert!(list.is_empty());
+    ///
+    /// list.push_front(task(0));
+    /// assert!(!list.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// Returns the length of the `IntrusiveXorList`
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    ///
+    /// list.push_front(task(1));
+    /// assert_eq!(list.len(), 1);
+    ///
+    /// list.push_back(task(2));
+    /// assert_eq!(list.len(), 2);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Removes all elements from the `IntrusiveXorList`, dropping their
+    /// pointers.
+    ///
+    /// This operation should compute in O(n) time
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::marker::PhantomData;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter<'a>(PhantomData<&'a Task>);
+    /// #
+    /// # unsafe impl<'a> Adapter for TaskAdapter<'a> {
+    /// #     type Value = Task;
+    /// #     type Pointer = &'a Task;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Task {
+    /// #     Task { id, link: XorLink::new() }
+    /// # }
+    /// #
+    /// let tasks: Vec<Task> = (0..2).map(task).collect();
+    ///
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.extend(&tasks);
+    /// assert!(tasks[0].link.is_linked());
+    ///
+    /// list.clear();
+    /// assert!(list.is_empty());
+    /// assert!(!tasks[0].link.is_linked());
+    /// ```
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    /// Returns `true` if the `IntrusiveXorList` contains an element equal to the
+    /// given value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::mem;
+    /// use std::pin::Pin;
+    /// use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    ///
+    /// #[derive(Default)]
+    /// struct Entry {
+    ///     value: u32,
+    ///     link: XorLink,
+    /// }
+    ///
+    /// // links are not taken into account
+    /// impl PartialEq for Entry {
+    ///     fn eq(&self, other: &Self) -> bool {
+    ///         self.value == other.value
+    ///     }
+    /// }
+    ///
+    /// struct EntryAdapter;
+    ///
+    /// unsafe impl Adapter for EntryAdapter {
+    ///     type Value = Entry;
+    ///     type Pointer = Pin<Box<Entry>>;
+    ///     const LINK_OFFSET: usize = mem::offset_of!(Entry, link);
+    /// }
+    ///
+    /// let mut list: IntrusiveXorList<EntryAdapter> = IntrusiveXorList::new();
+    /// list.push_back(Box::pin(Entry { value: 1, link: XorLink::new() }));
+    ///
+    /// assert!(list.contains(&Entry { value: 1, link: XorLink::new() }));
+    /// assert!(!list.contains(&Entry::default()));
+    /// ```
+    pub fn contains(&self, x: &A::Value) -> bool
+    where
+        A::Value: PartialEq,
+    {
+        self.iter().any(|e| e == x)
+    }
+
+    /// Provides a reference to the front element, or `None` if the list is
+    /// empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::marker::PhantomData;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter<'a>(PhantomData<&'a Task>);
+    /// #
+    /// # unsafe impl<'a> Adapter for TaskAdapter<'a> {
+    /// #     type Value = Task;
+    /// #     type Pointer = &'a Task;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Task {
+    /// #     Task { id, link: XorLink::new() }
+    /// # }
+    /// #
+    /// let first = task(1);
+    ///
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// assert!(list.front().is_none());
+    ///
+    /// list.push_front(&first);
+    /// assert_eq!(list.front().map(|task| task.id), Some(1));
+    /// ```
+    #[inline]
+    pub fn front(&self) -> Option<&A::Value> {
+        unsafe { self.head.map(|head| &*head.as_ptr()) }
+    }
+
+    /// Provides a reference to the back element, or `None` if the list is
+    /// empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::marker::PhantomData;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter<'a>(PhantomData<&'a Task>);
+    /// #
+    /// # unsafe impl<'a> Adapter for TaskAdapter<'a> {
+    /// #     type Value = Task;
+    /// #     type Pointer = &'a Task;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Task {
+    /// #     Task { id, link: XorLink::new() }
+    /// # }
+    /// #
+    /// let last = task(1);
+    ///
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// assert!(list.back().is_none());
+    ///
+    /// list.push_back(&last);
+    /// assert_eq!(list.back().map(|task| task.id), Some(1));
+    /// ```
+    #[inline]
+    pub fn back(&self) -> Option<&A::Value> {
+        unsafe { self.tail.map(|tail| &*tail.as_ptr()) }
+    }
+
+    /// Adds an element first in the list.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element is already linked into a list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    ///
+    /// list.push_front(task(2));
+    /// assert_eq!(list.front().map(|task| task.id), Some(2));
+    ///
+    /// list.push_front(task(1));
+    /// assert_eq!(list.front().map(|task| task.id), Some(1));
+    /// ```
+    pub fn push_front(&mut self, entry: A::Pointer) {
+        let node = Self::acquire(entry);
+        unsafe {
+            let head = self.head;
+            self.link_node(None, head, node);
+        }
+    }
+
+    /// Removes the first element and returns it, or `None` if the list is
+    /// empty.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// assert!(list.pop_front().is_none());
+    ///
+    /// list.push_front(task(1));
+    /// list.push_front(task(3));
+    ///
+    /// let popped = list.pop_front().unwrap();
+    /// assert_eq!(popped.id, 3);
+    /// assert!(!popped.link.is_linked());
+    /// assert_eq!(list.pop_front().map(|task| task.id), Some(1));
+    /// assert!(list.pop_front().is_none());
+    /// ```
+    pub fn pop_front(&mut self) -> Option<A::Pointer> {
+        self.head.map(|head| unsafe {
+            let next = Self::neighbour(None, head);
+            self.unlink_node(None, head, next)
+        })
+    }
+
+    /// Appends an element to the back of a list
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element is already linked into a list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(1));
+    /// list.push_back(task(3));
+    /// assert_eq!(list.back().map(|task| task.id), Some(3));
+    /// ```
+    pub fn push_back(&mut self, entry: A::Pointer) {
+        let node = Self::acquire(entry);
+        unsafe {
+            let tail = self.tail;
+            self.link_node(tail, None, node);
+        }
+    }
+
+    /// Removes the last element from a list and returns it, or `None` if
+    /// it is empty
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// assert!(list.pop_back().is_none());
+    ///
+    /// list.push_back(task(1));
+    /// list.push_back(task(3));
+    /// assert_eq!(list.pop_back().map(|task| task.id), Some(3));
+    /// ```
+    pub fn pop_back(&mut self) -> Option<A::Pointer> {
+        self.tail.map(|tail| unsafe {
+            let prev = Self::neighbour(None, tail);
+            self.unlink_node(prev, tail, None)
+        })
+    }
+
+    /// Splits the list into two at the given index. Returns everything after the given index,
+    /// including the index
+    ///
+    /// This operation should compute in O(min(i, n - i)) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_front(task(1));
+    /// list.push_front(task(2));
+    /// list.push_front(task(3));
+    ///
+    /// let mut splitted = list.split_off(2);
+    ///
+    /// assert_eq!(list.len(), 2);
+    /// assert_eq!(splitted.pop_front().map(|task| task.id), Some(1));
+    /// assert!(splitted.pop_front().is_none());
+    /// ```
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let len = self.len();
+        assert!(at <= len, "Cannot split off at a nonexistent index");
+        if at == 0 {
+            return mem::take(self);
+        } else if at == len {
+            return Self::new();
+        }
+
+        let (node, prev) = self.node_at(at - 1);
+        unsafe { self.split_after_node(node, prev, at) }
+    }
+}
+
+impl<A: Adapter> Drop for IntrusiveXorList<A> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<'a, A: Adapter> Iterator for Iter<'a, A> {
+    type Item = &'a A::Value;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a A::Value> {
+        if self.len == 0 {
+            return None;
+        }
+        self.head.map(|node| unsafe {
+            let next = IntrusiveXorList::<A>::neighbour(self.last_head, node);
+            self.last_head = mem::replace(&mut self.head, next);
+            self.len -= 1;
+            &*node.as_ptr()
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, A: Adapter> DoubleEndedIterator for Iter<'a, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a A::Value> {
+        if self.len == 0 {
+            return None;
+        }
+        self.tail.map(|node| unsafe {
+            let prev = IntrusiveXorList::<A>::neighbour(self.last_tail, node);
+            self.last_tail = mem::replace(&mut self.tail, prev);
+            self.len -= 1;
+            &*node.as_ptr()
+        })
+    }
+}
+
+impl<'a, A: Adapter> ExactSizeIterator for Iter<'a, A> {}
+
+impl<'a, A: Adapter> FusedIterator for Iter<'a, A> {}
+
+impl<A: Adapter> Iterator for IntoIter<A> {
+    type Item = A::Pointer;
+
+    #[inline]
+    fn next(&mut self) -> Option<A::Pointer> {
+        self.list.pop_front()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<A: Adapter> DoubleEndedIterator for IntoIter<A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<A::Pointer> {
+        self.list.pop_back()
+    }
+}
+
+impl<A: Adapter> ExactSizeIterator for IntoIter<A> {}
+
+impl<A: Adapter> FusedIterator for IntoIter<A> {}
+
+impl<'a, A: Adapter> Cursor<'a, A> {
+    /// Returns the cursor position index within the `IntrusiveXorList`.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    /// list.push_back(task(1));
+    ///
+    /// let mut cursor = list.cursor_back();
+    /// assert_eq!(cursor.index(), Some(1));
+    /// cursor.move_next();
+    /// assert_eq!(cursor.index(), None);
+    /// ```
+    #[inline]
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    /// Moves the cursor to the next element of the `IntrusiveXorList`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the `IntrusiveXorList`. If it is pointing to the last
+    /// element of the `IntrusiveXorList` then this will move it to the "ghost" non-element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    ///
+    /// let mut cursor = list.cursor_front();
+    /// cursor.move_next();
+    /// assert!(cursor.current().is_none());
+    /// cursor.move_next();
+    /// assert_eq!(cursor.current().map(|task| task.id), Some(0));
+    /// ```
+    pub fn move_next(&mut self) {
+        match self.current.take() {
+            None => {
+                self.current = self.list.head;
+                self.prev = None;
+                self.index = 0;
+            }
+            Some(current) => unsafe {
+                self.current = IntrusiveXorList::<A>::neighbour(self.prev, current);
+                self.prev = Some(current);
+                self.index += 1;
+            },
+        }
+    }
+
+    /// Moves the cursor to the previous element of the `IntrusiveXorList`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the `IntrusiveXorList`. If it is pointing to the first
+    /// element of the `IntrusiveXorList` then this will move it to the "ghost" non-element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    /// list.push_back(task(1));
+    ///
+    /// let mut cursor = list.cursor_front();
+    /// cursor.move_prev();
+    /// assert!(cursor.current().is_none());
+    /// cursor.move_prev();
+    /// assert_eq!(cursor.current().map(|task| task.id), Some(1));
+    /// ```
+    pub fn move_prev(&mut self) {
+        match self.prev.take() {
+            None => {
+                self.current = None;
+                self.prev = self.list.tail;
+                self.index = self.list.len;
+            }
+            Some(prev) => unsafe {
+                self.prev = IntrusiveXorList::<A>::neighbour(self.current, prev);
+                self.current = Some(prev);
+                self.index -= 1;
+            },
+        }
+    }
+
+    /// Returns a reference to the element that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::marker::PhantomData;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter<'a>(PhantomData<&'a Task>);
+    /// #
+    /// # unsafe impl<'a> Adapter for TaskAdapter<'a> {
+    /// #     type Value = Task;
+    /// #     type Pointer = &'a Task;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Task {
+    /// #     Task { id, link: XorLink::new() }
+    /// # }
+    /// #
+    /// let first = task(0);
+    ///
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// assert!(list.cursor_front().current().is_none());
+    ///
+    /// list.push_back(&first);
+    /// assert_eq!(list.cursor_front().current().map(|task| task.id), Some(0));
+    /// ```
+    #[inline]
+    pub fn current(&self) -> Option<&'a A::Value> {
+        unsafe { self.current.map(|current| &*current.as_ptr()) }
+    }
+
+    /// Returns a reference to the next element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the first element of the `IntrusiveXorList`. If it is pointing to the last
+    /// element of the `IntrusiveXorList` then this returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    /// list.push_back(task(1));
+    ///
+    /// let mut cursor = list.cursor_front();
+    /// assert_eq!(cursor.peek_next().map(|task| task.id), Some(1));
+    /// cursor.move_next();
+    /// assert!(cursor.peek_next().is_none());
+    /// ```
+    #[inline]
+    pub fn peek_next(&self) -> Option<&'a A::Value> {
+        let next = match self.current {
+            None => self.list.head,
+            Some(current) => unsafe { IntrusiveXorList::<A>::neighbour(self.prev, current) },
+        };
+        unsafe { next.map(|node| &*node.as_ptr()) }
+    }
+
+    /// Returns a reference to the previous element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the last element of the `IntrusiveXorList`. If it is pointing to the first
+    /// element of the `IntrusiveXorList` then this returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    /// list.push_back(task(1));
+    ///
+    /// let mut cursor = list.cursor_front();
+    /// assert!(cursor.peek_prev().is_none());
+    /// cursor.move_prev();
+    /// assert_eq!(cursor.peek_prev().map(|task| task.id), Some(1));
+    /// ```
+    #[inline]
+    pub fn peek_prev(&self) -> Option<&'a A::Value> {
+        unsafe { self.prev.map(|prev| &*prev.as_ptr()) }
+    }
+}
+
+impl<'a, A: Adapter> CursorMut<'a, A> {
+    /// Returns the next element, i.e. the successor of the current one or the
+    /// head if the cursor points to the "ghost" non-element.
+    #[inline]
+    fn next_node(&self) -> Link<A> {
+        match self.current {
+            None => self.list.head,
+            Some(current) => unsafe { IntrusiveXorList::<A>::neighbour(self.prev, current) },
+        }
+    }
+
+    /// Returns the cursor position index within the `IntrusiveXorList`.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(1));
+    ///
+    /// let mut cursor = list.cursor_front_mut();
+    /// assert_eq!(cursor.index(), Some(0));
+    /// cursor.insert_before(task(0));
+    /// assert_eq!(cursor.index(), Some(1));
+    /// ```
+    #[inline]
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    /// Moves the cursor to the next element of the `IntrusiveXorList`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the `IntrusiveXorList`. If it is pointing to the last
+    /// element of the `IntrusiveXorList` then this will move it to the "ghost" non-element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    /// list.push_back(task(2));
+    ///
+    /// let mut cursor = list.cursor_front_mut();
+    /// cursor.move_next();
+    /// cursor.insert_before(task(1));
+    ///
+    /// assert_eq!(list.iter().map(|task| task.id).collect::<Vec<_>>(), vec![0, 1, 2]);
+    /// ```
+    pub fn move_next(&mut self) {
+        match self.current.take() {
+            None => {
+                self.current = self.list.head;
+                self.prev = None;
+                self.index = 0;
+            }
+            Some(current) => unsafe {
+                self.current = IntrusiveXorList::<A>::neighbour(self.prev, current);
+                self.prev = Some(current);
+                self.index += 1;
+            },
+        }
+    }
+
+    /// Moves the cursor to the previous element of the `IntrusiveXorList`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the `IntrusiveXorList`. If it is pointing to the first
+    /// element of the `IntrusiveXorList` then this will move it to the "ghost" non-element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    /// list.push_back(task(1));
+    ///
+    /// let mut cursor = list.cursor_front_mut();
+    /// cursor.move_prev();
+    /// cursor.move_prev();
+    /// assert_eq!(cursor.remove_current().map(|task| task.id), Some(1));
+    /// ```
+    pub fn move_prev(&mut self) {
+        match self.prev.take() {
+            None => {
+                self.current = None;
+                self.prev = self.list.tail;
+                self.index = self.list.len;
+            }
+            Some(prev) => unsafe {
+                self.prev = IntrusiveXorList::<A>::neighbour(self.current, prev);
+                self.current = Some(prev);
+                self.index -= 1;
+            },
+        }
+    }
+
+    /// Returns a reference to the element that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    ///
+    /// let mut cursor = list.cursor_front_mut();
+    /// assert_eq!(cursor.current().map(|task| task.id), Some(0));
+    /// cursor.move_next();
+    /// assert!(cursor.current().is_none());
+    /// ```
+    #[inline]
+    pub fn current(&self) -> Option<&A::Value> {
+        unsafe { self.current.map(|current| &*current.as_ptr()) }
+    }
+
+    /// Returns a reference to the next element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the first element of the `IntrusiveXorList`. If it is pointing to the last
+    /// element of the `IntrusiveXorList` then this returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    ///
+    /// let mut cursor = list.cursor_front_mut();
+    /// assert!(cursor.peek_next().is_none());
+    /// cursor.insert_after(task(1));
+    /// assert_eq!(cursor.peek_next().map(|task| task.id), Some(1));
+    /// ```
+    #[inline]
+    pub fn peek_next(&self) -> Option<&A::Value> {
+        unsafe { self.next_node().map(|next| &*next.as_ptr()) }
+    }
+
+    /// Returns a reference to the previous element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the last element of the `IntrusiveXorList`. If it is pointing to the first
+    /// element of the `IntrusiveXorList` then this returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(1));
+    ///
+    /// let mut cursor = list.cursor_front_mut();
+    /// assert!(cursor.peek_prev().is_none());
+    /// cursor.insert_before(task(0));
+    /// assert_eq!(cursor.peek_prev().map(|task| task.id), Some(0));
+    /// ```
+    #[inline]
+    pub fn peek_prev(&self) -> Option<&A::Value> {
+        unsafe { self.prev.map(|prev| &*prev.as_ptr()) }
+    }
+
+    /// Provides a reference to the front element of the `IntrusiveXorList`, or
+    /// `None` if the list is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    /// list.push_back(task(1));
+    ///
+    /// let cursor = list.cursor_back_mut();
+    /// assert_eq!(cursor.front().map(|task| task.id), Some(0));
+    /// ```
+    #[inline]
+    pub fn front(&self) -> Option<&A::Value> {
+        self.list.front()
+    }
+
+    /// Provides a reference to the back element of the `IntrusiveXorList`, or
+    /// `None` if the list is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    /// list.push_back(task(1));
+    ///
+    /// let cursor = list.cursor_front_mut();
+    /// assert_eq!(cursor.back().map(|task| task.id), Some(1));
+    /// ```
+    #[inline]
+    pub fn back(&self) -> Option<&A::Value> {
+        self.list.back()
+    }
+
+    /// Returns a read-only cursor pointing to the current element.
+    ///
+    /// The lifetime of the returned `Cursor` is bound to that of the
+    /// `CursorMut`, which means it cannot outlive the `CursorMut` and that the
+    /// `CursorMut` is frozen for the lifetime of the `Cursor`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    ///
+    /// let cursor = list.cursor_front_mut();
+    /// assert_eq!(cursor.as_cursor().current().map(|task| task.id), Some(0));
+    /// ```
+    #[inline]
+    pub fn as_cursor(&self) -> Cursor<'_, A> {
+        Cursor {
+            index: self.index,
+            current: self.current,
+            prev: self.prev,
+            list: self.list,
+        }
+    }
+
+    /// Inserts a new element into the `IntrusiveXorList` after the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is
+    /// inserted at the front of the `IntrusiveXorList`.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element is already linked into a list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(1));
+    ///
+    /// {
+    ///     let mut cursor = list.cursor_front_mut();
+    ///     cursor.insert_after(task(2));
+    ///     cursor.move_prev();
+    ///     cursor.insert_after(task(0));
+    /// }
+    ///
+    /// assert_eq!(list.iter().map(|task| task.id).collect::<Vec<_>>(), vec![0, 1, 2]);
+    /// ```
+    pub fn insert_after(&mut self, entry: A::Pointer) {
+        let node = IntrusiveXorList::<A>::acquire(entry);
+        unsafe {
+            let next = self.next_node();
+            self.list.link_node(self.current, next, node);
+        }
+        if self.current.is_none() {
+            // The ghost non-element follows the tail which may have changed
+            self.prev = self.list.tail;
+            self.index = self.list.len;
+        }
+    }
+
+    /// Inserts a new element into the `IntrusiveXorList` before the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is
+    /// inserted at the end of the `IntrusiveXorList`.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element is already linked into a list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(1));
+    ///
+    /// {
+    ///     let mut cursor = list.cursor_front_mut();
+    ///     cursor.insert_before(task(0));
+    ///     cursor.move_next();
+    ///     cursor.insert_before(task(2));
+    /// }
+    ///
+    /// assert_eq!(list.iter().map(|task| task.id).collect::<Vec<_>>(), vec![0, 1, 2]);
+    /// ```
+    pub fn insert_before(&mut self, entry: A::Pointer) {
+        let node = IntrusiveXorList::<A>::acquire(entry);
+        unsafe {
+            self.list.link_node(self.prev, self.current, node);
+        }
+        self.prev = Some(node);
+        self.index += 1;
+    }
+
+    /// Removes the current element from the `IntrusiveXorList`.
+    ///
+    /// The pointer to the element that was removed is returned, and the cursor is
+    /// moved to point to the next element in the `IntrusiveXorList`.
+    ///
+    /// If the cursor is currently pointing to the "ghost" non-element then no element
+    /// is removed and `None` is returned.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    /// list.push_back(task(1));
+    ///
+    /// let mut cursor = list.cursor_front_mut();
+    /// let removed = cursor.remove_current().unwrap();
+    /// assert_eq!(removed.id, 0);
+    /// assert!(!removed.link.is_linked());
+    /// assert_eq!(cursor.current().map(|task| task.id), Some(1));
+    /// ```
+    pub fn remove_current(&mut self) -> Option<A::Pointer> {
+        let current = self.current?;
+        unsafe {
+            let next = self.next_node();
+            let entry = self.list.unlink_node(self.prev, current, next);
+            self.current = next;
+            Some(entry)
+        }
+    }
+
+    /// Removes the current element from the `IntrusiveXorList` and returns it as
+    /// a new list holding only that element.
+    ///
+    /// The cursor is moved to point to the next element in the `IntrusiveXorList`.
+    /// If the cursor is currently pointing to the "ghost" non-element then no element
+    /// is removed and `None` is returned.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    /// list.push_back(task(1));
+    ///
+    /// let mut cursor = list.cursor_front_mut();
+    /// let removed = cursor.remove_current_as_list().unwrap();
+    /// assert_eq!(removed.len(), 1);
+    /// assert_eq!(removed.front().map(|task| task.id), Some(0));
+    /// assert!(removed.front().unwrap().link.is_linked());
+    /// assert_eq!(cursor.current().map(|task| task.id), Some(1));
+    /// ```
+    pub fn remove_current_as_list(&mut self) -> Option<IntrusiveXorList<A>> {
+        self.remove_current().map(|entry| {
+            let mut list = IntrusiveXorList::new();
+            list.push_back(entry);
+            list
+        })
+    }
+
+    /// Inserts the elements from the given `IntrusiveXorList` after the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new elements are
+    /// inserted at the start of the `IntrusiveXorList`.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    /// list.push_back(task(1));
+    ///
+    /// let mut other: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// other.push_back(task(10));
+    /// other.push_back(task(11));
+    ///
+    /// list.cursor_front_mut().splice_after(other);
+    ///
+    /// assert_eq!(list.iter().map(|task| task.id).collect::<Vec<_>>(), vec![0, 10, 11, 1]);
+    /// ```
+    pub fn splice_after(&mut self, mut list: IntrusiveXorList<A>) {
+        unsafe {
+            let next = self.next_node();
+            self.list.splice_between(self.current, next, &mut list);
+        }
+        if self.current.is_none() {
+            // The ghost non-element follows the tail which may have changed
+            self.prev = self.list.tail;
+            self.index = self.list.len;
+        }
+    }
+
+    /// Inserts the elements from the given `IntrusiveXorList` before the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new elements are
+    /// inserted at the end of the `IntrusiveXorList`.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    /// list.push_back(task(1));
+    ///
+    /// let mut other: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// other.push_back(task(10));
+    /// other.push_back(task(11));
+    ///
+    /// {
+    ///     let mut cursor = list.cursor_back_mut();
+    ///     cursor.splice_before(other);
+    ///     assert_eq!(cursor.index(), Some(3));
+    /// }
+    ///
+    /// assert_eq!(list.iter().map(|task| task.id).collect::<Vec<_>>(), vec![0, 10, 11, 1]);
+    /// ```
+    pub fn splice_before(&mut self, mut list: IntrusiveXorList<A>) {
+        if let Some(tail) = list.tail {
+            let len = list.len;
+            unsafe {
+                self.list.splice_between(self.prev, self.current, &mut list);
+            }
+            self.prev = Some(tail);
+            self.index += len;
+        }
+    }
+
+    /// Splits the list into two after the current element. This will return a
+    /// new list consisting of everything after the cursor, with the original
+    /// list retaining everything before.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the entire contents
+    /// of the `IntrusiveXorList` are moved.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.extend((0..4).map(task));
+    ///
+    /// let splitted = list.cursor_front_mut().split_after();
+    ///
+    /// assert_eq!(list.len(), 1);
+    /// assert_eq!(splitted.len(), 3);
+    /// assert_eq!(splitted.front().map(|task| task.id), Some(1));
+    /// ```
+    pub fn split_after(&mut self) -> IntrusiveXorList<A> {
+        match self.current {
+            None => {
+                self.prev = None;
+                self.index = 0;
+                mem::take(self.list)
+            }
+            Some(current) => unsafe {
+                self.list
+                    .split_after_node(current, self.prev, self.index + 1)
+            },
+        }
+    }
+
+    /// Splits the list into two before the current element. This will return a
+    /// new list consisting of everything before the cursor, with the original
+    /// list retaining everything after.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the entire contents
+    /// of the `IntrusiveXorList` are moved.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.extend((0..4).map(task));
+    ///
+    /// let splitted = list.cursor_back_mut().split_before();
+    ///
+    /// assert_eq!(list.len(), 1);
+    /// assert_eq!(splitted.len(), 3);
+    /// assert_eq!(splitted.back().map(|task| task.id), Some(2));
+    /// ```
+    pub fn split_before(&mut self) -> IntrusiveXorList<A> {
+        let prev = match (self.current, self.prev) {
+            (None, _) => {
+                self.prev = None;
+                self.index = 0;
+                return mem::take(self.list);
+            }
+            (Some(_), None) => return IntrusiveXorList::new(),
+            (Some(_), Some(prev)) => prev,
+        };
+        unsafe {
+            let prev_prev = IntrusiveXorList::<A>::neighbour(self.current, prev);
+            let second_part = self.list.split_after_node(prev, prev_prev, self.index);
+            self.prev = None;
+            self.index = 0;
+            mem::replace(self.list, second_part)
+        }
+    }
+
+    /// Adds an element first in the `IntrusiveXorList`.
+    ///
+    /// The cursor keeps pointing to the same element, or to the "ghost" non-element.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element is already linked into a list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(1));
+    ///
+    /// let mut cursor = list.cursor_front_mut();
+    /// cursor.push_front(task(0));
+    /// assert_eq!(cursor.current().map(|task| task.id), Some(1));
+    /// assert_eq!(cursor.index(), Some(1));
+    /// assert_eq!(cursor.peek_prev().map(|task| task.id), Some(0));
+    /// ```
+    pub fn push_front(&mut self, entry: A::Pointer) {
+        let node = IntrusiveXorList::<A>::acquire(entry);
+        unsafe {
+            let head = self.list.head;
+            self.list.link_node(None, head, node);
+        }
+        if self.prev.is_none() {
+            // The new element precedes the current one, or the ghost non-element
+            // if the list was empty
+            self.prev = Some(node);
+        }
+        self.index += 1;
+    }
+
+    /// Appends an element to the back of the `IntrusiveXorList`.
+    ///
+    /// The cursor keeps pointing to the same element, or to the "ghost" non-element.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element is already linked into a list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.push_back(task(0));
+    ///
+    /// let mut cursor = list.cursor_front_mut();
+    /// cursor.push_back(task(1));
+    /// assert_eq!(cursor.current().map(|task| task.id), Some(0));
+    /// assert_eq!(cursor.peek_next().map(|task| task.id), Some(1));
+    /// ```
+    pub fn push_back(&mut self, entry: A::Pointer) {
+        let node = IntrusiveXorList::<A>::acquire(entry);
+        unsafe {
+            let tail = self.list.tail;
+            self.list.link_node(tail, None, node);
+        }
+        if self.current.is_none() {
+            // The ghost non-element follows the new tail
+            self.prev = Some(node);
+            self.index += 1;
+        }
+    }
+
+    /// Removes the first element from the `IntrusiveXorList` and returns it, or
+    /// `None` if the list is empty.
+    ///
+    /// The cursor keeps pointing to the same element, unless it pointed to the
+    /// front element. In that case, it is moved to the new front element.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.extend((0..3).map(task));
+    ///
+    /// let mut cursor = list.cursor_back_mut();
+    /// assert_eq!(cursor.pop_front().map(|task| task.id), Some(0));
+    /// assert_eq!(cursor.index(), Some(1));
+    /// assert_eq!(cursor.current().map(|task| task.id), Some(2));
+    /// ```
+    pub fn pop_front(&mut self) -> Option<A::Pointer> {
+        let head = self.list.head?;
+        if self.current == Some(head) {
+            return self.remove_current();
+        }
+        unsafe {
+            let next = IntrusiveXorList::<A>::neighbour(None, head);
+            if self.prev == Some(head) {
+                self.prev = None;
+            }
+            self.index -= 1;
+            Some(self.list.unlink_node(None, head, next))
+        }
+    }
+
+    /// Removes the last element from the `IntrusiveXorList` and returns it, or
+    /// `None` if the list is empty.
+    ///
+    /// The cursor keeps pointing to the same element, unless it pointed to the
+    /// back element. In that case, it is moved to the "ghost" non-element.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::mem;
+    /// # use std::pin::Pin;
+    /// # use rust_utils::intrusive_xor_list::{Adapter, IntrusiveXorList, XorLink};
+    /// #
+    /// # struct Task {
+    /// #     id: u32,
+    /// #     link: XorLink,
+    /// # }
+    /// #
+    /// # struct TaskAdapter;
+    /// #
+    /// # unsafe impl Adapter for TaskAdapter {
+    /// #     type Value = Task;
+    /// #     type Pointer = Pin<Box<Task>>;
+    /// #     const LINK_OFFSET: usize = mem::offset_of!(Task, link);
+    /// # }
+    /// #
+    /// # fn task(id: u32) -> Pin<Box<Task>> {
+    /// #     Box::pin(Task { id, link: XorLink::new() })
+    /// # }
+    /// #
+    /// let mut list: IntrusiveXorList<TaskAdapter> = IntrusiveXorList::new();
+    /// list.extend((0..3).map(task));
+    ///
+    /// let mut cursor = list.cursor_back_mut();
+    /// assert_eq!(cursor.pop_back().map(|task| task.id), Some(2));
+    /// assert!(cursor.current().is_none());
+    /// assert_eq!(cursor.peek_prev().map(|task| task.id), Some(1));
+    /// ```
+    pub fn pop_back(&mut self) -> Option<A::Pointer> {
+        let tail = self.list.tail?;
+        let entry = unsafe {
+            let prev = IntrusiveXorList::<A>::neighbour(None, tail);
+            self.list.unlink_node(prev, tail, None)
+        };
+        if self.current.is_none() || self.current == Some(tail) {
+            // The ghost non-element follows the new tail
+            self.current = None;
+            self.prev = self.list.tail;
+            self.index = self.list.len;
+        }
+        Some(entry)
+    }
+}
+
+impl<A: Adapter> IntoIterator for IntrusiveXorList<A> {
+    type Item = A::Pointer;
+    type IntoIter = IntoIter<A>;
+
+    /// Consumes the list into an iterator yielding the pointers to its elements
+    #[inline]
+    fn into_iter(self) -> IntoIter<A> {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, A: Adapter> IntoIterator for &'a IntrusiveXorList<A> {
+    type Item = &'a A::Value;
+    type IntoIter = Iter<'a, A>;
+
+    fn into_iter(self) -> Iter<'a, A> {
+        self.iter()
+    }
+}
+
+impl<A: Adapter> Extend<A::Pointer> for IntrusiveXorList<A> {
+    fn extend<I: IntoIterator<Item = A::Pointer>>(&mut self, iter: I) {
+        for elt in iter {
+            self.push_back(elt);
+        }
+    }
+}
+
+impl<A: Adapter> fmt::Debug for IntrusiveXorList<A>
+where
+    A::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+unsafe impl<A: Adapter> Send for IntrusiveXorList<A> where A::Pointer: Send {}
+
+unsafe impl<A: Adapter> Sync for IntrusiveXorList<A> where A::Pointer: Sync {}
+
+unsafe impl<'a, A: Adapter> Send for Iter<'a, A> where A::Value: Sync {}
+
+unsafe impl<'a, A: Adapter> Sync for Iter<'a, A> where A::Value: Sync {}
+
+unsafe impl<'a, A: Adapter> Send for Cursor<'a, A> where A::Pointer: Sync {}
+
+unsafe impl<'a, A: Adapter> Sync for Cursor<'a, A> where A::Pointer: Sync {}
+
+unsafe impl<'a, A: Adapter> Send for CursorMut<'a, A> where A::Pointer: Send {}
+
+unsafe impl<'a, A: Adapter> Sync for CursorMut<'a, A> where A::Pointer: Sync {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Adapter, IntrusiveXorList, XorLink};
+    use rand::{thread_rng, RngCore};
+    use std::cell::Cell;
+    use std::collections::VecDeque;
+    use std::marker::PhantomData;
+    use std::mem;
+    use std::pin::Pin;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    #[derive(Debug)]
+    struct Entry {
+        value: i32,
+        first: XorLink,
+        second: XorLink,
+        drops: Rc<Cell<usize>>,
+    }
+
+    impl Entry {
+        fn new(value: i32, drops: &Rc<Cell<usize>>) -> Self {
+            Entry {
+                value,
+                first: XorLink::new(),
+                second: XorLink::new(),
+                drops: Rc::clone(drops),
+            }
+        }
+    }
+
+    impl Drop for Entry {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    impl PartialEq for Entry {
+        fn eq(&self, other: &Self) -> bool {
+            self.value == other.value
+        }
+    }
+
+    struct BoxAdapter;
+
+    unsafe impl Adapter for BoxAdapter {
+        type Value = Entry;
+        type Pointer = Pin<Box<Entry>>;
+        const LINK_OFFSET: usize = mem::offset_of!(Entry, first);
+    }
+
+    struct FirstAdapter<'a>(PhantomData<&'a Entry>);
+
+    unsafe impl<'a> Adapter for FirstAdapter<'a> {
+        type Value = Entry;
+        type Pointer = &'a Entry;
+        const LINK_OFFSET: usize = mem::offset_of!(Entry, first);
+    }
+
+    struct SecondAdapter<'a>(PhantomData<&'a Entry>);
+
+    unsafe impl<'a> Adapter for SecondAdapter<'a> {
+        type Value = Entry;
+        type Pointer = &'a Entry;
+        const LINK_OFFSET: usize = mem::offset_of!(Entry, second);
+    }
+
+    fn values<A: Adapter<Value = Entry>>(list: &IntrusiveXorList<A>) -> Vec<i32> {
+        list.iter().map(|entry| entry.value).collect()
+    }
+
+    fn check_links<A: Adapter>(list: &IntrusiveXorList<A>) {
+        let mut node_ptr;
+        let tail_ptr;
+        let head_ptr;
+        match (list.head, list.tail) {
+            (None, None) => {
+                assert_eq!(0, list.len);
+                return;
+            }
+            (None, Some(_)) | (Some(_), None) => panic!("tail and head must both be none or some"),
+            (Some(head), Some(tail)) => {
+                node_ptr = head;
+                head_ptr = head;
+                tail_ptr = tail;
+            }
+        }
+        let mut last_ptr = None;
+        unsafe {
+            for _ in 0..(list.len - 1) {
+                assert!(IntrusiveXorList::<A>::link(node_ptr).is_linked());
+                let next_element = IntrusiveXorList::<A>::neighbour(last_ptr, node_ptr)
+                    .expect("next link is null, not good");
+                last_ptr = Some(node_ptr);
+                node_ptr = next_element;
+            }
+            assert_eq!(node_ptr, tail_ptr);
+
+            last_ptr = None;
+            for _ in 0..(list.len - 1) {
+                let prev_element = IntrusiveXorList::<A>::neighbour(last_ptr, node_ptr)
+                    .expect("prev link is null, not good");
+                last_ptr = Some(node_ptr);
+                node_ptr = prev_element;
+            }
+            assert_eq!(node_ptr, head_ptr);
+        }
+    }
+
+    #[test]
+    fn test_boxed() {
+        let drops = Rc::new(Cell::new(0));
+        let mut m: IntrusiveXorList<BoxAdapter> = IntrusiveXorList::new();
+        assert!(m.pop_front().is_none());
+        m.push_back(Box::pin(Entry::new(2, &drops)));
+        m.push_front(Box::pin(Entry::new(1, &drops)));
+        m.push_back(Box::pin(Entry::new(3, &drops)));
+        check_links(&m);
+        assert_eq!(m.len(), 3);
+        assert_eq!(values(&m), &[1, 2, 3]);
+        assert_eq!(m.front().map(|entry| entry.value), Some(1));
+        assert_eq!(m.back().map(|entry| entry.value), Some(3));
+
+        let popped = m.pop_back().unwrap();
+        assert_eq!(popped.value, 3);
+        assert!(!popped.first.is_linked());
+        check_links(&m);
+        // the popped element may be linked again
+        m.push_front(popped);
+        assert_eq!(values(&m), &[3, 1, 2]);
+        assert_eq!(drops.get(), 0);
+
+        drop(m.pop_front());
+        assert_eq!(drops.get(), 1);
+        drop(m);
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn test_multiple_lists() {
+        let drops = Rc::new(Cell::new(0));
+        let entries: Vec<Entry> = (0..6).map(|i| Entry::new(i, &drops)).collect();
+        let mut all: IntrusiveXorList<FirstAdapter> = IntrusiveXorList::new();
+        let mut even: IntrusiveXorList<SecondAdapter> = IntrusiveXorList::new();
+        for entry in &entries {
+            all.push_back(entry);
+            if entry.value % 2 == 0 {
+                even.push_front(entry);
+            }
+        }
+        check_links(&all);
+        check_links(&even);
+        assert_eq!(values(&all), &[0, 1, 2, 3, 4, 5]);
+        assert_eq!(values(&even), &[4, 2, 0]);
+
+        // removing an element from one list keeps it in the other one
+        {
+            let mut cursor = all.cursor_front_mut();
+            cursor.move_next();
+            cursor.move_next();
+            let removed = cursor.remove_current().unwrap();
+            assert_eq!(removed.value, 2);
+            assert!(!removed.first.is_linked());
+            assert!(removed.second.is_linked());
+        }
+        check_links(&all);
+        assert_eq!(values(&all), &[0, 1, 3, 4, 5]);
+        assert_eq!(values(&even), &[4, 2, 0]);
+
+        all.clear();
+        even.clear();
+        assert!(entries
+            .iter()
+            .all(|e| !e.first.is_linked() && !e.second.is_linked()));
+        assert_eq!(drops.get(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "already linked")]
+    fn test_link_twice() {
+        let drops = Rc::new(Cell::new(0));
+        let entry = Entry::new(0, &drops);
+        let mut m: IntrusiveXorList<FirstAdapter> = IntrusiveXorList::new();
+        m.push_back(&entry);
+        m.push_back(&entry);
+    }
+
+    #[test]
+    fn test_append() {
+        let drops = Rc::new(Cell::new(0));
+        let entries: Vec<Entry> = (0..5).map(|i| Entry::new(i, &drops)).collect();
+        let mut m: IntrusiveXorList<FirstAdapter> = IntrusiveXorList::new();
+        let mut n: IntrusiveXorList<FirstAdapter> = IntrusiveXorList::new();
+        m.append(&mut n);
+        assert!(m.is_empty());
+        n.extend(entries[..2].iter());
+        m.append(&mut n);
+        check_links(&m);
+        assert!(n.is_empty());
+        n.extend(entries[2..].iter());
+        m.append(&mut n);
+        check_links(&m);
+        check_links(&n);
+        assert_eq!(values(&m), &[0, 1, 2, 3, 4]);
+        assert_eq!(
+            m.iter().rev().map(|e| e.value).collect::<Vec<_>>(),
+            &[4, 3, 2, 1, 0]
+        );
+        m.append(&mut n);
+        assert_eq!(m.len(), 5);
+    }
+
+    #[test]
+    fn test_cursor() {
+        let drops = Rc::new(Cell::new(0));
+        let mut m: IntrusiveXorList<BoxAdapter> = IntrusiveXorList::new();
+        m.extend((1..4).map(|i| Box::pin(Entry::new(i, &drops))));
+
+        {
+            let mut cursor = m.cursor_back();
+            assert_eq!(cursor.index(), Some(2));
+            assert_eq!(cursor.current().map(|e| e.value), Some(3));
+            assert_eq!(cursor.peek_prev().map(|e| e.value), Some(2));
+            cursor.move_next();
+            assert_eq!(cursor.index(), None);
+            assert_eq!(cursor.peek_next().map(|e| e.value), Some(1));
+            cursor.move_prev();
+            cursor.move_prev();
+            assert_eq!(cursor.current().map(|e| e.value), Some(2));
+        }
+
+        {
+            let mut cursor = m.cursor_front_mut();
+            cursor.insert_before(Box::pin(Entry::new(0, &drops)));
+            assert_eq!(cursor.index(), Some(1));
+            cursor.insert_after(Box::pin(Entry::new(10, &drops)));
+            assert_eq!(cursor.peek_next().map(|e| e.value), Some(10));
+            cursor.move_prev();
+            cursor.move_prev();
+            assert_eq!(cursor.index(), None);
+            cursor.insert_after(Box::pin(Entry::new(-1, &drops)));
+            cursor.insert_before(Box::pin(Entry::new(4, &drops)));
+            assert_eq!(cursor.peek_prev().map(|e| e.value), Some(4));
+            cursor.move_next();
+            let removed = cursor.remove_current().unwrap();
+            assert_eq!(removed.value, -1);
+            assert_eq!(cursor.current().map(|e| e.value), Some(0));
+            assert_eq!(cursor.as_cursor().index(), Some(0));
+        }
+        check_links(&m);
+        assert_eq!(values(&m), &[0, 1, 10, 2, 3, 4]);
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[test]
+    fn test_split_off() {
+        let drops = Rc::new(Cell::new(0));
+        let entries: Vec<Entry> = (0..5).map(|i| Entry::new(i, &drops)).collect();
+        let mut m: IntrusiveXorList<FirstAdapter> = IntrusiveXorList::new();
+        m.extend(entries.iter());
+
+        let mut n = m.split_off(5);
+        assert!(n.is_empty());
+        n = m.split_off(3);
+        check_links(&m);
+        check_links(&n);
+        assert_eq!(values(&m), &[0, 1, 2]);
+        assert_eq!(values(&n), &[3, 4]);
+
+        let mut o = m.split_off(1);
+        check_links(&m);
+        check_links(&o);
+        assert_eq!(values(&m), &[0]);
+        assert_eq!(values(&o), &[1, 2]);
+
+        let p = o.split_off(0);
+        assert!(o.is_empty());
+        assert_eq!(values(&p), &[1, 2]);
+
+        m.append(&mut n);
+        check_links(&m);
+        assert_eq!(values(&m), &[0, 3, 4]);
+        assert!(entries[2].first.is_linked());
+    }
+
+    #[test]
+    #[should_panic(expected = "nonexistent index")]
+    fn test_split_off_out_of_bounds() {
+        let mut m: IntrusiveXorList<BoxAdapter> = IntrusiveXorList::new();
+        m.split_off(1);
+    }
+
+    #[test]
+    fn test_contains() {
+        let drops = Rc::new(Cell::new(0));
+        let entries: Vec<Entry> = (0..3).map(|i| Entry::new(i, &drops)).collect();
+        let mut m: IntrusiveXorList<FirstAdapter> = IntrusiveXorList::new();
+        m.extend(entries[..2].iter());
+        assert!(m.contains(&entries[0]));
+        assert!(m.contains(&entries[1]));
+        assert!(!m.contains(&entries[2]));
+    }
+
+    #[test]
+    fn test_cursor_splice() {
+        let drops = Rc::new(Cell::new(0));
+        let entries: Vec<Entry> = (0..8).map(|i| Entry::new(i, &drops)).collect();
+        let mut m: IntrusiveXorList<FirstAdapter> = IntrusiveXorList::new();
+        m.extend([&entries[0], &entries[7]]);
+        let mut single: IntrusiveXorList<FirstAdapter> = IntrusiveXorList::new();
+        single.push_back(&entries[1]);
+        let mut pair: IntrusiveXorList<FirstAdapter> = IntrusiveXorList::new();
+        pair.extend(entries[2..4].iter());
+        let mut back: IntrusiveXorList<FirstAdapter> = IntrusiveXorList::new();
+        back.extend(entries[4..7].iter());
+
+        {
+            let mut cursor = m.cursor_front_mut();
+            cursor.splice_after(single);
+            assert_eq!(cursor.peek_next().map(|e| e.value), Some(1));
+            cursor.move_next();
+            cursor.move_next();
+            cursor.splice_before(pair);
+            assert_eq!(cursor.index(), Some(4));
+            assert_eq!(cursor.peek_prev().map(|e| e.value), Some(3));
+            cursor.splice_before(IntrusiveXorList::new());
+            assert_eq!(cursor.index(), Some(4));
+            cursor.splice_after(back);
+            assert_eq!(cursor.current().map(|e| e.value), Some(7));
+            assert_eq!(cursor.back().map(|e| e.value), Some(6));
+        }
+        check_links(&m);
+        assert_eq!(values(&m), &[0, 1, 2, 3, 7, 4, 5, 6]);
+
+        // splicing at the "ghost" non-element
+        let mut front: IntrusiveXorList<FirstAdapter> = IntrusiveXorList::new();
+        front.push_back(m.pop_back().unwrap());
+        let mut tail: IntrusiveXorList<FirstAdapter> = IntrusiveXorList::new();
+        tail.push_back(m.pop_front().unwrap());
+        {
+            let mut cursor = m.cursor_back_mut();
+            cursor.move_next();
+            cursor.splice_after(front);
+            assert_eq!(cursor.index(), None);
+            assert_eq!(cursor.peek_prev().map(|e| e.value), Some(5));
+            cursor.splice_before(tail);
+            assert_eq!(cursor.peek_prev().map(|e| e.value), Some(0));
+            cursor.move_prev();
+            assert_eq!(cursor.index(), Some(7));
+        }
+        check_links(&m);
+        assert_eq!(values(&m), &[6, 1, 2, 3, 7, 4, 5, 0]);
+    }
+
+    #[test]
+    fn test_cursor_split() {
+        let drops = Rc::new(Cell::new(0));
+        let entries: Vec<Entry> = (0..6).map(|i| Entry::new(i, &drops)).collect();
+        let mut m: IntrusiveXorList<FirstAdapter> = IntrusiveXorList::new();
+        m.extend(entries.iter());
+
+        let (before, after) = {
+            let mut cursor = m.cursor_front_mut();
+            cursor.move_next();
+            cursor.move_next();
+            let before = cursor.split_before();
+            assert_eq!(cursor.index(), Some(0));
+            assert!(cursor.peek_prev().is_none());
+            cursor.move_next();
+            let after = cursor.split_after();
+            assert_eq!(cursor.index(), Some(1));
+            assert!(cursor.peek_next().is_none());
+            (before, after)
+        };
+        check_links(&m);
+        check_links(&before);
+        check_links(&after);
+        assert_eq!(values(&before), &[0, 1]);
+        assert_eq!(values(&m), &[2, 3]);
+        assert_eq!(values(&after), &[4, 5]);
+
+        {
+            let mut cursor = m.cursor_front_mut();
+            assert!(cursor.split_before().is_empty());
+            cursor.move_next();
+            assert!(cursor.split_after().is_empty());
+            cursor.move_next();
+            assert_eq!(values(&cursor.split_before()), &[2, 3]);
+            assert!(cursor.split_after().is_empty());
+        }
+        assert!(m.is_empty());
+    }
+
+    #[test]
+    fn test_cursor_push_pop() {
+        let drops = Rc::new(Cell::new(0));
+        let mut m: IntrusiveXorList<BoxAdapter> = IntrusiveXorList::new();
+        {
+            let mut cursor = m.cursor_front_mut();
+            assert!(cursor.front().is_none());
+            assert!(cursor.pop_front().is_none());
+            assert!(cursor.pop_back().is_none());
+            cursor.push_front(Box::pin(Entry::new(2, &drops)));
+            assert_eq!(cursor.index(), None);
+            assert_eq!(cursor.peek_prev().map(|e| e.value), Some(2));
+            cursor.push_back(Box::pin(Entry::new(3, &drops)));
+            assert_eq!(cursor.peek_prev().map(|e| e.value), Some(3));
+            cursor.move_next();
+            assert_eq!(cursor.current().map(|e| e.value), Some(2));
+            cursor.push_front(Box::pin(Entry::new(1, &drops)));
+            assert_eq!(cursor.index(), Some(1));
+            assert_eq!(cursor.peek_prev().map(|e| e.value), Some(1));
+            cursor.push_back(Box::pin(Entry::new(4, &drops)));
+            assert_eq!(cursor.front().map(|e| e.value), Some(1));
+            assert_eq!(cursor.back().map(|e| e.value), Some(4));
+
+            assert_eq!(cursor.pop_front().map(|e| e.value), Some(1));
+            assert_eq!(cursor.index(), Some(0));
+            assert!(cursor.peek_prev().is_none());
+            assert_eq!(cursor.pop_back().map(|e| e.value), Some(4));
+            assert_eq!(cursor.current().map(|e| e.value), Some(2));
+            assert_eq!(cursor.pop_front().map(|e| e.value), Some(2));
+            assert_eq!(cursor.current().map(|e| e.value), Some(3));
+            assert_eq!(cursor.index(), Some(0));
+            cursor.push_front(Box::pin(Entry::new(2, &drops)));
+            assert_eq!(cursor.pop_back().map(|e| e.value), Some(3));
+            assert_eq!(cursor.index(), None);
+            assert_eq!(cursor.peek_prev().map(|e| e.value), Some(2));
+            assert_eq!(cursor.pop_front().map(|e| e.value), Some(2));
+            assert!(cursor.peek_prev().is_none());
+            assert!(cursor.peek_next().is_none());
+        }
+        check_links(&m);
+        assert!(m.is_empty());
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn test_remove_current_as_list() {
+        let drops = Rc::new(Cell::new(0));
+        let mut m: IntrusiveXorList<BoxAdapter> = IntrusiveXorList::new();
+        m.extend((0..3).map(|i| Box::pin(Entry::new(i, &drops))));
+        let removed = {
+            let mut cursor = m.cursor_back_mut();
+            cursor.move_prev();
+            let removed = cursor.remove_current_as_list().unwrap();
+            assert_eq!(cursor.current().map(|e| e.value), Some(2));
+            cursor.move_next();
+            assert!(cursor.remove_current_as_list().is_none());
+            removed
+        };
+        check_links(&m);
+        check_links(&removed);
+        assert_eq!(values(&m), &[0, 2]);
+        assert_eq!(values(&removed), &[1]);
+        drop(removed);
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let drops = Rc::new(Cell::new(0));
+        let mut m: IntrusiveXorList<BoxAdapter> = IntrusiveXorList::new();
+        m.extend((0..5).map(|i| Box::pin(Entry::new(i, &drops))));
+        let mut iter = m.into_iter();
+        assert_eq!(iter.size_hint(), (5, Some(5)));
+        assert_eq!(iter.next().map(|e| e.value), Some(0));
+        assert_eq!(iter.next_back().map(|e| e.value), Some(4));
+        assert_eq!(iter.len(), 3);
+        drop(iter);
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn test_fuzz() {
+        let rounds = if cfg!(miri) { 2 } else { 25 };
+        for _ in 0..rounds {
+            fuzz_test(3);
+            fuzz_test(16);
+            fuzz_test(189);
+        }
+    }
+
+    fn fuzz_test(sz: i32) {
+        let drops = Rc::new(Cell::new(0));
+        let mut m: IntrusiveXorList<BoxAdapter> = IntrusiveXorList::new();
+        let mut v = VecDeque::new();
+        let mut created = 0;
+        for i in 0..sz {
+            check_links(&m);
+            let r: u8 = thread_rng().next_u32() as u8;
+            match r % 6 {
+                0 => {
+                    assert_eq!(m.pop_back().map(|e| e.value), v.pop_back());
+                }
+                1 => {
+                    assert_eq!(m.pop_front().map(|e| e.value), v.pop_front());
+                }
+                2 | 4 => {
+                    m.push_front(Box::pin(Entry::new(-i, &drops)));
+                    v.push_front(-i);
+                    created += 1;
+                }
+                _ => {
+                    m.push_back(Box::pin(Entry::new(i, &drops)));
+                    v.push_back(i);
+                    created += 1;
+                }
+            }
+        }
+
+        check_links(&m);
+        assert_eq!(values(&m), v.iter().cloned().collect::<Vec<_>>());
+        drop(m);
+        assert_eq!(drops.get(), created);
+    }
+}
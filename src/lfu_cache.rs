@@ -0,0 +1,883 @@
+//! A least frequently used cache
+//!
+//! The `LfuCache` implements the O(1) LFU scheme: the entries are grouped into
+//! frequency buckets, which are kept in a [`XorLinkedList`] ordered by their
+//! frequency. Every bucket stores its entries in another `XorLinkedList`
+//! ordered by their last use, so that ties are broken by evicting the least
+//! recently used entry. Accessing an entry moves its node into the next bucket
+//! without any allocation.
+//!
+//! Every bucket and every entry records its predecessor, so that its node can
+//! be unlinked in constant time, see [removing nodes from the middle].
+//!
+//! [`XorLinkedList`]: ../xor_linked_list/struct.XorLinkedList.html
+//! [removing nodes from the middle]: ../xor_linked_list/index.html#removing-nodes-from-the-middle
+
+use lru_cache::{KeyRef, KeyWrapper};
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    fmt,
+    hash::Hash,
+    iter::FusedIterator,
+    mem,
+    ptr::{self, NonNull},
+};
+use xor_linked_list::{self, Node};
+use XorLinkedList;
+
+type EntryNode<K, V> = NonNull<Node<Entry<K, V>>>;
+type BucketNode<K, V> = NonNull<Node<Bucket<K, V>>>;
+
+/// A least frequently used cache
+///
+/// The cache holds at most `cap` entries. When a new entry is put into a full
+/// cache, the entry used the least number of times is evicted and passed to
+/// the callback set with [`set_evict_callback`]. Among entries of the same
+/// frequency, the least recently used one is evicted first. Both [`get`] and
+/// [`put`] count as a use of an entry, [`peek`] does not.
+///
+/// [`set_evict_callback`]: #method.set_evict_callback
+/// [`get`]: #method.get
+/// [`put`]: #method.put
+/// [`peek`]: #method.peek
+pub struct LfuCache<K, V> {
+    map: HashMap<KeyRef<K>, EntryNode<K, V>>,
+    // ordered from the lowest to the highest frequency, never holds an empty bucket
+    buckets: XorLinkedList<Bucket<K, V>>,
+    // links no entries, but keeps the nodes of removed entries for reuse
+    pool: XorLinkedList<Entry<K, V>>,
+    cap: usize,
+    on_evict: Option<Box<dyn FnMut(K, V) + Send>>,
+}
+
+struct Bucket<K, V> {
+    freq: usize,
+    // ordered from the most to the least recently used entry
+    entries: XorLinkedList<Entry<K, V>>,
+    prev: Option<BucketNode<K, V>>,
+}
+
+struct Entry<K, V> {
+    key: K,
+    value: V,
+    prev: Option<EntryNode<K, V>>,
+    bucket: BucketNode<K, V>,
+}
+
+/// An iterator over the entries of a `LfuCache`.
+///
+/// The entries are yielded from the most to the least frequently used one,
+/// entries of the same frequency from the most to the least recently used one.
+/// This `struct` is created by the [`iter`] method on [`LfuCache`]. See its
+/// documentation for more.
+///
+/// [`iter`]: struct.LfuCache.html#method.iter
+/// [`LfuCache`]: struct.LfuCache.html
+pub struct Iter<'a, K: 'a, V: 'a> {
+    buckets: xor_linked_list::Iter<'a, Bucket<K, V>>,
+    front: xor_linked_list::Iter<'a, Entry<K, V>>,
+    back: xor_linked_list::Iter<'a, Entry<K, V>>,
+    len: usize,
+}
+
+impl<'a, K, V> Clone for Iter<'a, K, V> {
+    fn clone(&self) -> Self {
+        Iter {
+            buckets: self.buckets.clone(),
+            front: self.front.clone(),
+            back: self.back.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<'a, K, V> fmt::Debug for Iter<'a, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Iter").field(&self.len).finish()
+    }
+}
+
+// private methods
+impl<K: Hash + Eq, V> LfuCache<K, V> {
+    /// Unlinks the given entry node from its bucket and returns the bucket.
+    unsafe fn unlink_entry(node: EntryNode<K, V>) -> BucketNode<K, V> {
+        let bucket = (*node.as_ptr()).data.bucket;
+        let prev = (*node.as_ptr()).data.prev;
+        let next = Node::get_element(prev, (*node.as_ptr()).reference);
+        (*bucket.as_ptr())
+            .data
+            .entries
+            .unlink_node(prev, node, next);
+        if let Some(next_node) = next {
+            (*next_node.as_ptr()).data.prev = prev;
+        }
+        bucket
+    }
+
+    /// Links the given unlinked entry node as the most recently used entry of
+    /// the given bucket.
+    unsafe fn link_entry(bucket: BucketNode<K, V>, node: EntryNode<K, V>) {
+        (*bucket.as_ptr()).data.entries.push_front_node(node);
+        (*node.as_ptr()).data.prev = None;
+        (*node.as_ptr()).data.bucket = bucket;
+        if let Some(next_node) = Node::<Entry<K, V>>::get_element(None, (*node.as_ptr()).reference)
+        {
+            (*next_node.as_ptr()).data.prev = Some(node);
+        }
+    }
+
+    /// Links a new empty bucket of the given frequency after `prev`, or at the
+    /// front if `prev` is `None`.
+    unsafe fn insert_bucket(
+        &mut self,
+        prev: Option<BucketNode<K, V>>,
+        freq: usize,
+    ) -> BucketNode<K, V> {
+        let node = self.buckets.allocate_node(Bucket {
+            freq,
+            entries: XorLinkedList::new(),
+            prev,
+        });
+        let next = match prev {
+            Some(prev_node) => {
+                let next = Node::get_element(
+                    (*prev_node.as_ptr()).data.prev,
+                    (*prev_node.as_ptr()).reference,
+                );
+                self.buckets.link_node(prev, next, node);
+                next
+            }
+            None => {
+                self.buckets.push_front_node(node);
+                Node::get_element(None, (*node.as_ptr()).reference)
+            }
+        };
+        if let Some(next_node) = next {
+            (*next_node.as_ptr()).data.prev = Some(node);
+        }
+        node
+    }
+
+    /// Unlinks and releases the given bucket if it holds no more entries.
+    unsafe fn remove_bucket_if_empty(&mut self, bucket: BucketNode<K, V>) {
+        if (*bucket.as_ptr()).data.entries.is_empty() {
+            let prev = (*bucket.as_ptr()).data.prev;
+            let next = Node::get_element(prev, (*bucket.as_ptr()).reference);
+            self.buckets.unlink_node(prev, bucket, next);
+            if let Some(next_node) = next {
+                (*next_node.as_ptr()).data.prev = prev;
+            }
+            drop(self.buckets.release_node(bucket));
+        }
+    }
+
+    /// Counts a use of the given entry by moving it into the bucket of the next
+    /// higher frequency.
+    unsafe fn touch(&mut self, node: EntryNode<K, V>) {
+        let bucket = (*node.as_ptr()).data.bucket;
+        let freq = (*bucket.as_ptr()).data.freq + 1;
+        let next = Node::get_element((*bucket.as_ptr()).data.prev, (*bucket.as_ptr()).reference);
+        let target = match next {
+            Some(next_bucket) if (*next_bucket.as_ptr()).data.freq == freq => next_bucket,
+            _ if (*bucket.as_ptr()).data.entries.len() == 1 => {
+                // the bucket would become empty, so it is reused instead
+                (*bucket.as_ptr()).data.freq = freq;
+                return;
+            }
+            _ => self.insert_bucket(Some(bucket), freq),
+        };
+        Self::unlink_entry(node);
+        Self::link_entry(target, node);
+        self.remove_bucket_if_empty(bucket);
+    }
+
+    /// Removes the least frequently used entry and hands it to the eviction
+    /// callback.
+    fn evict_lfu(&mut self) {
+        if let Some((key, value)) = self.pop_lfu() {
+            self.evict(key, value);
+        }
+    }
+
+    /// Hands the given entry to the eviction callback, if there is one.
+    fn evict(&mut self, key: K, value: V) {
+        if let Some(ref mut on_evict) = self.on_evict {
+            on_evict(key, value);
+        }
+    }
+}
+
+impl<K: Hash + Eq, V> LfuCache<K, V> {
+    /// Creates an empty `LfuCache` holding at most `cap` entries
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::LfuCache;
+    ///
+    /// let cache: LfuCache<u32, &str> = LfuCache::new(16);
+    /// assert_eq!(cache.cap(), 16);
+    /// ```
+    pub fn new(cap: usize) -> Self {
+        LfuCache {
+            map: HashMap::with_capacity(cap),
+            buckets: XorLinkedList::new(),
+            pool: XorLinkedList::new(),
+            cap,
+            on_evict: None,
+        }
+    }
+
+    /// Sets the callback which is called with every entry evicted because the
+    /// cache is full or has been resized
+    ///
+    /// Entries which are removed explicitly or whose value is replaced are not
+    /// passed to the callback. A previously set callback is replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::mpsc;
+    /// use rust_utils::LfuCache;
+    ///
+    /// let (sender, receiver) = mpsc::channel();
+    /// let mut cache = LfuCache::new(1);
+    /// cache.set_evict_callback(move |key, value| sender.send((key, value)).unwrap());
+    ///
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    /// assert_eq!(receiver.try_recv(), Ok((1, "a")));
+    /// ```
+    pub fn set_evict_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut(K, V) + Send + 'static,
+    {
+        self.on_evict = Some(Box::new(callback));
+    }
+
+    /// Returns the maximum number of entries of the cache
+    #[inline]
+    pub fn cap(&self) -> usize {
+        self.cap
+    }
+
+    /// Returns the number of entries in the cache
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the cache is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Removes all entries from the cache without passing them to the eviction
+    /// callback.
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.buckets.clear();
+    }
+
+    /// Puts a value into the cache and counts it as a use of its entry.
+    ///
+    /// If the cache already contained the key, its value is replaced and the old
+    /// value is returned. Otherwise, if the cache is full, the least frequently
+    /// used entry is evicted and the new entry starts with a frequency of 1.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::LfuCache;
+    ///
+    /// let mut cache = LfuCache::new(2);
+    ///
+    /// assert_eq!(cache.put(1, "a"), None);
+    /// assert_eq!(cache.put(2, "b"), None);
+    /// assert_eq!(cache.put(1, "c"), Some("a"));
+    /// assert_eq!(cache.put(3, "d"), None);
+    ///
+    /// assert_eq!(cache.peek(&2), None);
+    /// assert_eq!(cache.frequency(&1), Some(2));
+    /// assert_eq!(cache.frequency(&3), Some(1));
+    /// ```
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(&node) = self.map.get(KeyWrapper::from_ref(&key)) {
+            unsafe {
+                self.touch(node);
+                return Some(mem::replace(&mut (*node.as_ptr()).data.value, value));
+            }
+        }
+        if self.cap == 0 {
+            // a cache without room evicts every new entry right away
+            self.evict(key, value);
+            return None;
+        }
+        if self.len() >= self.cap {
+            self.evict_lfu();
+        }
+        let bucket = match self.buckets.front_node() {
+            Some(bucket) if unsafe { (*bucket.as_ptr()).data.freq } == 1 => bucket,
+            _ => unsafe { self.insert_bucket(None, 1) },
+        };
+        let node = self.pool.allocate_node(Entry {
+            key,
+            value,
+            prev: None,
+            bucket,
+        });
+        unsafe {
+            Self::link_entry(bucket, node);
+            self.map.insert(
+                KeyRef::new(ptr::addr_of_mut!((*node.as_ptr()).data.key)),
+                node,
+            );
+        }
+        None
+    }
+
+    /// Returns a reference to the value of the given key and counts it as a use
+    /// of its entry.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::LfuCache;
+    ///
+    /// let mut cache = LfuCache::new(2);
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    ///
+    /// assert_eq!(cache.get(&1), Some(&"a"));
+    /// cache.put(3, "c");
+    /// assert_eq!(cache.get(&2), None);
+    /// ```
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node = *self.map.get(KeyWrapper::from_ref(key))?;
+        unsafe {
+            self.touch(node);
+            Some(&(*node.as_ptr()).data.value)
+        }
+    }
+
+    /// Returns a mutable reference to the value of the given key and counts it
+    /// as a use of its entry.
+    ///
+    /// This operation should compute in O(1) time.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node = *self.map.get(KeyWrapper::from_ref(key))?;
+        unsafe {
+            self.touch(node);
+            Some(&mut (*node.as_ptr()).data.value)
+        }
+    }
+
+    /// Returns a reference to the value of the given key without counting it as
+    /// a use.
+    ///
+    /// This operation should compute in O(1) time.
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map
+            .get(KeyWrapper::from_ref(key))
+            .map(|node| unsafe { &(*node.as_ptr()).data.value })
+    }
+
+    /// Returns the entry which would be evicted next without counting it as a
+    /// use, or `None` if the cache is empty.
+    ///
+    /// This operation should compute in O(1) time.
+    pub fn peek_lfu(&self) -> Option<(&K, &V)> {
+        self.buckets
+            .front()
+            .and_then(|bucket| bucket.entries.back())
+            .map(|entry| (&entry.key, &entry.value))
+    }
+
+    /// Returns the number of uses of the given key, or `None` if the cache does
+    /// not contain the key.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::LfuCache;
+    ///
+    /// let mut cache = LfuCache::new(2);
+    /// cache.put(1, "a");
+    /// cache.get(&1);
+    /// cache.peek(&1);
+    ///
+    /// assert_eq!(cache.frequency(&1), Some(2));
+    /// assert_eq!(cache.frequency(&2), None);
+    /// ```
+    pub fn frequency<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map
+            .get(KeyWrapper::from_ref(key))
+            .map(|node| unsafe { (*(*node.as_ptr()).data.bucket.as_ptr()).data.freq })
+    }
+
+    /// Returns `true` if the cache contains the given key. It does not count as
+    /// a use of the entry.
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(KeyWrapper::from_ref(key))
+    }
+
+    /// Removes the entry of the given key and returns its value, or `None` if
+    /// the cache does not contain the key.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::LfuCache;
+    ///
+    /// let mut cache = LfuCache::new(2);
+    /// cache.put(1, "a");
+    ///
+    /// assert_eq!(cache.pop(&1), Some("a"));
+    /// assert_eq!(cache.pop(&1), None);
+    /// ```
+    pub fn pop<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node = self.map.remove(KeyWrapper::from_ref(key))?;
+        unsafe {
+            let bucket = Self::unlink_entry(node);
+            self.remove_bucket_if_empty(bucket);
+            Some(self.pool.release_node(node).value)
+        }
+    }
+
+    /// Removes the least frequently used entry and returns it, or `None` if the
+    /// cache is empty. Among entries of the same frequency, the least recently
+    /// used one is removed.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::LfuCache;
+    ///
+    /// let mut cache = LfuCache::new(3);
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    /// cache.put(3, "c");
+    /// cache.get(&1);
+    ///
+    /// assert_eq!(cache.pop_lfu(), Some((2, "b")));
+    /// assert_eq!(cache.pop_lfu(), Some((3, "c")));
+    /// assert_eq!(cache.pop_lfu(), Some((1, "a")));
+    /// assert_eq!(cache.pop_lfu(), None);
+    /// ```
+    pub fn pop_lfu(&mut self) -> Option<(K, V)> {
+        let bucket = self.buckets.front_node()?;
+        unsafe {
+            // the predecessor of the last entry stays the same
+            let node = (*bucket.as_ptr()).data.entries.pop_back_node()?;
+            self.remove_bucket_if_empty(bucket);
+            self.map
+                .remove(KeyWrapper::from_ref(&(*node.as_ptr()).data.key));
+            let entry = self.pool.release_node(node);
+            Some((entry.key, entry.value))
+        }
+    }
+
+    /// Changes the maximum number of entries of the cache. If the cache holds
+    /// more entries than the new maximum, the least frequently used ones are
+    /// evicted.
+    ///
+    /// This operation should compute in O(m) time, where `m` is the number of
+    /// evicted entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::LfuCache;
+    ///
+    /// let mut cache = LfuCache::new(3);
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    /// cache.put(3, "c");
+    /// cache.get(&2);
+    ///
+    /// cache.resize(1);
+    /// assert_eq!(cache.len(), 1);
+    /// assert_eq!(cache.peek(&2), Some(&"b"));
+    /// ```
+    pub fn resize(&mut self, cap: usize) {
+        self.cap = cap;
+        while self.len() > cap {
+            self.evict_lfu();
+        }
+        self.pool.shrink_to_fit();
+    }
+
+    /// Provides an iterator over the entries from the most to the least
+    /// frequently used one. Entries of the same frequency are yielded from the
+    /// most to the least recently used one. It does not count as a use of the
+    /// entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::LfuCache;
+    ///
+    /// let mut cache = LfuCache::new(3);
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    /// cache.put(3, "c");
+    /// cache.get(&1);
+    ///
+    /// let keys: Vec<u32> = cache.iter().map(|(key, _)| *key).collect();
+    /// assert_eq!(keys, vec![1, 3, 2]);
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            buckets: self.buckets.iter(),
+            front: xor_linked_list::Iter::default(),
+            back: xor_linked_list::Iter::default(),
+            len: self.len(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        loop {
+            if let Some(entry) = self.front.next() {
+                self.len -= 1;
+                return Some((&entry.key, &entry.value));
+            }
+            match self.buckets.next_back() {
+                Some(bucket) => self.front = bucket.entries.iter(),
+                None => {
+                    let entry = self.back.next()?;
+                    self.len -= 1;
+                    return Some((&entry.key, &entry.value));
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<(&'a K, &'a V)> {
+        loop {
+            if let Some(entry) = self.back.next_back() {
+                self.len -= 1;
+                return Some((&entry.key, &entry.value));
+            }
+            match self.buckets.next() {
+                Some(bucket) => self.back = bucket.entries.iter(),
+                None => {
+                    let entry = self.front.next_back()?;
+                    self.len -= 1;
+                    return Some((&entry.key, &entry.value));
+                }
+            }
+        }
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+impl<'a, K, V> FusedIterator for Iter<'a, K, V> {}
+
+impl<'a, K: Hash + Eq, V> IntoIterator for &'a LfuCache<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<K: Hash + Eq + fmt::Debug, V: fmt::Debug> fmt::Debug for LfuCache<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+unsafe impl<K: Send, V: Send> Send for LfuCache<K, V> {}
+
+// The eviction callback is only accessed through `&mut self`
+unsafe impl<K: Sync, V: Sync> Sync for LfuCache<K, V> {}
+
+unsafe impl<'a, K: Sync, V: Sync> Send for Iter<'a, K, V> {}
+
+unsafe impl<'a, K: Sync, V: Sync> Sync for Iter<'a, K, V> {}
+
+#[cfg(test)]
+mod tests {
+    use super::LfuCache;
+    use lru_cache::KeyWrapper;
+    use rand::{thread_rng, RngCore};
+    use std::mem;
+    use std::sync::{Arc, Mutex};
+    use std::vec::Vec;
+
+    fn check_buckets(cache: &LfuCache<u32, u32>) {
+        // the recorded predecessors and buckets have to match the lists
+        let mut expected_bucket_prev = None;
+        let mut last_freq = 0;
+        let mut count = 0;
+        for bucket in cache.buckets.iter() {
+            assert!(bucket.freq > last_freq);
+            assert!(!bucket.entries.is_empty());
+            last_freq = bucket.freq;
+            let mut expected_prev = None;
+            for entry in bucket.entries.iter() {
+                let node = cache.map[KeyWrapper::from_ref(&entry.key)];
+                unsafe {
+                    assert_eq!((*node.as_ptr()).data.prev, expected_prev);
+                    let bucket_node = (*node.as_ptr()).data.bucket;
+                    assert_eq!((*bucket_node.as_ptr()).data.prev, expected_bucket_prev);
+                    assert_eq!((*bucket_node.as_ptr()).data.freq, bucket.freq);
+                }
+                expected_prev = Some(node);
+                count += 1;
+            }
+            expected_bucket_prev = unsafe { Some((*expected_prev.unwrap().as_ptr()).data.bucket) };
+        }
+        assert_eq!(cache.len(), count);
+        assert!(cache.len() <= cache.cap());
+    }
+
+    fn keys(cache: &LfuCache<u32, u32>) -> Vec<u32> {
+        cache.iter().map(|(key, _)| *key).collect()
+    }
+
+    #[test]
+    fn test_put_and_get() {
+        let mut cache = LfuCache::new(3);
+        assert_eq!(cache.put(1, 10), None);
+        assert_eq!(cache.put(2, 20), None);
+        assert_eq!(cache.put(3, 30), None);
+        check_buckets(&cache);
+        assert_eq!(keys(&cache), &[3, 2, 1]);
+
+        assert_eq!(cache.get(&1), Some(&10));
+        assert_eq!(cache.get(&1), Some(&10));
+        check_buckets(&cache);
+        assert_eq!(keys(&cache), &[1, 3, 2]);
+        assert_eq!(cache.frequency(&1), Some(3));
+
+        *cache.get_mut(&2).unwrap() += 1;
+        check_buckets(&cache);
+        assert_eq!(keys(&cache), &[1, 2, 3]);
+        assert_eq!(cache.put(3, 31), Some(30));
+        check_buckets(&cache);
+        assert_eq!(keys(&cache), &[1, 3, 2]);
+        assert_eq!(cache.peek_lfu(), Some((&2, &21)));
+
+        assert_eq!(cache.put(4, 40), None);
+        check_buckets(&cache);
+        assert_eq!(keys(&cache), &[1, 3, 4]);
+        assert_eq!(cache.peek(&2), None);
+        assert_eq!(cache.peek(&4), Some(&40));
+        assert_eq!(cache.frequency(&4), Some(1));
+        assert!(cache.contains(&3));
+        assert!(!cache.contains(&2));
+
+        let reversed: Vec<u32> = cache.iter().rev().map(|(key, _)| *key).collect();
+        assert_eq!(reversed, &[4, 3, 1]);
+        let mut iter = cache.iter();
+        assert_eq!(iter.next(), Some((&1, &10)));
+        assert_eq!(iter.next_back(), Some((&4, &40)));
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.next_back(), Some((&3, &31)));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_pop() {
+        let mut cache = LfuCache::new(4);
+        for i in 0..4 {
+            cache.put(i, i * 10);
+            for _ in 0..i {
+                cache.get(&i);
+            }
+        }
+        check_buckets(&cache);
+        assert_eq!(cache.pop(&2), Some(20));
+        check_buckets(&cache);
+        assert_eq!(cache.pop(&2), None);
+        assert_eq!(cache.pop_lfu(), Some((0, 0)));
+        check_buckets(&cache);
+        assert_eq!(cache.pop_lfu(), Some((1, 10)));
+        assert_eq!(cache.pop_lfu(), Some((3, 30)));
+        assert_eq!(cache.pop_lfu(), None);
+        assert!(cache.is_empty());
+        assert!(cache.buckets.is_empty());
+    }
+
+    #[test]
+    fn test_evict_callback_and_resize() {
+        let evicted = Arc::new(Mutex::new(Vec::new()));
+        let mut cache = LfuCache::new(2);
+        let sink = Arc::clone(&evicted);
+        cache.set_evict_callback(move |key, value| sink.lock().unwrap().push((key, value)));
+
+        cache.put(1, 10);
+        cache.put(2, 20);
+        cache.put(2, 21);
+        cache.put(3, 30);
+        cache.pop(&2);
+        assert_eq!(*evicted.lock().unwrap(), &[(1, 10)]);
+
+        cache.resize(4);
+        for i in 4..7 {
+            cache.put(i, i * 10);
+            cache.get(&i);
+        }
+        cache.get(&6);
+        assert_eq!(keys(&cache), &[6, 5, 4, 3]);
+        cache.resize(1);
+        check_buckets(&cache);
+        assert_eq!(keys(&cache), &[6]);
+        assert_eq!(
+            *evicted.lock().unwrap(),
+            &[(1, 10), (3, 30), (4, 40), (5, 50)]
+        );
+        assert_eq!(cache.pool.capacity(), 0);
+
+        cache.resize(0);
+        cache.put(7, 70);
+        assert!(cache.is_empty());
+        assert_eq!(evicted.lock().unwrap().len(), 6);
+    }
+
+    #[test]
+    fn test_borrowed_keys() {
+        let mut cache = LfuCache::new(2);
+        cache.put(String::from("a"), 1);
+        cache.put(String::from("b"), 2);
+        assert_eq!(cache.get("a"), Some(&1));
+        assert_eq!(cache.frequency("a"), Some(2));
+        assert_eq!(format!("{:?}", cache), r#"{"a": 1, "b": 2}"#);
+        assert_eq!(cache.pop("b"), Some(2));
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.peek("a"), None);
+    }
+
+    #[test]
+    fn test_keys_without_clone() {
+        #[derive(Debug, PartialEq, Eq, Hash)]
+        struct Key(u32);
+
+        let mut cache = LfuCache::new(2);
+        cache.put(Key(1), 1);
+        cache.put(Key(2), 2);
+        assert_eq!(cache.put(Key(1), 3), Some(1));
+        cache.put(Key(3), 4);
+        assert!(!cache.contains(&Key(2)));
+        assert_eq!(cache.frequency(&Key(1)), Some(2));
+        assert_eq!(cache.pop_lfu(), Some((Key(3), 4)));
+    }
+
+    #[test]
+    fn test_fuzz() {
+        let rounds = if cfg!(miri) { 2 } else { 25 };
+        for _ in 0..rounds {
+            fuzz_test(1);
+            fuzz_test(8);
+            fuzz_test(64);
+        }
+    }
+
+    // compares the cache against a vector of `(key, value, frequency, last use)`
+    fn fuzz_test(cap: usize) {
+        let mut cache = LfuCache::new(cap);
+        let mut v: Vec<(u32, u32, usize, u32)> = Vec::new();
+        for i in 0..500 {
+            let r = thread_rng().next_u32();
+            let key = r / 8 % (2 * cap as u32);
+            let lfu = (0..v.len()).min_by_key(|&at| (v[at].2, v[at].3));
+            let found = v.iter().position(|e| e.0 == key);
+            match r % 8 {
+                0 => {
+                    let expected = lfu.map(|at| v.remove(at)).map(|e| (e.0, e.1));
+                    assert_eq!(cache.pop_lfu(), expected);
+                }
+                1 => {
+                    let expected = found.map(|at| v.remove(at).1);
+                    assert_eq!(cache.pop(&key), expected);
+                }
+                2 | 3 => {
+                    let expected = found.map(|at| {
+                        v[at].2 += 1;
+                        v[at].3 = i;
+                        v[at].1
+                    });
+                    assert_eq!(cache.get(&key).cloned(), expected);
+                }
+                _ => {
+                    let expected = match found {
+                        Some(at) => {
+                            v[at].2 += 1;
+                            v[at].3 = i;
+                            Some(mem::replace(&mut v[at].1, i))
+                        }
+                        None => {
+                            if v.len() == cap {
+                                v.remove(lfu.unwrap());
+                            }
+                            v.push((key, i, 1, i));
+                            None
+                        }
+                    };
+                    assert_eq!(cache.put(key, i), expected);
+                }
+            }
+            check_buckets(&cache);
+            assert_eq!(
+                cache.frequency(&key),
+                v.iter().find(|e| e.0 == key).map(|e| e.2)
+            );
+        }
+        v.sort_by_key(|e| (usize::MAX - e.2, u32::MAX - e.3));
+        let entries: Vec<(u32, u32)> = cache.iter().map(|(key, value)| (*key, *value)).collect();
+        let expected: Vec<(u32, u32)> = v.iter().map(|e| (e.0, e.1)).collect();
+        assert_eq!(entries, expected);
+    }
+}
@@ -20,29 +20,62 @@
     variant_size_differences
 )]
 // enable additional clippy warnings
-#![cfg_attr(feature = "cargo-clippy", warn(int_plus_one))]
-#![cfg_attr(
-    feature = "cargo-clippy",
-    warn(shadow_reuse, shadow_same, shadow_unrelated)
-)]
-#![cfg_attr(feature = "cargo-clippy", warn(mut_mut))]
-#![cfg_attr(feature = "cargo-clippy", warn(nonminimal_bool))]
-#![cfg_attr(feature = "cargo-clippy", warn(pub_enum_variant_names))]
-#![cfg_attr(feature = "cargo-clippy", warn(range_plus_one))]
-#![cfg_attr(
-    feature = "cargo-clippy",
-    warn(string_add, string_add_assign)
+#![warn(
+    clippy::int_plus_one,
+    clippy::shadow_reuse,
+    clippy::shadow_same,
+    clippy::shadow_unrelated,
+    clippy::mut_mut,
+    clippy::nonminimal_bool,
+    clippy::range_plus_one,
+    clippy::string_add,
+    clippy::string_add_assign,
+    clippy::module_name_repetitions,
+    clippy::unwrap_used
 )]
-#![cfg_attr(feature = "cargo-clippy", warn(stutter))]
-#![cfg_attr(feature = "cargo-clippy", warn(result_unwrap_used))]
 
+#[cfg(feature = "allocator-api2")]
+extern crate allocator_api2;
+#[cfg(all(test, feature = "allocator-api2"))]
+extern crate bumpalo;
 #[cfg(test)]
 extern crate rand;
 
+pub mod alloc;
+pub mod arena_xor_linked_list;
+pub mod bounded_xor_list;
 pub mod btrie;
+pub mod chunked_buffer;
+pub mod circular_xor_list;
+pub mod handle_list;
+pub mod intrusive_xor_list;
+pub mod lfu_cache;
+pub mod lru_cache;
+pub mod timer_wheel;
+pub mod unrolled_xor_list;
 pub mod xor_linked_list;
 
+#[doc(inline)]
+pub use self::arena_xor_linked_list::ArenaXorLinkedList;
+#[doc(inline)]
+pub use self::bounded_xor_list::BoundedXorList;
 #[doc(inline)]
 pub use self::btrie::BTrieMap;
 #[doc(inline)]
+pub use self::chunked_buffer::ChunkedBuffer;
+#[doc(inline)]
+pub use self::circular_xor_list::CircularXorList;
+#[doc(inline)]
+pub use self::handle_list::HandleList;
+#[doc(inline)]
+pub use self::intrusive_xor_list::IntrusiveXorList;
+#[doc(inline)]
+pub use self::lfu_cache::LfuCache;
+#[doc(inline)]
+pub use self::lru_cache::LruCache;
+#[doc(inline)]
+pub use self::timer_wheel::TimerWheel;
+#[doc(inline)]
+pub use self::unrolled_xor_list::UnrolledXorList;
+#[doc(inline)]
 pub use self::xor_linked_list::XorLinkedList;
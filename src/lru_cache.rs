@@ -0,0 +1,768 @@
+//! A least recently used cache
+//!
+//! The `LruCache` combines a `HashMap` index with a [`XorLinkedList`] which
+//! keeps the entries in the order of their last use. All operations, including
+//! promoting an entry on access, compute in O(1) time. To promote an entry
+//! without a search, every entry records the node of its more recently used
+//! neighbour, see [removing nodes from the middle].
+//!
+//! [`XorLinkedList`]: ../xor_linked_list/struct.XorLinkedList.html
+//! [removing nodes from the middle]: ../xor_linked_list/index.html#removing-nodes-from-the-middle
+
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    mem,
+    ptr::{self, NonNull},
+};
+use xor_linked_list::{self, Node};
+use XorLinkedList;
+
+type Link<K, V> = Option<NonNull<Node<Entry<K, V>>>>;
+
+/// A least recently used cache
+///
+/// The cache holds at most `cap` entries. When a new entry is put into a full
+/// cache, the least recently used entry is evicted and passed to the callback
+/// set with [`set_evict_callback`]. Both [`get`] and [`put`] mark an entry as
+/// most recently used, [`peek`] does not.
+///
+/// [`set_evict_callback`]: #method.set_evict_callback
+/// [`get`]: #method.get
+/// [`put`]: #method.put
+/// [`peek`]: #method.peek
+pub struct LruCache<K, V> {
+    map: HashMap<KeyRef<K>, NonNull<Node<Entry<K, V>>>>,
+    // ordered from the most to the least recently used entry
+    list: XorLinkedList<Entry<K, V>>,
+    cap: usize,
+    on_evict: Option<Box<dyn FnMut(K, V) + Send>>,
+}
+
+struct Entry<K, V> {
+    key: K,
+    value: V,
+    prev: Link<K, V>,
+}
+
+/// A pointer to a key stored in a node, so that the index of a cache does not
+/// need a copy of every key. It must be removed from the index before its node
+/// is released.
+///
+/// The pointer has to be derived from a raw pointer to the node rather than
+/// from a reference, as a reference would be invalidated as soon as the
+/// neighbours of the node are relinked.
+pub(crate) struct KeyRef<K>(NonNull<K>);
+
+/// Wraps the borrowed form of a key to look up `KeyRef`s, as `KeyRef<K>`
+/// cannot implement `Borrow<Q>` for all `Q` which `K` implements it for.
+#[repr(transparent)]
+pub(crate) struct KeyWrapper<Q: ?Sized>(Q);
+
+impl<K> KeyRef<K> {
+    /// Creates a `KeyRef` from a pointer to a key stored in a node.
+    ///
+    /// The pointer must not be null.
+    #[inline]
+    pub(crate) unsafe fn new(key: *mut K) -> Self {
+        KeyRef(NonNull::new_unchecked(key))
+    }
+}
+
+impl<K: Hash> Hash for KeyRef<K> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        unsafe { self.0.as_ref() }.hash(state)
+    }
+}
+
+impl<K: PartialEq> PartialEq for KeyRef<K> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        unsafe { self.0.as_ref() == other.0.as_ref() }
+    }
+}
+
+impl<K: Eq> Eq for KeyRef<K> {}
+
+impl<K, Q> Borrow<KeyWrapper<Q>> for KeyRef<K>
+where
+    K: Borrow<Q>,
+    Q: ?Sized,
+{
+    #[inline]
+    fn borrow(&self) -> &KeyWrapper<Q> {
+        KeyWrapper::from_ref(unsafe { self.0.as_ref() }.borrow())
+    }
+}
+
+impl<Q: ?Sized> KeyWrapper<Q> {
+    #[inline]
+    pub(crate) fn from_ref(key: &Q) -> &Self {
+        // `KeyWrapper` is a transparent wrapper around `Q`
+        let ptr: *const Q = key;
+        unsafe { &*(ptr as *const KeyWrapper<Q>) }
+    }
+}
+
+impl<Q: Hash + ?Sized> Hash for KeyWrapper<Q> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl<Q: PartialEq + ?Sized> PartialEq for KeyWrapper<Q> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<Q: Eq + ?Sized> Eq for KeyWrapper<Q> {}
+
+/// An iterator over the entries of a `LruCache`.
+///
+/// The entries are yielded from the most to the least recently used one. This
+/// `struct` is created by the [`iter`] method on [`LruCache`]. See its
+/// documentation for more.
+///
+/// [`iter`]: struct.LruCache.html#method.iter
+/// [`LruCache`]: struct.LruCache.html
+pub struct Iter<'a, K: 'a, V: 'a> {
+    iter: xor_linked_list::Iter<'a, Entry<K, V>>,
+}
+
+impl<'a, K, V> Clone for Iter<'a, K, V> {
+    fn clone(&self) -> Self {
+        Iter {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> fmt::Debug for Iter<'a, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Iter").field(&self.iter.len()).finish()
+    }
+}
+
+// private methods
+impl<K: Hash + Eq, V> LruCache<K, V> {
+    /// Unlinks the given node from the recency list, keeping its node.
+    unsafe fn unlink(&mut self, node: NonNull<Node<Entry<K, V>>>) {
+        let prev = (*node.as_ptr()).data.prev;
+        let next = Node::get_element(prev, (*node.as_ptr()).reference);
+        self.list.unlink_node(prev, node, next);
+        if let Some(next_node) = next {
+            (*next_node.as_ptr()).data.prev = prev;
+        }
+    }
+
+    /// Links the given unlinked node as the most recently used one.
+    unsafe fn push_front(&mut self, node: NonNull<Node<Entry<K, V>>>) {
+        self.list.push_front_node(node);
+        (*node.as_ptr()).data.prev = None;
+        if let Some(next_node) = Node::<Entry<K, V>>::get_element(None, (*node.as_ptr()).reference)
+        {
+            (*next_node.as_ptr()).data.prev = Some(node);
+        }
+    }
+
+    /// Marks the entry of the given node as most recently used.
+    unsafe fn promote(&mut self, node: NonNull<Node<Entry<K, V>>>) {
+        if (*node.as_ptr()).data.prev.is_some() {
+            self.unlink(node);
+            self.push_front(node);
+        }
+    }
+
+    /// Removes the least recently used entry and hands it to the eviction
+    /// callback.
+    fn evict_lru(&mut self) {
+        if let Some((key, value)) = self.pop_lru() {
+            self.evict(key, value);
+        }
+    }
+
+    /// Hands the given entry to the eviction callback, if there is one.
+    fn evict(&mut self, key: K, value: V) {
+        if let Some(ref mut on_evict) = self.on_evict {
+            on_evict(key, value);
+        }
+    }
+}
+
+impl<K: Hash + Eq, V> LruCache<K, V> {
+    /// Creates an empty `LruCache` holding at most `cap` entries
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::LruCache;
+    ///
+    /// let cache: LruCache<u32, &str> = LruCache::new(16);
+    /// assert_eq!(cache.cap(), 16);
+    /// ```
+    pub fn new(cap: usize) -> Self {
+        LruCache {
+            map: HashMap::with_capacity(cap),
+            list: XorLinkedList::new(),
+            cap,
+            on_evict: None,
+        }
+    }
+
+    /// Sets the callback which is called with every entry evicted because the
+    /// cache is full or has been resized
+    ///
+    /// Entries which are removed explicitly or whose value is replaced are not
+    /// passed to the callback. A previously set callback is replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::mpsc;
+    /// use rust_utils::LruCache;
+    ///
+    /// let (sender, receiver) = mpsc::channel();
+    /// let mut cache = LruCache::new(1);
+    /// cache.set_evict_callback(move |key, value| sender.send((key, value)).unwrap());
+    ///
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    /// assert_eq!(receiver.try_recv(), Ok((1, "a")));
+    /// ```
+    pub fn set_evict_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut(K, V) + Send + 'static,
+    {
+        self.on_evict = Some(Box::new(callback));
+    }
+
+    /// Returns the maximum number of entries of the cache
+    #[inline]
+    pub fn cap(&self) -> usize {
+        self.cap
+    }
+
+    /// Returns the number of entries in the cache
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Returns `true` if the cache is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Removes all entries from the cache without passing them to the eviction
+    /// callback.
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.list.clear();
+    }
+
+    /// Puts a value into the cache and marks its entry as most recently used.
+    ///
+    /// If the cache already contained the key, its value is replaced and the old
+    /// value is returned. Otherwise, if the cache is full, the least recently
+    /// used entry is evicted.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::LruCache;
+    ///
+    /// let mut cache = LruCache::new(2);
+    ///
+    /// assert_eq!(cache.put(1, "a"), None);
+    /// assert_eq!(cache.put(2, "b"), None);
+    /// assert_eq!(cache.put(1, "c"), Some("a"));
+    /// assert_eq!(cache.put(3, "d"), None);
+    ///
+    /// assert_eq!(cache.peek(&2), None);
+    /// assert_eq!(cache.peek(&1), Some(&"c"));
+    /// ```
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(&node) = self.map.get(KeyWrapper::from_ref(&key)) {
+            unsafe {
+                self.promote(node);
+                return Some(mem::replace(&mut (*node.as_ptr()).data.value, value));
+            }
+        }
+        if self.cap == 0 {
+            // a cache without room evicts every new entry right away
+            self.evict(key, value);
+            return None;
+        }
+        if self.len() >= self.cap {
+            self.evict_lru();
+        }
+        let node = self.list.allocate_node(Entry {
+            key,
+            value,
+            prev: None,
+        });
+        unsafe {
+            self.push_front(node);
+            self.map.insert(
+                KeyRef::new(ptr::addr_of_mut!((*node.as_ptr()).data.key)),
+                node,
+            );
+        }
+        None
+    }
+
+    /// Returns a reference to the value of the given key and marks its entry as
+    /// most recently used.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::LruCache;
+    ///
+    /// let mut cache = LruCache::new(2);
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    ///
+    /// assert_eq!(cache.get(&1), Some(&"a"));
+    /// cache.put(3, "c");
+    /// assert_eq!(cache.get(&2), None);
+    /// ```
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node = *self.map.get(KeyWrapper::from_ref(key))?;
+        unsafe {
+            self.promote(node);
+            Some(&(*node.as_ptr()).data.value)
+        }
+    }
+
+    /// Returns a mutable reference to the value of the given key and marks its
+    /// entry as most recently used.
+    ///
+    /// This operation should compute in O(1) time.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node = *self.map.get(KeyWrapper::from_ref(key))?;
+        unsafe {
+            self.promote(node);
+            Some(&mut (*node.as_ptr()).data.value)
+        }
+    }
+
+    /// Returns a reference to the value of the given key without marking its
+    /// entry as used.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::LruCache;
+    ///
+    /// let mut cache = LruCache::new(2);
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    ///
+    /// assert_eq!(cache.peek(&1), Some(&"a"));
+    /// cache.put(3, "c");
+    /// assert_eq!(cache.peek(&1), None);
+    /// ```
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map
+            .get(KeyWrapper::from_ref(key))
+            .map(|node| unsafe { &(*node.as_ptr()).data.value })
+    }
+
+    /// Returns the least recently used entry without marking it as used, or
+    /// `None` if the cache is empty.
+    ///
+    /// This operation should compute in O(1) time.
+    pub fn peek_lru(&self) -> Option<(&K, &V)> {
+        self.list.back().map(|entry| (&entry.key, &entry.value))
+    }
+
+    /// Returns `true` if the cache contains the given key. The entry is not
+    /// marked as used.
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(KeyWrapper::from_ref(key))
+    }
+
+    /// Removes the entry of the given key and returns its value, or `None` if
+    /// the cache does not contain the key.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::LruCache;
+    ///
+    /// let mut cache = LruCache::new(2);
+    /// cache.put(1, "a");
+    ///
+    /// assert_eq!(cache.pop(&1), Some("a"));
+    /// assert_eq!(cache.pop(&1), None);
+    /// ```
+    pub fn pop<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node = self.map.remove(KeyWrapper::from_ref(key))?;
+        unsafe {
+            self.unlink(node);
+            Some(self.list.release_node(node).value)
+        }
+    }
+
+    /// Removes the least recently used entry and returns it, or `None` if the
+    /// cache is empty.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::LruCache;
+    ///
+    /// let mut cache = LruCache::new(3);
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    /// cache.get(&1);
+    ///
+    /// assert_eq!(cache.pop_lru(), Some((2, "b")));
+    /// assert_eq!(cache.pop_lru(), Some((1, "a")));
+    /// assert_eq!(cache.pop_lru(), None);
+    /// ```
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        // the predecessor of the tail stays the same
+        let node = self.list.pop_back_node()?;
+        unsafe {
+            self.map
+                .remove(KeyWrapper::from_ref(&(*node.as_ptr()).data.key));
+            let entry = self.list.release_node(node);
+            Some((entry.key, entry.value))
+        }
+    }
+
+    /// Changes the maximum number of entries of the cache. If the cache holds
+    /// more entries than the new maximum, the least recently used ones are
+    /// evicted.
+    ///
+    /// This operation should compute in O(m) time, where `m` is the number of
+    /// evicted entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::LruCache;
+    ///
+    /// let mut cache = LruCache::new(3);
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    /// cache.put(3, "c");
+    ///
+    /// cache.resize(1);
+    /// assert_eq!(cache.len(), 1);
+    /// assert_eq!(cache.peek(&3), Some(&"c"));
+    /// ```
+    pub fn resize(&mut self, cap: usize) {
+        self.cap = cap;
+        while self.len() > cap {
+            self.evict_lru();
+        }
+        self.list.shrink_to_fit();
+    }
+
+    /// Provides an iterator over the entries from the most to the least recently
+    /// used one. The entries are not marked as used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::LruCache;
+    ///
+    /// let mut cache = LruCache::new(3);
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    /// cache.put(3, "c");
+    /// cache.get(&1);
+    ///
+    /// let keys: Vec<u32> = cache.iter().map(|(key, _)| *key).collect();
+    /// assert_eq!(keys, vec![1, 3, 2]);
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            iter: self.list.iter(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        self.iter.next().map(|entry| (&entry.key, &entry.value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(&'a K, &'a V)> {
+        self.iter
+            .next_back()
+            .map(|entry| (&entry.key, &entry.value))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+impl<'a, K, V> FusedIterator for Iter<'a, K, V> {}
+
+impl<'a, K: Hash + Eq, V> IntoIterator for &'a LruCache<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for LruCache<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(self.list.iter().map(|entry| (&entry.key, &entry.value)))
+            .finish()
+    }
+}
+
+unsafe impl<K: Send, V: Send> Send for LruCache<K, V> {}
+
+// The eviction callback is only accessed through `&mut self`
+unsafe impl<K: Sync, V: Sync> Sync for LruCache<K, V> {}
+
+unsafe impl<'a, K: Sync, V: Sync> Send for Iter<'a, K, V> {}
+
+unsafe impl<'a, K: Sync, V: Sync> Sync for Iter<'a, K, V> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{KeyWrapper, LruCache};
+    use rand::{thread_rng, RngCore};
+    use std::sync::{Arc, Mutex};
+    use std::vec::Vec;
+
+    fn check_order(cache: &LruCache<u32, u32>) {
+        // the recorded predecessors have to match the list
+        let mut expected_prev = None;
+        for entry in cache.list.iter() {
+            let node = cache.map[KeyWrapper::from_ref(&entry.key)];
+            unsafe {
+                assert_eq!((*node.as_ptr()).data.prev, expected_prev);
+            }
+            expected_prev = Some(node);
+        }
+        assert_eq!(cache.map.len(), cache.len());
+        assert!(cache.len() <= cache.cap());
+    }
+
+    fn keys(cache: &LruCache<u32, u32>) -> Vec<u32> {
+        cache.iter().map(|(key, _)| *key).collect()
+    }
+
+    #[test]
+    fn test_put_and_get() {
+        let mut cache = LruCache::new(3);
+        assert_eq!(cache.put(1, 10), None);
+        assert_eq!(cache.put(2, 20), None);
+        assert_eq!(cache.put(3, 30), None);
+        check_order(&cache);
+        assert_eq!(keys(&cache), &[3, 2, 1]);
+
+        assert_eq!(cache.get(&1), Some(&10));
+        check_order(&cache);
+        assert_eq!(keys(&cache), &[1, 3, 2]);
+        assert_eq!(cache.get(&1), Some(&10));
+        assert_eq!(keys(&cache), &[1, 3, 2]);
+
+        *cache.get_mut(&2).unwrap() += 1;
+        check_order(&cache);
+        assert_eq!(keys(&cache), &[2, 1, 3]);
+        assert_eq!(cache.put(3, 31), Some(30));
+        check_order(&cache);
+        assert_eq!(keys(&cache), &[3, 2, 1]);
+
+        assert_eq!(cache.put(4, 40), None);
+        check_order(&cache);
+        assert_eq!(keys(&cache), &[4, 3, 2]);
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.peek(&2), Some(&21));
+        assert_eq!(cache.peek_lru(), Some((&2, &21)));
+        assert_eq!(keys(&cache), &[4, 3, 2]);
+        assert!(cache.contains(&3));
+        assert!(!cache.contains(&1));
+    }
+
+    #[test]
+    fn test_pop() {
+        let mut cache = LruCache::new(4);
+        for i in 0..4 {
+            cache.put(i, i * 10);
+        }
+        assert_eq!(cache.pop(&2), Some(20));
+        check_order(&cache);
+        assert_eq!(cache.pop(&3), Some(30));
+        check_order(&cache);
+        assert_eq!(cache.pop(&3), None);
+        assert_eq!(keys(&cache), &[1, 0]);
+        assert_eq!(cache.pop_lru(), Some((0, 0)));
+        check_order(&cache);
+        assert_eq!(cache.pop_lru(), Some((1, 10)));
+        assert_eq!(cache.pop_lru(), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_evict_callback_and_resize() {
+        let evicted = Arc::new(Mutex::new(Vec::new()));
+        let mut cache = LruCache::new(2);
+        let sink = Arc::clone(&evicted);
+        cache.set_evict_callback(move |key, value| sink.lock().unwrap().push((key, value)));
+
+        cache.put(1, 10);
+        cache.put(2, 20);
+        cache.put(1, 11);
+        cache.put(3, 30);
+        cache.pop(&1);
+        assert_eq!(*evicted.lock().unwrap(), &[(2, 20)]);
+
+        cache.resize(4);
+        for i in 4..7 {
+            cache.put(i, i * 10);
+        }
+        assert_eq!(keys(&cache), &[6, 5, 4, 3]);
+        cache.resize(1);
+        check_order(&cache);
+        assert_eq!(keys(&cache), &[6]);
+        assert_eq!(
+            *evicted.lock().unwrap(),
+            &[(2, 20), (3, 30), (4, 40), (5, 50)]
+        );
+        assert_eq!(cache.list.capacity(), 1);
+
+        cache.resize(0);
+        cache.put(7, 70);
+        assert!(cache.is_empty());
+        assert_eq!(evicted.lock().unwrap().len(), 6);
+    }
+
+    #[test]
+    fn test_borrowed_keys() {
+        let mut cache = LruCache::new(2);
+        cache.put(String::from("a"), 1);
+        cache.put(String::from("b"), 2);
+        assert_eq!(cache.get("a"), Some(&1));
+        assert_eq!(cache.pop("b"), Some(2));
+        assert_eq!(format!("{:?}", cache), r#"{"a": 1}"#);
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.peek("a"), None);
+    }
+
+    #[test]
+    fn test_keys_without_clone() {
+        #[derive(Debug, PartialEq, Eq, Hash)]
+        struct Key(u32);
+
+        let mut cache = LruCache::new(2);
+        cache.put(Key(1), 1);
+        cache.put(Key(2), 2);
+        assert_eq!(cache.put(Key(1), 3), Some(1));
+        cache.put(Key(3), 4);
+        assert!(!cache.contains(&Key(2)));
+        assert_eq!(cache.pop_lru(), Some((Key(1), 3)));
+        assert_eq!(cache.get(&Key(3)), Some(&4));
+    }
+
+    #[test]
+    fn test_fuzz() {
+        let rounds = if cfg!(miri) { 2 } else { 25 };
+        for _ in 0..rounds {
+            fuzz_test(1);
+            fuzz_test(8);
+            fuzz_test(64);
+        }
+    }
+
+    // compares the cache against a vector ordered from the most to the least
+    // recently used entry
+    fn fuzz_test(cap: usize) {
+        let mut cache = LruCache::new(cap);
+        let mut v: Vec<(u32, u32)> = Vec::new();
+        for i in 0..500 {
+            let r = thread_rng().next_u32();
+            let key = r / 8 % (2 * cap as u32);
+            match r % 8 {
+                0 => {
+                    assert_eq!(cache.pop_lru(), v.pop());
+                }
+                1 => {
+                    let expected = v.iter().position(|e| e.0 == key).map(|at| v.remove(at).1);
+                    assert_eq!(cache.pop(&key), expected);
+                }
+                2 | 3 => {
+                    let expected = v.iter().position(|e| e.0 == key).map(|at| {
+                        let entry = v.remove(at);
+                        v.insert(0, entry);
+                        entry.1
+                    });
+                    assert_eq!(cache.get(&key).cloned(), expected);
+                }
+                _ => {
+                    let expected = v.iter().position(|e| e.0 == key).map(|at| v.remove(at).1);
+                    if expected.is_none() && v.len() == cap {
+                        v.pop();
+                    }
+                    v.insert(0, (key, i));
+                    assert_eq!(cache.put(key, i), expected);
+                }
+            }
+            check_order(&cache);
+        }
+        let entries: Vec<(u32, u32)> = cache.iter().map(|(key, value)| (*key, *value)).collect();
+        assert_eq!(entries, v);
+    }
+}
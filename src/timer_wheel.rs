@@ -0,0 +1,533 @@
+//! A hierarchical hashed timer wheel
+//!
+//! The `TimerWheel` schedules items to expire after a number of ticks of a
+//! clock which is driven by [`advance`]. It consists of `LEVELS` wheels of
+//! `SLOTS` buckets each, where every bucket is a [`XorLinkedList`] of timers.
+//! A bucket of the wheel at level `l` covers `SLOTS^l` ticks. Whenever the
+//! clock reaches the range of a bucket, its timers are cascaded into the wheels
+//! below, until they expire from a bucket of the lowest wheel. Timers whose
+//! deadline lies beyond the range of the highest wheel are kept in an overflow
+//! bucket, which is cascaded whenever the highest wheel completes a turn.
+//!
+//! Scheduling and cancelling a timer compute in O(1) time, as every timer
+//! records its predecessor within its bucket, see [removing nodes from the
+//! middle]. Advancing the clock only visits the buckets which hold timers, so
+//! idle ticks are skipped.
+//!
+//! [`advance`]: struct.TimerWheel.html#method.advance
+//! [`XorLinkedList`]: ../xor_linked_list/struct.XorLinkedList.html
+//! [removing nodes from the middle]: ../xor_linked_list/index.html#removing-nodes-from-the-middle
+
+use std::{collections::HashMap, fmt, mem, ptr::NonNull};
+use xor_linked_list::Node;
+use XorLinkedList;
+
+const SLOT_BITS: usize = 6;
+const SLOTS: usize = 1 << SLOT_BITS;
+const LEVELS: usize = 6;
+// the number of ticks covered by the highest wheel
+const SPAN: u64 = 1 << (SLOT_BITS * LEVELS);
+const OVERFLOW: usize = LEVELS * SLOTS;
+
+type TimerNode<T> = NonNull<Node<Timer<T>>>;
+
+/// A hierarchical hashed timer wheel
+///
+/// Items are scheduled with a delay in ticks and returned by [`advance`] once
+/// the clock has reached their deadline. The wheel does not read any clock by
+/// itself, so the length of a tick is up to the caller.
+///
+/// # Examples
+///
+/// ```
+/// use rust_utils::TimerWheel;
+///
+/// let mut wheel = TimerWheel::new();
+/// wheel.schedule(5, "a");
+/// let handle = wheel.schedule(10, "b");
+/// wheel.schedule(100_000, "c");
+///
+/// assert_eq!(wheel.advance(5), vec!["a"]);
+/// assert_eq!(wheel.cancel(handle), Some("b"));
+/// assert!(wheel.advance(1_000).is_empty());
+/// assert_eq!(wheel.advance(100_000), vec!["c"]);
+/// ```
+///
+/// [`advance`]: #method.advance
+pub struct TimerWheel<T> {
+    // `LEVELS` wheels of `SLOTS` buckets each, followed by the overflow bucket
+    buckets: Vec<XorLinkedList<Timer<T>>>,
+    // a bit for every bucket of a wheel which holds timers
+    occupied: [u64; LEVELS],
+    timers: HashMap<u64, TimerNode<T>>,
+    // links no timers, but keeps the nodes of removed timers for reuse
+    pool: XorLinkedList<Timer<T>>,
+    now: u64,
+    next_id: u64,
+}
+
+/// A handle to a timer of a `TimerWheel`
+///
+/// A handle is created by scheduling a timer and stays valid until the timer
+/// expires or is cancelled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimerHandle {
+    id: u64,
+}
+
+struct Timer<T> {
+    id: u64,
+    deadline: u64,
+    bucket: usize,
+    prev: Option<TimerNode<T>>,
+    item: T,
+}
+
+// private methods
+impl<T> TimerWheel<T> {
+    /// Returns the bucket a timer with the given deadline belongs to. The level
+    /// is determined by the highest bit in which the deadline differs from the
+    /// current tick.
+    fn bucket_for(&self, deadline: u64) -> usize {
+        let diff = self.now ^ deadline;
+        if diff >= SPAN {
+            return OVERFLOW;
+        }
+        let level = (63 - (diff | (SLOTS as u64 - 1)).leading_zeros()) as usize / SLOT_BITS;
+        level * SLOTS + (deadline >> (level * SLOT_BITS)) as usize % SLOTS
+    }
+
+    /// Links the given unlinked node at the back of the bucket of its deadline.
+    unsafe fn link(&mut self, node: TimerNode<T>) {
+        let bucket = self.bucket_for((*node.as_ptr()).data.deadline);
+        self.buckets[bucket].push_back_node(node);
+        (*node.as_ptr()).data.bucket = bucket;
+        (*node.as_ptr()).data.prev =
+            Node::<Timer<T>>::get_element(None, (*node.as_ptr()).reference);
+        if bucket < OVERFLOW {
+            self.occupied[bucket / SLOTS] |= 1 << (bucket % SLOTS);
+        }
+    }
+
+    /// Unlinks the given node from its bucket.
+    unsafe fn unlink(&mut self, node: TimerNode<T>) {
+        let bucket = (*node.as_ptr()).data.bucket;
+        let prev = (*node.as_ptr()).data.prev;
+        let next = Node::get_element(prev, (*node.as_ptr()).reference);
+        self.buckets[bucket].unlink_node(prev, node, next);
+        if let Some(next_node) = next {
+            (*next_node.as_ptr()).data.prev = prev;
+        }
+        if bucket < OVERFLOW && self.buckets[bucket].is_empty() {
+            self.occupied[bucket / SLOTS] &= !(1 << (bucket % SLOTS));
+        }
+    }
+
+    /// Returns the next tick at which a bucket has to be cascaded, or `None` if
+    /// no timer is scheduled.
+    fn next_cascade(&self) -> Option<u64> {
+        // a lower wheel always reaches its next bucket before a higher one
+        for (level, &occupied) in self.occupied.iter().enumerate() {
+            if occupied != 0 {
+                let shift = level * SLOT_BITS;
+                let turn = self.now & !((1 << (shift + SLOT_BITS)) - 1);
+                return Some(turn + (u64::from(occupied.trailing_zeros()) << shift));
+            }
+        }
+        if self.buckets[OVERFLOW].is_empty() {
+            None
+        } else {
+            (self.now | (SPAN - 1)).checked_add(1)
+        }
+    }
+
+    /// Moves the clock to the given tick and cascades all buckets whose range
+    /// starts there, from the highest to the lowest wheel.
+    fn cascade_at(&mut self, tick: u64, expired: &mut Vec<T>) {
+        self.now = tick;
+        if tick & (SPAN - 1) == 0 {
+            self.cascade(OVERFLOW, expired);
+        }
+        for level in (0..LEVELS).rev() {
+            let shift = level * SLOT_BITS;
+            if tick & ((1 << shift) - 1) == 0 {
+                let slot = (tick >> shift) as usize % SLOTS;
+                if self.occupied[level] & (1 << slot) != 0 {
+                    self.cascade(level * SLOTS + slot, expired);
+                }
+            }
+        }
+    }
+
+    /// Relinks all timers of the given bucket according to the current tick,
+    /// moving the items of the due timers into `expired`.
+    fn cascade(&mut self, bucket: usize, expired: &mut Vec<T>) {
+        // the bucket is taken as a whole, as the overflow bucket may receive
+        // some of its timers again
+        let mut timers = mem::take(&mut self.buckets[bucket]);
+        if bucket < OVERFLOW {
+            self.occupied[bucket / SLOTS] &= !(1 << (bucket % SLOTS));
+        }
+        while let Some(node) = timers.pop_front_node() {
+            unsafe {
+                if (*node.as_ptr()).data.deadline <= self.now {
+                    let timer = self.pool.release_node(node);
+                    self.timers.remove(&timer.id);
+                    expired.push(timer.item);
+                } else {
+                    self.link(node);
+                }
+            }
+        }
+    }
+}
+
+impl<T> TimerWheel<T> {
+    /// Creates an empty `TimerWheel` starting at tick `0`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::TimerWheel;
+    ///
+    /// let wheel: TimerWheel<u32> = TimerWheel::new();
+    /// assert_eq!(wheel.now(), 0);
+    /// ```
+    pub fn new() -> Self {
+        TimerWheel::with_capacity(0)
+    }
+
+    /// Creates an empty `TimerWheel` starting at tick `0` with room for at
+    /// least `capacity` timers
+    pub fn with_capacity(capacity: usize) -> Self {
+        TimerWheel {
+            buckets: (0..=OVERFLOW).map(|_| XorLinkedList::new()).collect(),
+            occupied: [0; LEVELS],
+            timers: HashMap::with_capacity(capacity),
+            pool: XorLinkedList::with_capacity(capacity),
+            now: 0,
+            next_id: 0,
+        }
+    }
+
+    /// Returns the current tick of the wheel
+    #[inline]
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Returns the number of scheduled timers
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.timers.len()
+    }
+
+    /// Returns `true` if no timer is scheduled
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    /// Cancels all timers without returning their items.
+    pub fn clear(&mut self) {
+        self.timers.clear();
+        for bucket in &mut self.buckets {
+            bucket.clear();
+        }
+        self.occupied = [0; LEVELS];
+    }
+
+    /// Schedules the given item to expire `delay` ticks from now and returns a
+    /// handle to its timer. A delay of `0` is rounded up to a single tick.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::TimerWheel;
+    ///
+    /// let mut wheel = TimerWheel::new();
+    /// let handle = wheel.schedule(0, "a");
+    ///
+    /// assert_eq!(wheel.deadline(handle), Some(1));
+    /// assert_eq!(wheel.advance(1), vec!["a"]);
+    /// ```
+    pub fn schedule(&mut self, delay: u64, item: T) -> TimerHandle {
+        let id = self.next_id;
+        self.next_id += 1;
+        let node = self.pool.allocate_node(Timer {
+            id,
+            deadline: self.now.saturating_add(delay.max(1)),
+            bucket: OVERFLOW,
+            prev: None,
+            item,
+        });
+        unsafe { self.link(node) };
+        self.timers.insert(id, node);
+        TimerHandle { id }
+    }
+
+    /// Cancels the timer of the given handle and returns its item, or `None`
+    /// if the timer has already expired or been cancelled.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::TimerWheel;
+    ///
+    /// let mut wheel = TimerWheel::new();
+    /// let handle = wheel.schedule(10, "a");
+    ///
+    /// assert_eq!(wheel.cancel(handle), Some("a"));
+    /// assert_eq!(wheel.cancel(handle), None);
+    /// assert!(wheel.advance(10).is_empty());
+    /// ```
+    pub fn cancel(&mut self, handle: TimerHandle) -> Option<T> {
+        let node = self.timers.remove(&handle.id)?;
+        unsafe {
+            self.unlink(node);
+            Some(self.pool.release_node(node).item)
+        }
+    }
+
+    /// Returns `true` if the timer of the given handle is still scheduled.
+    #[inline]
+    pub fn contains(&self, handle: TimerHandle) -> bool {
+        self.timers.contains_key(&handle.id)
+    }
+
+    /// Returns the tick at which the timer of the given handle expires, or
+    /// `None` if the timer has already expired or been cancelled.
+    pub fn deadline(&self, handle: TimerHandle) -> Option<u64> {
+        self.timers
+            .get(&handle.id)
+            .map(|node| unsafe { (*node.as_ptr()).data.deadline })
+    }
+
+    /// Advances the clock by the given number of ticks and returns the items of
+    /// all timers which expired in the meantime, ordered by their deadline.
+    ///
+    /// This operation should compute in O(m + k) time, where `m` is the number
+    /// of cascaded timers and `k` the number of visited buckets, which is
+    /// independent of the number of ticks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::TimerWheel;
+    ///
+    /// let mut wheel = TimerWheel::new();
+    /// wheel.schedule(3, 'c');
+    /// wheel.schedule(1, 'a');
+    /// wheel.schedule(2, 'b');
+    /// wheel.schedule(1 << 40, 'd');
+    ///
+    /// assert_eq!(wheel.advance(2), vec!['a', 'b']);
+    /// assert_eq!(wheel.advance(1 << 40), vec!['c', 'd']);
+    /// assert_eq!(wheel.now(), 2 + (1 << 40));
+    /// ```
+    pub fn advance(&mut self, ticks: u64) -> Vec<T> {
+        let target = self.now.saturating_add(ticks);
+        let mut expired = Vec::new();
+        while let Some(tick) = self.next_cascade().filter(|&tick| tick <= target) {
+            self.cascade_at(tick, &mut expired);
+        }
+        self.now = target;
+        expired
+    }
+}
+
+impl<T> Default for TimerWheel<T> {
+    /// Creates an empty `TimerWheel<T>`.
+    #[inline]
+    fn default() -> Self {
+        TimerWheel::new()
+    }
+}
+
+impl<T> fmt::Debug for TimerWheel<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TimerWheel")
+            .field("now", &self.now)
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+unsafe impl<T: Send> Send for TimerWheel<T> {}
+
+unsafe impl<T: Sync> Sync for TimerWheel<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{TimerHandle, TimerWheel, LEVELS, OVERFLOW, SLOTS, SPAN};
+    use rand::{thread_rng, RngCore};
+    use std::vec::Vec;
+
+    fn check_buckets(wheel: &TimerWheel<u64>) {
+        // the recorded predecessors, buckets and occupancy have to match the lists
+        let mut count = 0;
+        for (bucket, timers) in wheel.buckets.iter().enumerate() {
+            if bucket < OVERFLOW {
+                let occupied = wheel.occupied[bucket / SLOTS] & (1 << (bucket % SLOTS)) != 0;
+                assert_eq!(occupied, !timers.is_empty());
+            }
+            let mut expected_prev = None;
+            for timer in timers.iter() {
+                let node = wheel.timers[&timer.id];
+                unsafe {
+                    assert_eq!((*node.as_ptr()).data.prev, expected_prev);
+                }
+                assert_eq!(timer.bucket, bucket);
+                assert_eq!(wheel.bucket_for(timer.deadline), bucket);
+                assert!(timer.deadline > wheel.now);
+                expected_prev = Some(node);
+                count += 1;
+            }
+        }
+        assert_eq!(wheel.len(), count);
+    }
+
+    #[test]
+    fn test_levels() {
+        let mut wheel = TimerWheel::new();
+        let delays = [
+            1,
+            63,
+            64,
+            65,
+            4095,
+            4096,
+            300_000,
+            SPAN - 1,
+            SPAN,
+            3 * SPAN + 7,
+        ];
+        for &delay in delays.iter().rev() {
+            wheel.schedule(delay, delay);
+        }
+        check_buckets(&wheel);
+        assert!(!wheel.buckets[OVERFLOW].is_empty());
+        assert_eq!(wheel.occupied[4], 0);
+        assert!(wheel.occupied[LEVELS - 1] != 0);
+
+        let mut expired = Vec::new();
+        while !wheel.is_empty() {
+            let step = wheel.advance(1);
+            check_buckets(&wheel);
+            if let Some(&delay) = step.first() {
+                assert_eq!(delay, wheel.now());
+                expired.extend(step);
+                // skip the idle ticks up to the next deadline
+                if let Some(&next) = delays.iter().find(|&&next| next > delay) {
+                    assert!(wheel.advance(next - delay - 1).is_empty());
+                }
+            }
+        }
+        assert_eq!(expired, delays);
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mut wheel = TimerWheel::new();
+        let handles: Vec<TimerHandle> = (0..10).map(|i| wheel.schedule(i * 100, i)).collect();
+        assert_eq!(wheel.cancel(handles[3]), Some(3));
+        assert_eq!(wheel.cancel(handles[0]), Some(0));
+        assert_eq!(wheel.cancel(handles[3]), None);
+        check_buckets(&wheel);
+        assert!(!wheel.contains(handles[3]));
+        assert_eq!(wheel.deadline(handles[5]), Some(500));
+
+        assert_eq!(wheel.advance(450), &[1, 2, 4]);
+        check_buckets(&wheel);
+        assert_eq!(wheel.cancel(handles[1]), None);
+        assert_eq!(wheel.cancel(handles[9]), Some(9));
+        assert_eq!(wheel.len(), 4);
+        assert_eq!(wheel.advance(10_000), &[5, 6, 7, 8]);
+        assert!(wheel.is_empty());
+    }
+
+    #[test]
+    fn test_same_deadline() {
+        let mut wheel = TimerWheel::new();
+        wheel.schedule(130, 0);
+        wheel.advance(60);
+        wheel.schedule(70, 1);
+        wheel.advance(10);
+        wheel.schedule(60, 2);
+        check_buckets(&wheel);
+        let mut expired = wheel.advance(60);
+        expired.sort();
+        assert_eq!(expired, &[0, 1, 2]);
+        assert_eq!(wheel.now(), 130);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut wheel = TimerWheel::new();
+        for i in 0..100 {
+            wheel.schedule(i * 1000, i);
+        }
+        wheel.advance(5000);
+        wheel.clear();
+        check_buckets(&wheel);
+        assert!(wheel.is_empty());
+        assert!(wheel.advance(u64::MAX).is_empty());
+        assert_eq!(wheel.now(), u64::MAX);
+        let handle = wheel.schedule(1, 0);
+        assert_eq!(wheel.deadline(handle), Some(u64::MAX));
+        assert_eq!(
+            format!("{:?}", wheel),
+            "TimerWheel { now: 18446744073709551615, len: 1, .. }"
+        );
+    }
+
+    #[test]
+    fn test_fuzz() {
+        let rounds = if cfg!(miri) { 2 } else { 25 };
+        for _ in 0..rounds {
+            fuzz_test(1 << 8);
+            fuzz_test(1 << 20);
+            fuzz_test(1 << 40);
+        }
+    }
+
+    // compares the wheel against a vector of `(handle, deadline)`
+    fn fuzz_test(max_delay: u64) {
+        let mut wheel = TimerWheel::new();
+        let mut v: Vec<(TimerHandle, u64)> = Vec::new();
+        let mut rng = thread_rng();
+        for _ in 0..500 {
+            let delay = rng.next_u64() % max_delay;
+            match rng.next_u32() % 8 {
+                0 if !v.is_empty() => {
+                    let (handle, _) = v.remove(rng.next_u32() as usize % v.len());
+                    assert!(wheel.cancel(handle).is_some());
+                }
+                1 | 2 => {
+                    let target = wheel.now() + delay / 4;
+                    let mut expected: Vec<u64> = v
+                        .iter()
+                        .filter(|timer| timer.1 <= target)
+                        .map(|timer| timer.1)
+                        .collect();
+                    v.retain(|timer| timer.1 > target);
+                    expected.sort();
+                    assert_eq!(wheel.advance(delay / 4), expected);
+                }
+                _ => {
+                    let deadline = wheel.now() + delay.max(1);
+                    v.push((wheel.schedule(delay, deadline), deadline));
+                }
+            }
+            check_buckets(&wheel);
+        }
+        let mut expected: Vec<u64> = v.iter().map(|timer| timer.1).collect();
+        expected.sort();
+        assert_eq!(wheel.advance(max_delay), expected);
+        assert!(wheel.is_empty());
+    }
+}
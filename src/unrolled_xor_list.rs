@@ -0,0 +1,1600 @@
+//! A doubly-linked list storing several elements per node
+//!
+//! The `UnrolledXorList` allows pushing and popping elements at either end
+//! in constant time. Every node stores up to `N` elements inline, which
+//! reduces the memory overhead per element and the number of nodes to follow
+//! while iterating. Like the nodes of the [`XorLinkedList`], the nodes only
+//! store the XOR of the addresses of both of their neighbours.
+//!
+//! Almost always it is better to use `Vec` or [`VecDeque`] instead of
+//! [`LinkedList`]. In general, array-based containers are faster,
+//! more memory efficient and make better use of CPU cache.
+//!
+//! [`XorLinkedList`]: ../xor_linked_list/struct.XorLinkedList.html
+//! [`LinkedList`]: ../linked_list/struct.LinkedList.html
+//! [`VecDeque`]: ../vec_deque/struct.VecDeque.html
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::{FromIterator, FusedIterator},
+    marker::PhantomData,
+    mem::{self, MaybeUninit},
+    ops::{Index, IndexMut},
+    ptr::{self, NonNull},
+    slice,
+};
+
+/// A doubly-linked list storing up to `N` elements per node
+///
+/// The `UnrolledXorList` allows pushing and popping elements at either end
+/// in constant time. Inserting into a full node splits it in half, removing
+/// elements merges a node with its successor once both fit into one node.
+/// Appending and splitting lists only touches the nodes at the boundary.
+///
+/// Almost always it is better to use `Vec` or `VecDeque` instead of
+/// `UnrolledXorList`. In general, array-based containers are faster,
+/// more memory efficient and make better use of CPU cache.
+pub struct UnrolledXorList<T, const N: usize> {
+    head: Link<T, N>,
+    tail: Link<T, N>,
+    len: usize,
+    marker: PhantomData<Box<Chunk<T, N>>>,
+}
+
+// Linked chunks are never empty and store their elements in `data[..len]`.
+struct Chunk<T, const N: usize> {
+    reference: usize,
+    len: usize,
+    data: [MaybeUninit<T>; N],
+}
+
+type Link<T, const N: usize> = Option<NonNull<Chunk<T, N>>>;
+
+/// An iterator over the elements of a `UnrolledXorList`.
+///
+/// This `struct` is created by the [`iter`] method on [`UnrolledXorList`]. See its
+/// documentation for more.
+///
+/// [`iter`]: struct.UnrolledXorList.html#method.iter
+/// [`UnrolledXorList`]: struct.UnrolledXorList.html
+pub struct Iter<'a, T: 'a, const N: usize> {
+    head: Link<T, N>,
+    tail: Link<T, N>,
+    last_head: Link<T, N>,
+    last_tail: Link<T, N>,
+    head_index: usize,
+    tail_index: usize,
+    len: usize,
+    marker: PhantomData<&'a Chunk<T, N>>,
+}
+
+impl<'a, T, const N: usize> Clone for Iter<'a, T, N> {
+    fn clone(&self) -> Self {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            last_head: self.last_head,
+            last_tail: self.last_tail,
+            head_index: self.head_index,
+            tail_index: self.tail_index,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: 'a + fmt::Debug, const N: usize> fmt::Debug for Iter<'a, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Iter").field(&self.len).finish()
+    }
+}
+
+/// A mutable iterator over the elements of a `UnrolledXorList`.
+///
+/// This `struct` is created by the [`iter_mut`] method on [`UnrolledXorList`]. See its
+/// documentation for more.
+///
+/// [`iter_mut`]: struct.UnrolledXorList.html#method.iter_mut
+/// [`UnrolledXorList`]: struct.UnrolledXorList.html
+pub struct IterMut<'a, T: 'a, const N: usize> {
+    head: Link<T, N>,
+    tail: Link<T, N>,
+    last_head: Link<T, N>,
+    last_tail: Link<T, N>,
+    head_index: usize,
+    tail_index: usize,
+    len: usize,
+    marker: PhantomData<&'a mut Chunk<T, N>>,
+}
+
+impl<'a, T: 'a + fmt::Debug, const N: usize> fmt::Debug for IterMut<'a, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IterMut").field(&self.len).finish()
+    }
+}
+
+/// An owning iterator over the elements of a `UnrolledXorList`.
+///
+/// This `struct` is created by the [`into_iter`] method on
+/// [`UnrolledXorList`][`UnrolledXorList`] (provided by the `IntoIterator` trait).
+/// See its documentation for more.
+///
+/// [`into_iter`]: struct.UnrolledXorList.html#method.into_iter
+/// [`UnrolledXorList`]: struct.UnrolledXorList.html
+pub struct IntoIter<T, const N: usize> {
+    list: UnrolledXorList<T, N>,
+}
+
+impl<T: Clone, const N: usize> Clone for IntoIter<T, N> {
+    fn clone(&self) -> Self {
+        IntoIter {
+            list: self.list.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for IntoIter<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.list).finish()
+    }
+}
+
+impl<T, const N: usize> Chunk<T, N> {
+    /// Allocates an empty chunk. The caller takes over the ownership of it.
+    fn allocate() -> NonNull<Self> {
+        let chunk = Box::new(Chunk {
+            reference: 0,
+            len: 0,
+            // an array of `MaybeUninit` does not require initialization
+            data: unsafe { MaybeUninit::<[MaybeUninit<T>; N]>::uninit().assume_init() },
+        });
+        NonNull::from(Box::leak(chunk))
+    }
+
+    /// Frees a chunk without dropping any of its elements.
+    #[inline]
+    unsafe fn free(chunk: NonNull<Self>) {
+        drop(Box::from_raw(chunk.as_ptr()));
+    }
+
+    /// Combines the addresses of both neighbours into a chunk reference. The
+    /// provenance of both chunks is exposed, so that `get_element` is allowed to
+    /// recreate pointers to them later on.
+    #[inline]
+    fn calculate_reference(previous: Link<T, N>, next: Link<T, N>) -> usize {
+        let pr = previous.map_or(0, |i| i.as_ptr().expose_provenance());
+        let ne = next.map_or(0, |i| i.as_ptr().expose_provenance());
+        pr ^ ne
+    }
+
+    /// Recreates the pointer to the other neighbour of a chunk from its reference
+    /// using the provenance exposed by `calculate_reference`.
+    #[inline]
+    fn get_element(previous_or_next: Link<T, N>, reference: usize) -> Link<T, N> {
+        let other = previous_or_next.map_or(0, |i| i.as_ptr().addr());
+        NonNull::new(ptr::with_exposed_provenance_mut(other ^ reference))
+    }
+
+    #[inline]
+    fn as_ptr(&self) -> *const T {
+        self.data.as_ptr().cast()
+    }
+
+    #[inline]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.data.as_mut_ptr().cast()
+    }
+
+    #[inline]
+    fn elements(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+
+    #[inline]
+    fn elements_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+
+    /// Inserts an element at `index`, shifting all elements after it. The
+    /// chunk must not be full and `index <= len`.
+    #[inline]
+    unsafe fn insert(&mut self, index: usize, value: T) {
+        let position = self.as_mut_ptr().add(index);
+        ptr::copy(position, position.add(1), self.len - index);
+        position.write(value);
+        self.len += 1;
+    }
+
+    /// Removes the element at `index`, shifting all elements after it.
+    /// `index` must be less than `len`.
+    #[inline]
+    unsafe fn remove(&mut self, index: usize) -> T {
+        let position = self.as_mut_ptr().add(index);
+        let value = position.read();
+        ptr::copy(position.add(1), position, self.len - index - 1);
+        self.len -= 1;
+        value
+    }
+
+    /// Moves all elements starting at `at` to the end of `other`, which must
+    /// have room for them.
+    #[inline]
+    unsafe fn move_tail_to(&mut self, at: usize, other: &mut Self) {
+        let count = self.len - at;
+        ptr::copy_nonoverlapping(
+            self.as_ptr().add(at),
+            other.as_mut_ptr().add(other.len),
+            count,
+        );
+        self.len = at;
+        other.len += count;
+    }
+}
+
+// private methods
+impl<T, const N: usize> UnrolledXorList<T, N> {
+    /// Links the given chunk in between the two adjacent chunks `prev` and `next`.
+    /// `None` denotes the position before the head or after the tail.
+    #[inline]
+    unsafe fn link_chunk(
+        &mut self,
+        prev: Link<T, N>,
+        next: Link<T, N>,
+        mut chunk: NonNull<Chunk<T, N>>,
+    ) {
+        chunk.as_mut().reference = Chunk::calculate_reference(prev, next);
+        match prev {
+            Some(mut prev_chunk) => {
+                prev_chunk.as_mut().reference ^= Chunk::calculate_reference(next, Some(chunk));
+            }
+            None => self.head = Some(chunk),
+        }
+        match next {
+            Some(mut next_chunk) => {
+                next_chunk.as_mut().reference ^= Chunk::calculate_reference(prev, Some(chunk));
+            }
+            None => self.tail = Some(chunk),
+        }
+    }
+
+    /// Unlinks the given chunk which resides in between `prev` and `next` and
+    /// frees it. The chunk must not contain any elements.
+    #[inline]
+    unsafe fn unlink_chunk(
+        &mut self,
+        prev: Link<T, N>,
+        chunk: NonNull<Chunk<T, N>>,
+        next: Link<T, N>,
+    ) {
+        match prev {
+            Some(mut prev_chunk) => {
+                prev_chunk.as_mut().reference ^= Chunk::calculate_reference(Some(chunk), next);
+            }
+            None => self.head = next,
+        }
+        match next {
+            Some(mut next_chunk) => {
+                next_chunk.as_mut().reference ^= Chunk::calculate_reference(Some(chunk), prev);
+            }
+            None => self.tail = prev,
+        }
+        Chunk::free(chunk);
+    }
+
+    /// Moves the elements of the chunk following `chunk` into `chunk` and frees
+    /// the emptied chunk, if all elements fit into `chunk`.
+    unsafe fn merge_with_next(&mut self, prev: Link<T, N>, mut chunk: NonNull<Chunk<T, N>>) {
+        if let Some(mut next) = Chunk::get_element(prev, chunk.as_ref().reference) {
+            if chunk.as_ref().len + next.as_ref().len <= N {
+                next.as_mut().move_tail_to(0, chunk.as_mut());
+                let next_next = Chunk::get_element(Some(chunk), next.as_ref().reference);
+                self.unlink_chunk(Some(chunk), next, next_next);
+            }
+        }
+    }
+
+    /// Returns the chunk holding the element at the given index together with
+    /// the chunk in front of it and the offset of the element within the chunk.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at >= len`.
+    fn locate(&self, at: usize) -> (Link<T, N>, NonNull<Chunk<T, N>>, usize) {
+        let len = self.len;
+        assert!(at < len, "Cannot access a nonexistent index");
+
+        // Below, we iterate towards the chunk holding the `at`th element, either
+        // from the start or the end, depending on which would be faster
+        unsafe {
+            if at <= len - 1 - at {
+                let mut prev = None;
+                let mut chunk = self.head.expect("non-empty list must have a head");
+                let mut offset = at;
+                while offset >= chunk.as_ref().len {
+                    offset -= chunk.as_ref().len;
+                    let next = Chunk::get_element(prev, chunk.as_ref().reference)
+                        .expect("index must be covered by the chunks");
+                    prev = Some(chunk);
+                    chunk = next;
+                }
+                (prev, chunk, offset)
+            } else {
+                // better off starting from the end
+                let mut next = None;
+                let mut chunk = self.tail.expect("non-empty list must have a tail");
+                let mut offset = len - 1 - at;
+                while offset >= chunk.as_ref().len {
+                    offset -= chunk.as_ref().len;
+                    let prev_chunk = Chunk::get_element(next, chunk.as_ref().reference)
+                        .expect("index must be covered by the chunks");
+                    next = Some(chunk);
+                    chunk = prev_chunk;
+                }
+                let prev = Chunk::get_element(next, chunk.as_ref().reference);
+                (prev, chunk, chunk.as_ref().len - 1 - offset)
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> Default for UnrolledXorList<T, N> {
+    /// Creates an empty `UnrolledXorList<T, N>`
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> UnrolledXorList<T, N> {
+    /// Creates an empty `UnrolledXorList`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let list: UnrolledXorList<u32, 8> = UnrolledXorList::new();
+    /// ```
+    #[inline]
+    pub fn new() -> Self {
+        assert!(N > 0, "Cannot store elements in chunks without capacity");
+        UnrolledXorList {
+            head: None,
+            tail: None,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Moves all elements from `other` to the end of the list.
+    ///
+    /// This reuses all the nodes from `other` and moves them into `self`. If
+    /// the elements of the first node of `other` fit into the last node of
+    /// `self`, both nodes are merged. After this operation, `other` becomes empty.
+    ///
+    /// This operation should compute in O(N) time and O(1) memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut list1: UnrolledXorList<char, 4> = UnrolledXorList::new();
+    /// list1.push_back('a');
+    ///
+    /// let mut list2 = UnrolledXorList::new();
+    /// list2.push_back('b');
+    /// list2.push_back('c');
+    ///
+    /// list1.append(&mut list2);
+    ///
+    /// let mut iter = list1.iter();
+    /// assert_eq!(iter.next(), Some(&'a'));
+    /// assert_eq!(iter.next(), Some(&'b'));
+    /// assert_eq!(iter.next(), Some(&'c'));
+    /// assert!(iter.next().is_none());
+    ///
+    /// assert!(list2.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut Self) {
+        match self.tail {
+            None => mem::swap(self, other),
+            Some(mut tail) => {
+                if let Some(mut other_head) = other.head.take() {
+                    unsafe {
+                        tail.as_mut().reference ^=
+                            Chunk::calculate_reference(Some(other_head), None);
+                        other_head.as_mut().reference ^=
+                            Chunk::calculate_reference(Some(tail), None);
+                        self.tail = other.tail.take();
+                        self.len += mem::replace(&mut other.len, 0);
+
+                        let tail_prev =
+                            Chunk::get_element(Some(other_head), tail.as_ref().reference);
+                        self.merge_with_next(tail_prev, tail);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Provides a forward iterator
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut list: UnrolledXorList<u32, 2> = UnrolledXorList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(1);
+    /// list.push_back(2);
+    ///
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&0));
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next(), Some(&2));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T, N> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            last_head: None,
+            last_tail: None,
+            head_index: 0,
+            tail_index: self.tail.map_or(0, |tail| unsafe { tail.as_ref().len }),
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Provides a forward iterator with mutable references
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut list: UnrolledXorList<u32, 2> = UnrolledXorList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(1);
+    /// list.push_back(2);
+    ///
+    /// for element in list.iter_mut() {
+    ///     *element += 10;
+    /// }
+    ///
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&10));
+    /// assert_eq!(iter.next(), Some(&11));
+    /// assert_eq!(iter.next(), Some(&12));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, N> {
+        IterMut {
+            head: self.head,
+            tail: self.tail,
+            last_head: None,
+            last_tail: None,
+            head_index: 0,
+            tail_index: self.tail.map_or(0, |tail| unsafe { tail.as_ref().len }),
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns `true` if the `UnrolledXorList` is empty
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut dl: UnrolledXorList<&str, 4> = UnrolledXorList::new();
+    /// assert!(dl.is_empty());
+    ///
+    /// dl.push_front("foo");
+    /// assert!(!dl.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// Returns the length of the `UnrolledXorList`
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut dl: UnrolledXorList<i32, 4> = UnrolledXorList::new();
+    ///
+    /// dl.push_front(2);
+    /// assert_eq!(dl.len(), 1);
+    ///
+    /// dl.push_front(1);
+    /// assert_eq!(dl.len(), 2);
+    ///
+    /// dl.push_back(3);
+    /// assert_eq!(dl.len(), 3);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Removes all elements from the `UnrolledXorList`
+    ///
+    /// This operation should compute in O(n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut dl: UnrolledXorList<i32, 4> = UnrolledXorList::new();
+    ///
+    /// dl.push_front(2);
+    /// dl.push_front(1);
+    /// assert_eq!(dl.len(), 2);
+    /// assert_eq!(dl.front(), Some(&1));
+    ///
+    /// dl.clear();
+    /// assert_eq!(dl.len(), 0);
+    /// assert_eq!(dl.front(), None);
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Returns `true` if the `UnrolledXorList` contains an element equal to the
+    /// given value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut list: UnrolledXorList<u32, 4> = UnrolledXorList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(1);
+    /// list.push_back(2);
+    ///
+    /// assert_eq!(list.contains(&0), true);
+    /// assert_eq!(list.contains(&10), false);
+    /// ```
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq<T>,
+    {
+        self.iter().any(|e| e == x)
+    }
+
+    /// Provides a reference to the front element, or `None` if the list is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut dl: UnrolledXorList<i32, 4> = UnrolledXorList::new();
+    /// assert_eq!(dl.front(), None);
+    ///
+    /// dl.push_front(1);
+    /// assert_eq!(dl.front(), Some(&1));
+    /// ```
+    #[inline]
+    pub fn front(&self) -> Option<&T> {
+        unsafe {
+            self.head
+                .as_ref()
+                .and_then(|chunk| (*chunk.as_ptr()).elements().first())
+        }
+    }
+
+    /// Provides a mutable reference to the front element, or `None` if the list
+    /// is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut dl: UnrolledXorList<i32, 4> = UnrolledXorList::new();
+    /// assert_eq!(dl.front(), None);
+    ///
+    /// dl.push_front(1);
+    /// assert_eq!(dl.front(), Some(&1));
+    ///
+    /// match dl.front_mut() {
+    ///     None => {},
+    ///     Some(x) => *x = 5,
+    /// }
+    /// assert_eq!(dl.front(), Some(&5));
+    /// ```
+    #[inline]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        unsafe {
+            self.head
+                .as_mut()
+                .and_then(|chunk| (*chunk.as_ptr()).elements_mut().first_mut())
+        }
+    }
+
+    /// Provides a reference to the back element, or `None` if the list is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut dl: UnrolledXorList<i32, 4> = UnrolledXorList::new();
+    /// assert_eq!(dl.back(), None);
+    ///
+    /// dl.push_back(1);
+    /// assert_eq!(dl.back(), Some(&1));
+    /// ```
+    #[inline]
+    pub fn back(&self) -> Option<&T> {
+        unsafe {
+            self.tail
+                .as_ref()
+                .and_then(|chunk| (*chunk.as_ptr()).elements().last())
+        }
+    }
+
+    /// Provides a mutable reference to the back element, or `None` if the list
+    /// is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut dl: UnrolledXorList<i32, 4> = UnrolledXorList::new();
+    /// assert_eq!(dl.back(), None);
+    ///
+    /// dl.push_back(1);
+    /// assert_eq!(dl.back(), Some(&1));
+    ///
+    /// match dl.back_mut() {
+    ///     None => {},
+    ///     Some(x) => *x = 5,
+    /// }
+    /// assert_eq!(dl.back(), Some(&5));
+    /// ```
+    #[inline]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        unsafe {
+            self.tail
+                .as_mut()
+                .and_then(|chunk| (*chunk.as_ptr()).elements_mut().last_mut())
+        }
+    }
+
+    /// Provides a reference to the element at the given index, or `None` if
+    /// the index is out of bounds
+    ///
+    /// This operation should compute in O(min(i, n - i) / N) time in the best
+    /// case, when all nodes are full, and in O(min(i, n - i)) time otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let dl: UnrolledXorList<i32, 2> = (3..6).collect();
+    ///
+    /// assert_eq!(dl.get(1), Some(&4));
+    /// assert_eq!(dl.get(3), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            let (_, chunk, offset) = self.locate(index);
+            unsafe { Some(&(*chunk.as_ptr()).elements()[offset]) }
+        } else {
+            None
+        }
+    }
+
+    /// Provides a mutable reference to the element at the given index, or `None`
+    /// if the index is out of bounds
+    ///
+    /// This operation should compute in O(min(i, n - i) / N) time in the best
+    /// case, when all nodes are full, and in O(min(i, n - i)) time otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut dl: UnrolledXorList<i32, 2> = (3..6).collect();
+    ///
+    /// if let Some(x) = dl.get_mut(1) {
+    ///     *x = 7;
+    /// }
+    /// assert_eq!(dl.get(1), Some(&7));
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len {
+            let (_, chunk, offset) = self.locate(index);
+            unsafe { Some(&mut (*chunk.as_ptr()).elements_mut()[offset]) }
+        } else {
+            None
+        }
+    }
+
+    /// Adds an element first in the list.
+    ///
+    /// This operation should compute in O(N) time, as the elements of the first
+    /// node are shifted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut dl: UnrolledXorList<i32, 4> = UnrolledXorList::new();
+    ///
+    /// dl.push_front(2);
+    /// assert_eq!(dl.front().unwrap(), &2);
+    ///
+    /// dl.push_front(1);
+    /// assert_eq!(dl.front().unwrap(), &1);
+    /// ```
+    pub fn push_front(&mut self, data: T) {
+        unsafe {
+            match self.head {
+                Some(mut head) if head.as_ref().len < N => head.as_mut().insert(0, data),
+                _ => {
+                    let mut chunk = Chunk::allocate();
+                    chunk.as_mut().insert(0, data);
+                    self.link_chunk(None, self.head, chunk);
+                }
+            }
+        }
+        self.len += 1;
+    }
+
+    /// Removes the first element and returns it, or `None` if the list is
+    /// empty.
+    ///
+    /// This operation should compute in O(N) time, as the elements of the first
+    /// node are shifted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut d: UnrolledXorList<i32, 4> = UnrolledXorList::new();
+    /// assert_eq!(d.pop_front(), None);
+    ///
+    /// d.push_front(1);
+    /// d.push_front(3);
+    /// assert_eq!(d.pop_front(), Some(3));
+    /// assert_eq!(d.pop_front(), Some(1));
+    /// assert_eq!(d.pop_front(), None);
+    /// ```
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.head.map(|mut head| unsafe {
+            let data = head.as_mut().remove(0);
+            if head.as_ref().len == 0 {
+                let next = Chunk::get_element(None, head.as_ref().reference);
+                self.unlink_chunk(None, head, next);
+            }
+            self.len -= 1;
+            data
+        })
+    }
+
+    /// Appends an element to the back of a list
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut d: UnrolledXorList<i32, 4> = UnrolledXorList::new();
+    /// d.push_back(1);
+    /// d.push_back(3);
+    /// assert_eq!(3, *d.back().unwrap());
+    /// ```
+    pub fn push_back(&mut self, data: T) {
+        unsafe {
+            match self.tail {
+                Some(mut tail) if tail.as_ref().len < N => {
+                    let len = tail.as_ref().len;
+                    tail.as_mut().insert(len, data);
+                }
+                _ => {
+                    let mut chunk = Chunk::allocate();
+                    chunk.as_mut().insert(0, data);
+                    self.link_chunk(self.tail, None, chunk);
+                }
+            }
+        }
+        self.len += 1;
+    }
+
+    /// Removes the last element from a list and returns it, or `None` if
+    /// it is empty.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut d: UnrolledXorList<i32, 4> = UnrolledXorList::new();
+    /// assert_eq!(d.pop_back(), None);
+    /// d.push_back(1);
+    /// d.push_back(3);
+    /// assert_eq!(d.pop_back(), Some(3));
+    /// ```
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.tail.map(|mut tail| unsafe {
+            let last = tail.as_ref().len - 1;
+            let data = tail.as_mut().remove(last);
+            if last == 0 {
+                let prev = Chunk::get_element(None, tail.as_ref().reference);
+                self.unlink_chunk(prev, tail, None);
+            }
+            self.len -= 1;
+            data
+        })
+    }
+
+    /// Inserts an element at position `index` within the list, shifting all elements
+    /// with indices greater than or equal to `index` towards the back.
+    ///
+    /// If the node holding the position is full, it is split in half.
+    ///
+    /// This operation should compute in O(min(i, n - i) + N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut dl: UnrolledXorList<char, 2> = UnrolledXorList::new();
+    /// dl.push_back('a');
+    /// dl.push_back('c');
+    ///
+    /// dl.insert(1, 'b');
+    ///
+    /// let mut iter = dl.iter();
+    /// assert_eq!(iter.next(), Some(&'a'));
+    /// assert_eq!(iter.next(), Some(&'b'));
+    /// assert_eq!(iter.next(), Some(&'c'));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn insert(&mut self, index: usize, value: T) {
+        assert!(index <= self.len, "Cannot insert at a nonexistent index");
+        if index == self.len {
+            self.push_back(value);
+            return;
+        }
+
+        let (prev, mut chunk, offset) = self.locate(index);
+        unsafe {
+            if chunk.as_ref().len < N {
+                chunk.as_mut().insert(offset, value);
+            } else {
+                // Move the upper half into a new chunk. With `N == 1` the lower
+                // half is empty, but the new element is inserted there right away.
+                let mid = N / 2;
+                let next = Chunk::get_element(prev, chunk.as_ref().reference);
+                let mut upper = Chunk::allocate();
+                chunk.as_mut().move_tail_to(mid, upper.as_mut());
+                self.link_chunk(Some(chunk), next, upper);
+                if offset <= mid {
+                    chunk.as_mut().insert(offset, value);
+                } else {
+                    upper.as_mut().insert(offset - mid, value);
+                }
+            }
+        }
+        self.len += 1;
+    }
+
+    /// Removes and returns the element at `index` from the list, or `None` if
+    /// the index is out of bounds.
+    ///
+    /// If the node holding the element drops below half of its capacity, it is
+    /// merged with the following node if both fit into one.
+    ///
+    /// This operation should compute in O(min(i, n - i) + N) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut dl: UnrolledXorList<char, 2> = "abc".chars().collect();
+    ///
+    /// assert_eq!(dl.remove(1), Some('b'));
+    /// assert_eq!(dl.remove(5), None);
+    /// assert_eq!(dl.iter().collect::<String>(), "ac");
+    /// ```
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.len {
+            return None;
+        }
+
+        let (prev, mut chunk, offset) = self.locate(index);
+        self.len -= 1;
+        unsafe {
+            let data = chunk.as_mut().remove(offset);
+            if chunk.as_ref().len == 0 {
+                let next = Chunk::get_element(prev, chunk.as_ref().reference);
+                self.unlink_chunk(prev, chunk, next);
+            } else if chunk.as_ref().len < N / 2 {
+                self.merge_with_next(prev, chunk);
+            }
+            Some(data)
+        }
+    }
+
+    /// Splits the list into two at the given index. Returns everything after the given index,
+    /// including the index
+    ///
+    /// If the index lies within a node, the node is split in two.
+    ///
+    /// This operation should compute in O(min(i, n - i) + N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::UnrolledXorList;
+    ///
+    /// let mut d: UnrolledXorList<i32, 2> = UnrolledXorList::new();
+    ///
+    /// d.push_front(1);
+    /// d.push_front(2);
+    /// d.push_front(3);
+    ///
+    /// let mut splitted = d.split_off(2);
+    ///
+    /// assert_eq!(splitted.pop_front(), Some(1));
+    /// assert_eq!(splitted.pop_front(), None);
+    /// ```
+    pub fn split_off(&mut self, at: usize) -> UnrolledXorList<T, N> {
+        let len = self.len();
+        assert!(at <= len, "Cannot split off at a nonexistent index");
+        if at == 0 {
+            return mem::take(self);
+        } else if at == len {
+            return Self::new();
+        }
+
+        let (prev, mut chunk, offset) = self.locate(at);
+        let mut second_part = UnrolledXorList {
+            head: None,
+            tail: None,
+            len: len - at,
+            marker: PhantomData,
+        };
+        self.len = at;
+        unsafe {
+            // The chunks before and after the split point
+            let (first_tail, second_head) = if offset == 0 {
+                (prev, Some(chunk))
+            } else {
+                (
+                    Some(chunk),
+                    Chunk::get_element(prev, chunk.as_ref().reference),
+                )
+            };
+            if let (Some(mut before), Some(mut after)) = (first_tail, second_head) {
+                before.as_mut().reference ^= Chunk::calculate_reference(Some(after), None);
+                after.as_mut().reference ^= Chunk::calculate_reference(Some(before), None);
+                second_part.head = Some(after);
+                second_part.tail = self.tail;
+            }
+            self.tail = first_tail;
+
+            if offset != 0 {
+                let mut upper = Chunk::allocate();
+                chunk.as_mut().move_tail_to(offset, upper.as_mut());
+                second_part.link_chunk(None, second_part.head, upper);
+            }
+        }
+        second_part
+    }
+}
+
+impl<T, const N: usize> Drop for UnrolledXorList<T, N> {
+    fn drop(&mut self) {
+        let mut prev = None;
+        let mut current = self.head;
+        while let Some(chunk) = current {
+            unsafe {
+                current = Chunk::get_element(prev, chunk.as_ref().reference);
+                prev = Some(chunk);
+                ptr::drop_in_place((*chunk.as_ptr()).elements_mut());
+                Chunk::free(chunk);
+            }
+        }
+    }
+}
+
+impl<'a, T, const N: usize> Iterator for Iter<'a, T, N> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+        unsafe {
+            let mut chunk = self.head?;
+            if self.head_index == chunk.as_ref().len {
+                let next = Chunk::get_element(self.last_head, chunk.as_ref().reference);
+                self.last_head = mem::replace(&mut self.head, next);
+                self.head_index = 0;
+                chunk = next?;
+            }
+            let element = &*chunk.as_ref().as_ptr().add(self.head_index);
+            self.head_index += 1;
+            self.len -= 1;
+            Some(element)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for Iter<'a, T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+        unsafe {
+            let mut chunk = self.tail?;
+            if self.tail_index == 0 {
+                let prev = Chunk::get_element(self.last_tail, chunk.as_ref().reference);
+                self.last_tail = mem::replace(&mut self.tail, prev);
+                chunk = prev?;
+                self.tail_index = chunk.as_ref().len;
+            }
+            self.tail_index -= 1;
+            self.len -= 1;
+            Some(&*chunk.as_ref().as_ptr().add(self.tail_index))
+        }
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for Iter<'a, T, N> {}
+
+impl<'a, T, const N: usize> Default for Iter<'a, T, N> {
+    /// Creates an empty `unrolled_xor_list::Iter`
+    #[inline]
+    fn default() -> Self {
+        Iter {
+            head: None,
+            tail: None,
+            last_head: None,
+            last_tail: None,
+            head_index: 0,
+            tail_index: 0,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, const N: usize> FusedIterator for Iter<'a, T, N> {}
+
+impl<'a, T, const N: usize> Iterator for IterMut<'a, T, N> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            return None;
+        }
+        unsafe {
+            let mut chunk = self.head?;
+            if self.head_index == chunk.as_ref().len {
+                let next = Chunk::get_element(self.last_head, chunk.as_ref().reference);
+                self.last_head = mem::replace(&mut self.head, next);
+                self.head_index = 0;
+                chunk = next?;
+            }
+            let element = &mut *chunk.as_mut().as_mut_ptr().add(self.head_index);
+            self.head_index += 1;
+            self.len -= 1;
+            Some(element)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for IterMut<'a, T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            return None;
+        }
+        unsafe {
+            let mut chunk = self.tail?;
+            if self.tail_index == 0 {
+                let prev = Chunk::get_element(self.last_tail, chunk.as_ref().reference);
+                self.last_tail = mem::replace(&mut self.tail, prev);
+                chunk = prev?;
+                self.tail_index = chunk.as_ref().len;
+            }
+            self.tail_index -= 1;
+            self.len -= 1;
+            Some(&mut *chunk.as_mut().as_mut_ptr().add(self.tail_index))
+        }
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for IterMut<'a, T, N> {}
+
+impl<'a, T, const N: usize> Default for IterMut<'a, T, N> {
+    /// Creates an empty `unrolled_xor_list::IterMut`
+    #[inline]
+    fn default() -> Self {
+        IterMut {
+            head: None,
+            tail: None,
+            last_head: None,
+            last_tail: None,
+            head_index: 0,
+            tail_index: 0,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, const N: usize> FusedIterator for IterMut<'a, T, N> {}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> Default for IntoIter<T, N> {
+    /// Creates an empty `unrolled_xor_list::IntoIter`
+    #[inline]
+    fn default() -> Self {
+        UnrolledXorList::new().into_iter()
+    }
+}
+
+impl<T, const N: usize> FusedIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> FromIterator<T> for UnrolledXorList<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T, const N: usize> IntoIterator for UnrolledXorList<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    /// Consumes the list into an iterator yielding elements by value
+    #[inline]
+    fn into_iter(self) -> IntoIter<T, N> {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a UnrolledXorList<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, N>;
+
+    fn into_iter(self) -> Iter<'a, T, N> {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut UnrolledXorList<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T, N>;
+
+    fn into_iter(self) -> IterMut<'a, T, N> {
+        self.iter_mut()
+    }
+}
+
+impl<T, const N: usize> Extend<T> for UnrolledXorList<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elt in iter {
+            self.push_back(elt);
+        }
+    }
+}
+
+impl<'a, T: 'a + Copy, const N: usize> Extend<&'a T> for UnrolledXorList<T, N> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for UnrolledXorList<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other)
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for UnrolledXorList<T, N> {}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for UnrolledXorList<T, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other)
+    }
+}
+
+impl<T: Ord, const N: usize> Ord for UnrolledXorList<T, N> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other)
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for UnrolledXorList<T, N> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T, const N: usize> Index<usize> for UnrolledXorList<T, N> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("Out of bounds access")
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for UnrolledXorList<T, N> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("Out of bounds access")
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for UnrolledXorList<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: Hash, const N: usize> Hash for UnrolledXorList<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for elt in self {
+            elt.hash(state);
+        }
+    }
+}
+
+// Ensure that `UnrolledXorList` and its read-only iterators are covariant in their type parameters
+#[allow(dead_code)]
+fn assert_covariance() {
+    fn a<'a>(x: UnrolledXorList<&'static str, 4>) -> UnrolledXorList<&'a str, 4> {
+        x
+    }
+    fn b<'i, 'a>(x: Iter<'i, &'static str, 4>) -> Iter<'i, &'a str, 4> {
+        x
+    }
+    fn c<'a>(x: IntoIter<&'static str, 4>) -> IntoIter<&'a str, 4> {
+        x
+    }
+}
+
+unsafe impl<T: Send, const N: usize> Send for UnrolledXorList<T, N> {}
+
+unsafe impl<T: Sync, const N: usize> Sync for UnrolledXorList<T, N> {}
+
+unsafe impl<'a, T: Sync, const N: usize> Send for Iter<'a, T, N> {}
+
+unsafe impl<'a, T: Sync, const N: usize> Sync for Iter<'a, T, N> {}
+
+unsafe impl<'a, T: Send, const N: usize> Send for IterMut<'a, T, N> {}
+
+unsafe impl<'a, T: Sync, const N: usize> Sync for IterMut<'a, T, N> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Chunk, UnrolledXorList};
+    use rand::{thread_rng, RngCore};
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    struct DropCounter(Rc<Cell<usize>>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    fn list_from<T: Clone, const N: usize>(v: &[T]) -> UnrolledXorList<T, N> {
+        v.iter().cloned().collect()
+    }
+
+    /// Checks the links in both directions and returns the number of elements
+    /// stored in every chunk.
+    fn check_links<T, const N: usize>(list: &UnrolledXorList<T, N>) -> Vec<usize> {
+        let mut lens = Vec::new();
+        let mut last = None;
+        let mut current = list.head;
+        while let Some(chunk) = current {
+            let chunk_len = unsafe { chunk.as_ref().len };
+            assert!(chunk_len > 0, "linked chunks must not be empty");
+            assert!(chunk_len <= N);
+            lens.push(chunk_len);
+            current = Chunk::get_element(last, unsafe { chunk.as_ref().reference });
+            last = Some(chunk);
+        }
+        assert_eq!(last, list.tail);
+        assert_eq!(lens.iter().sum::<usize>(), list.len);
+
+        let mut rev_lens = Vec::new();
+        last = None;
+        current = list.tail;
+        while let Some(chunk) = current {
+            rev_lens.push(unsafe { chunk.as_ref().len });
+            current = Chunk::get_element(last, unsafe { chunk.as_ref().reference });
+            last = Some(chunk);
+        }
+        assert_eq!(last, list.head);
+        rev_lens.reverse();
+        assert_eq!(lens, rev_lens);
+        lens
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let mut m: UnrolledXorList<i32, 3> = UnrolledXorList::new();
+        for i in 0..5 {
+            m.push_back(i);
+        }
+        assert_eq!(check_links(&m), vec![3, 2]);
+        m.push_front(-1);
+        assert_eq!(check_links(&m), vec![1, 3, 2]);
+        assert_eq!(m.pop_front(), Some(-1));
+        assert_eq!(check_links(&m), vec![3, 2]);
+        assert_eq!(m.pop_back(), Some(4));
+        assert_eq!(m.pop_back(), Some(3));
+        assert_eq!(check_links(&m), vec![3]);
+        assert_eq!(m.front(), Some(&0));
+        assert_eq!(m.back(), Some(&2));
+        assert_eq!(m.iter().rev().cloned().collect::<Vec<_>>(), vec![2, 1, 0]);
+        m.clear();
+        check_links(&m);
+        assert_eq!(m.pop_front(), None);
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut m: UnrolledXorList<i32, 4> = list_from(&[0, 1, 2, 3]);
+        m.insert(1, 10);
+        assert_eq!(check_links(&m), vec![3, 2]);
+        m.insert(4, 11);
+        assert_eq!(check_links(&m), vec![3, 3]);
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            vec![0, 10, 1, 2, 11, 3]
+        );
+
+        assert_eq!(m.remove(1), Some(10));
+        assert_eq!(m.remove(0), Some(0));
+        assert_eq!(check_links(&m), vec![4]);
+        assert_eq!(m.remove(0), Some(1));
+        assert_eq!(check_links(&m), vec![3]);
+        assert_eq!(m.remove(3), None);
+        assert_eq!(m[1], 11);
+
+        let mut n: UnrolledXorList<i32, 1> = list_from(&[0, 2]);
+        n.insert(1, 1);
+        n.insert(0, -1);
+        assert_eq!(check_links(&n), vec![1, 1, 1, 1]);
+        assert_eq!(n.iter().cloned().collect::<Vec<_>>(), vec![-1, 0, 1, 2]);
+    }
+
+    #[test]
+    fn test_append() {
+        let mut m: UnrolledXorList<i32, 4> = list_from(&[1, 2, 3]);
+        let mut n = list_from(&[4, 5]);
+        m.append(&mut n);
+        check_links(&n);
+        assert!(n.is_empty());
+        assert_eq!(check_links(&m), vec![3, 2]);
+
+        let mut o = list_from(&[6]);
+        m.append(&mut o);
+        assert_eq!(check_links(&m), vec![3, 3]);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[1, 2, 3, 4, 5, 6]);
+
+        let mut p = UnrolledXorList::new();
+        p.append(&mut m);
+        check_links(&p);
+        assert_eq!(p.len(), 6);
+        assert!(m.is_empty());
+    }
+
+    #[test]
+    fn test_split_off() {
+        let v: Vec<_> = (1..10).collect();
+        let m: UnrolledXorList<i32, 4> = list_from(&v);
+        for ix in 0..=v.len() {
+            let mut a = m.clone();
+            let b = a.split_off(ix);
+            check_links(&a);
+            check_links(&b);
+            assert_eq!(a.iter().cloned().collect::<Vec<_>>(), &v[..ix]);
+            assert_eq!(b.iter().cloned().collect::<Vec<_>>(), &v[ix..]);
+            assert_eq!(
+                b.iter().rev().cloned().collect::<Vec<_>>(),
+                v[ix..].iter().rev().cloned().collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_iter_both_ends() {
+        let mut m: UnrolledXorList<i32, 3> = (0..8).collect();
+        {
+            let mut iter = m.iter();
+            assert_eq!(iter.len(), 8);
+            assert_eq!(iter.next(), Some(&0));
+            assert_eq!(iter.next_back(), Some(&7));
+            assert_eq!(iter.next_back(), Some(&6));
+            assert_eq!(iter.next_back(), Some(&5));
+            assert_eq!(iter.clone().collect::<Vec<_>>(), vec![&1, &2, &3, &4]);
+            assert_eq!(iter.next(), Some(&1));
+            assert_eq!(iter.next_back(), Some(&4));
+            assert_eq!(iter.next(), Some(&2));
+            assert_eq!(iter.next(), Some(&3));
+            assert_eq!(iter.next(), None);
+            assert_eq!(iter.next_back(), None);
+        }
+        for elt in m.iter_mut().rev().take(4) {
+            *elt *= 10;
+        }
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 40, 50, 60, 70]
+        );
+    }
+
+    #[test]
+    fn test_drops() {
+        let drops = Rc::new(Cell::new(0));
+        {
+            let mut m: UnrolledXorList<_, 3> = UnrolledXorList::new();
+            for _ in 0..7 {
+                m.push_back(DropCounter(Rc::clone(&drops)));
+            }
+            m.remove(3);
+            assert_eq!(drops.get(), 1);
+            let mut n = m.split_off(4);
+            n.pop_back();
+            assert_eq!(drops.get(), 2);
+            drop(n);
+            assert_eq!(drops.get(), 3);
+        }
+        assert_eq!(drops.get(), 7);
+    }
+
+    #[test]
+    fn test_fuzz() {
+        let rounds = if cfg!(miri) { 2 } else { 25 };
+        for _ in 0..rounds {
+            fuzz_test::<1>(16);
+            fuzz_test::<4>(189);
+            fuzz_test::<16>(189);
+        }
+    }
+
+    fn fuzz_test<const N: usize>(sz: i32) {
+        let mut m: UnrolledXorList<_, N> = UnrolledXorList::new();
+        let mut v = vec![];
+        for i in 0..sz {
+            check_links(&m);
+            let r = thread_rng().next_u32() as usize;
+            match r % 8 {
+                0 => {
+                    assert_eq!(m.pop_back(), v.pop());
+                }
+                1 => {
+                    if !v.is_empty() {
+                        assert_eq!(m.pop_front(), Some(v.remove(0)));
+                    }
+                }
+                2 => {
+                    let index = (r >> 8) % (v.len() + 1);
+                    m.insert(index, i);
+                    v.insert(index, i);
+                }
+                3 => {
+                    if !v.is_empty() {
+                        let index = (r >> 8) % v.len();
+                        assert_eq!(m.remove(index), Some(v.remove(index)));
+                    }
+                }
+                4 | 5 => {
+                    m.push_front(-i);
+                    v.insert(0, -i);
+                }
+                _ => {
+                    m.push_back(i);
+                    v.push(i);
+                }
+            }
+        }
+
+        check_links(&m);
+        assert_eq!(
+            m.iter().rev().cloned().collect::<Vec<_>>(),
+            v.iter().rev().cloned().collect::<Vec<_>>()
+        );
+        assert_eq!(m.into_iter().collect::<Vec<_>>(), v);
+    }
+}
@@ -7,9 +7,55 @@
 //! [`LinkedList`]. In general, array-based containers are faster,
 //! more memory efficient and make better use of CPU cache.
 //!
+//! # Removing nodes from the middle
+//!
+//! A node only stores the XOR of the addresses of its neighbours, so it can only
+//! be unlinked if one of its neighbours is known as well. A pointer to a node
+//! alone is not enough, finding a neighbour means walking the list from one of
+//! its ends.
+//!
+//! The collections of this crate which remove nodes they hold a pointer to, like
+//! the [`LruCache`], the [`LfuCache`] and the [`TimerWheel`], therefore store
+//! the predecessor of every node next to its element and update it whenever
+//! the nodes around it change. Such a node takes as much space as a node of a
+//! regular doubly-linked list, but can still be linked, spliced and moved with
+//! the operations of the `XorLinkedList`. The [`HandleList`], which has no
+//! use for these operations, stores both neighbours of a node directly.
+//!
+//! # Pointer provenance
+//!
+//! Every node only stores the XOR of the addresses of its neighbours, so a
+//! neighbour pointer has to be recreated from a plain address while walking
+//! the list. This cannot be expressed with the strict provenance APIs: a pointer
+//! carries the provenance of a single allocation, but a link has to lead to either
+//! of two separately allocated neighbours. Deriving both from a common base would
+//! require all nodes to share one allocation, which in turn gives up the stable
+//! node addresses the [`LruCache`], the [`LfuCache`] and the [`TimerWheel`] rely on,
+//! and the constant time [`append`] and [`splice_after`].
+//!
+//! Therefore the list exposes the provenance of every linked node with
+//! [`expose_provenance`] and recreates pointers with [`with_exposed_provenance_mut`].
+//! This is sound under Rust's exposed provenance model and accepted by Miri in its
+//! default mode, whereas `-Zmiri-strict-provenance` rejects every such list.
+//!
+//! Code which has to run under strict provenance should use the
+//! [`ArenaXorLinkedList`] instead. It offers the same operations, but keeps its
+//! nodes in a single `Vec` and links them by the XOR of slot indices, so it never
+//! turns an integer into a pointer.
+//!
+//! [`ArenaXorLinkedList`]: ../arena_xor_linked_list/struct.ArenaXorLinkedList.html
+//! [`append`]: struct.XorLinkedList.html#method.append
+//! [`splice_after`]: struct.CursorMut.html#method.splice_after
+//! [`HandleList`]: ../handle_list/struct.HandleList.html
+//! [`LfuCache`]: ../lfu_cache/struct.LfuCache.html
+//! [`LruCache`]: ../lru_cache/struct.LruCache.html
+//! [`TimerWheel`]: ../timer_wheel/struct.TimerWheel.html
 //! [`LinkedList`]: ../linked_list/struct.LinkedList.html
 //! [`VecDeque`]: ../vec_deque/struct.VecDeque.html
+//! [`expose_provenance`]: https://doc.rust-lang.org/std/primitive.pointer.html#method.expose_provenance
+//! [`with_exposed_provenance_mut`]: https://doc.rust-lang.org/std/ptr/fn.with_exposed_provenance_mut.html
 
+use alloc::{self, Allocator, Global};
 use std::{
     cmp::Ordering,
     fmt,
@@ -17,7 +63,8 @@ use std::{
     iter::{FromIterator, FusedIterator},
     marker::PhantomData,
     mem,
-    ptr::{null_mut, NonNull},
+    ops::{Bound, Index, IndexMut, RangeBounds},
+    ptr::{self, NonNull},
 };
 
 /// A doubly-linked list with owned nodes
@@ -25,21 +72,34 @@ use std::{
 /// The `XorLinkedList` allows pushing and popping elements at either end
 /// in constant time.
 ///
+/// Removed nodes are not freed right away but kept in an internal pool and
+/// reused by later insertions. Use [`shrink_to_fit`] to release them.
+///
 /// Almost always it is better to use `Vec` or `VecDeque` instead of
 /// `XorLinkedList`. In general, array-based containers are faster,
 /// more memory efficient and make better use of CPU cache.
-pub struct XorLinkedList<T> {
+///
+/// [`shrink_to_fit`]: #method.shrink_to_fit
+pub struct XorLinkedList<T, A: Allocator = Global> {
     head: Option<NonNull<Node<T>>>,
     tail: Option<NonNull<Node<T>>>,
     len: usize,
+    pool: Option<NonNull<Node<T>>>,
+    pooled: usize,
+    alloc: A,
     marker: PhantomData<Node<T>>,
 }
 
-struct Node<T> {
-    reference: usize,
-    data: T,
+// Pooled nodes hold no data and store the address of the next pooled node
+// in `reference`.
+pub(crate) struct Node<T> {
+    pub(crate) reference: usize,
+    pub(crate) data: T,
 }
 
+/// A pair of adjacent nodes `(prev, node)` required to traverse the list starting at `node`
+type Position<T> = (Option<NonNull<Node<T>>>, Option<NonNull<Node<T>>>);
+
 /// An iterator over the elements of a `XorLinkedList`.
 ///
 /// This `struct` is created by the [`iter`] method on [`XorLinkedList`]. See its
@@ -47,7 +107,6 @@ struct Node<T> {
 ///
 /// [`iter`]: struct.XorLinkedList.html#method.iter
 /// [`XorLinkedList`]: struct.XorLinkedList.html
-#[derive(Clone)]
 pub struct Iter<'a, T: 'a> {
     head: Option<NonNull<Node<T>>>,
     tail: Option<NonNull<Node<T>>>,
@@ -57,6 +116,19 @@ pub struct Iter<'a, T: 'a> {
     marker: PhantomData<&'a Node<T>>,
 }
 
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            last_head: self.last_head,
+            last_tail: self.last_tail,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+}
+
 impl<'a, T: 'a + fmt::Debug> fmt::Debug for Iter<'a, T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_tuple("Iter").field(&self.len).finish()
@@ -92,58 +164,494 @@ impl<'a, T: 'a + fmt::Debug> fmt::Debug for IterMut<'a, T> {
 ///
 /// [`into_iter`]: struct.XorLinkedList.html#method.into_iter
 /// [`XorLinkedList`]: struct.XorLinkedList.html
-pub struct IntoIter<T> {
-    list: XorLinkedList<T>,
+pub struct IntoIter<T, A: Allocator = Global> {
+    list: XorLinkedList<T, A>,
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for IntoIter<T, A> {
+    fn clone(&self) -> Self {
+        IntoIter {
+            list: self.list.clone(),
+        }
+    }
 }
 
-impl<T: fmt::Debug> fmt::Debug for IntoIter<T> {
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for IntoIter<T, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_tuple("IntoIter").field(&self.list).finish()
     }
 }
 
-impl<T> Node<T> {
-    fn new(data: T) -> Node<T> {
-        Node { reference: 0, data }
+/// A cursor over a `XorLinkedList`.
+///
+/// A `Cursor` is like an iterator, except that it can freely seek back-and-forth. As a node
+/// of a `XorLinkedList` only stores the combination of both of its neighbours, the cursor
+/// keeps track of the previous node in addition to the current one.
+///
+/// Cursors always rest between two elements in the list, and index in a logically circular way.
+/// To accommodate this, there is a "ghost" non-element that yields `None` between the head and
+/// tail of the list.
+///
+/// This `struct` is created by the [`cursor_front`] and [`cursor_back`] methods on
+/// [`XorLinkedList`]. See their documentation for more.
+///
+/// [`cursor_front`]: struct.XorLinkedList.html#method.cursor_front
+/// [`cursor_back`]: struct.XorLinkedList.html#method.cursor_back
+/// [`XorLinkedList`]: struct.XorLinkedList.html
+pub struct Cursor<'a, T: 'a, A: 'a + Allocator = Global> {
+    index: usize,
+    current: Option<NonNull<Node<T>>>,
+    prev: Option<NonNull<Node<T>>>,
+    list: &'a XorLinkedList<T, A>,
+}
+
+impl<'a, T, A: Allocator> Clone for Cursor<'a, T, A> {
+    fn clone(&self) -> Self {
+        Cursor {
+            index: self.index,
+            current: self.current,
+            prev: self.prev,
+            list: self.list,
+        }
     }
+}
 
-    fn into_data(self) -> T {
-        self.data
+impl<'a, T: 'a + fmt::Debug, A: Allocator> fmt::Debug for Cursor<'a, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Cursor")
+            .field(&self.list)
+            .field(&self.index())
+            .finish()
     }
 }
 
-// private methods
-impl<T> XorLinkedList<T> {
+/// A cursor over a `XorLinkedList` with editing operations.
+///
+/// A `CursorMut` is like an iterator, except that it can freely seek back-and-forth, and can
+/// safely mutate the list during iteration. As a node of a `XorLinkedList` only stores the
+/// combination of both of its neighbours, the cursor keeps track of the previous node in
+/// addition to the current one.
+///
+/// Cursors always rest between two elements in the list, and index in a logically circular way.
+/// To accommodate this, there is a "ghost" non-element that yields `None` between the head and
+/// tail of the list.
+///
+/// This `struct` is created by the [`cursor_front_mut`] and [`cursor_back_mut`] methods on
+/// [`XorLinkedList`]. See their documentation for more.
+///
+/// [`cursor_front_mut`]: struct.XorLinkedList.html#method.cursor_front_mut
+/// [`cursor_back_mut`]: struct.XorLinkedList.html#method.cursor_back_mut
+/// [`XorLinkedList`]: struct.XorLinkedList.html
+pub struct CursorMut<'a, T: 'a, A: 'a + Allocator = Global> {
+    index: usize,
+    current: Option<NonNull<Node<T>>>,
+    prev: Option<NonNull<Node<T>>>,
+    list: &'a mut XorLinkedList<T, A>,
+}
+
+impl<'a, T: 'a + fmt::Debug, A: Allocator> fmt::Debug for CursorMut<'a, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("CursorMut")
+            .field(&self.list)
+            .field(&self.index())
+            .finish()
+    }
+}
+
+/// A draining iterator over a range of elements of a `XorLinkedList`.
+///
+/// This `struct` is created by the [`drain`] method on [`XorLinkedList`]. See its
+/// documentation for more.
+///
+/// [`drain`]: struct.XorLinkedList.html#method.drain
+/// [`XorLinkedList`]: struct.XorLinkedList.html
+pub struct Drain<'a, T: 'a, A: 'a + Allocator = Global> {
+    list: &'a mut XorLinkedList<T, A>,
+    head: Option<NonNull<Node<T>>>,
+    tail: Option<NonNull<Node<T>>>,
+    len: usize,
+}
+
+impl<'a, T: 'a + fmt::Debug, A: Allocator> fmt::Debug for Drain<'a, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Drain")
+            .field(&self.iter().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// An iterator produced by calling `extract_if` on a `XorLinkedList`.
+///
+/// This `struct` is created by the [`extract_if`] method on [`XorLinkedList`]. See its
+/// documentation for more.
+///
+/// [`extract_if`]: struct.XorLinkedList.html#method.extract_if
+/// [`XorLinkedList`]: struct.XorLinkedList.html
+pub struct ExtractIf<'a, T: 'a, F, A: 'a + Allocator = Global>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    cursor: CursorMut<'a, T, A>,
+    pred: F,
+}
+
+impl<'a, T: 'a + fmt::Debug, F, A: Allocator> fmt::Debug for ExtractIf<'a, T, F, A>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ExtractIf").field(&self.cursor.list).finish()
+    }
+}
+
+impl<T> Node<T> {
+    fn new(data: T) -> Node<T> {
+        Node { reference: 0, data }
+    }
+
+    /// Combines the addresses of both neighbours into a node reference. The
+    /// provenance of both nodes is exposed, so that `get_element` is allowed to
+    /// recreate pointers to them later on.
     fn calculate_reference(
         previous: Option<NonNull<Node<T>>>,
         next: Option<NonNull<Node<T>>>,
     ) -> usize {
-        let pr = previous.map(|i| i.as_ptr()).unwrap_or(null_mut());
-        let ne = next.map(|i| i.as_ptr()).unwrap_or(null_mut());
-        pr as usize ^ ne as usize
+        let pr = previous.map_or(0, |i| i.as_ptr().expose_provenance());
+        let ne = next.map_or(0, |i| i.as_ptr().expose_provenance());
+        pr ^ ne
     }
 
-    fn get_element(
+    /// Recreates the pointer to the other neighbour of a node from its reference
+    /// using the provenance exposed by `calculate_reference`.
+    pub(crate) fn get_element(
         previous_or_next: Option<NonNull<Node<T>>>,
         reference: usize,
     ) -> Option<NonNull<Node<T>>> {
-        let other = previous_or_next.map(|i| i.as_ptr()).unwrap_or(null_mut());
-        NonNull::new((other as usize ^ reference) as *mut Node<T>)
+        let other = previous_or_next.map_or(0, |i| i.as_ptr().addr());
+        NonNull::new(ptr::with_exposed_provenance_mut(other ^ reference))
+    }
+}
+
+// private methods, some of them are used by other collections of this crate
+//
+// The helpers used by other collections only access nodes through raw pointers.
+// A reference to a whole node would invalidate the pointers into its element
+// those collections keep, like the keys indexed by the caches.
+impl<T, A: Allocator> XorLinkedList<T, A> {
+    /// Links the given node in between the two adjacent nodes `prev` and `next`.
+    /// `None` denotes the position before the head or after the tail.
+    #[inline]
+    pub(crate) unsafe fn link_node(
+        &mut self,
+        prev: Option<NonNull<Node<T>>>,
+        next: Option<NonNull<Node<T>>>,
+        node: NonNull<Node<T>>,
+    ) {
+        (*node.as_ptr()).reference = Node::calculate_reference(prev, next);
+        match prev {
+            Some(prev_node) => {
+                (*prev_node.as_ptr()).reference ^= Node::calculate_reference(next, Some(node));
+            }
+            None => self.head = Some(node),
+        }
+        match next {
+            Some(next_node) => {
+                (*next_node.as_ptr()).reference ^= Node::calculate_reference(prev, Some(node));
+            }
+            None => self.tail = Some(node),
+        }
+        self.len += 1;
+    }
+
+    /// Unlinks the given node which resides in between `prev` and `next` and
+    /// connects both neighbours with each other. The caller takes over the
+    /// ownership of the returned node.
+    #[inline]
+    pub(crate) unsafe fn unlink_node(
+        &mut self,
+        prev: Option<NonNull<Node<T>>>,
+        node: NonNull<Node<T>>,
+        next: Option<NonNull<Node<T>>>,
+    ) -> NonNull<Node<T>> {
+        match prev {
+            Some(prev_node) => {
+                (*prev_node.as_ptr()).reference ^= Node::calculate_reference(Some(node), next);
+            }
+            None => self.head = next,
+        }
+        match next {
+            Some(next_node) => {
+                (*next_node.as_ptr()).reference ^= Node::calculate_reference(Some(node), prev);
+            }
+            None => self.tail = prev,
+        }
+        self.len -= 1;
+        node
+    }
+
+    /// Provides a cursor with editing operations at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at >= len`.
+    fn cursor_mut_at(&mut self, at: usize) -> CursorMut<'_, T, A> {
+        let (current, prev) = self.node_at(at);
+        CursorMut {
+            index: at,
+            current: Some(current),
+            prev,
+            list: self,
+        }
+    }
+
+    /// Returns the node in front of the tail.
+    #[inline]
+    fn tail_prev(&self) -> Option<NonNull<Node<T>>> {
+        self.tail
+            .and_then(|tail| unsafe { Node::get_element(None, tail.as_ref().reference) })
+    }
+
+    /// Returns the node at the given index together with the node in front of it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at >= len`.
+    fn node_at(&self, at: usize) -> (NonNull<Node<T>>, Option<NonNull<Node<T>>>) {
+        let len = self.len();
+        assert!(at < len, "Cannot access a nonexistent index");
+
+        // Below, we iterate towards the `at`th node, either from the start or the end,
+        // depending on which would be faster
+        let node = if at <= len - 1 - at {
+            let mut iter = self.iter();
+            let mut last = None;
+            // instead of skipping using .skip() (which creates a new struct),
+            // we skip manually so we can access the head field without
+            // depending on implementation details of Skip
+            for _ in 0..at {
+                last = iter.head;
+                iter.next();
+            }
+            (iter.head, last)
+        } else {
+            // better off starting from the end
+            let mut iter = self.iter();
+            let mut last = None;
+            for _ in 0..len - at {
+                last = iter.tail;
+                iter.next_back();
+            }
+            (last, iter.tail)
+        };
+        (node.0.expect("`at` is within the list"), node.1)
+    }
+
+    /// Moves all nodes of `other` in between the two adjacent nodes `prev` and `next`.
+    /// `None` denotes the position before the head or after the tail. After this
+    /// operation, `other` becomes empty.
+    unsafe fn splice_between(
+        &mut self,
+        prev: Option<NonNull<Node<T>>>,
+        next: Option<NonNull<Node<T>>>,
+        other: &mut Self,
+    ) {
+        let (mut other_head, mut other_tail) = match (other.head.take(), other.tail.take()) {
+            (Some(head), Some(tail)) => (head, tail),
+            _ => return,
+        };
+
+        let other_head_next = Node::get_element(None, other_head.as_ref().reference);
+        other_head.as_mut().reference = Node::calculate_reference(prev, other_head_next);
+        match prev {
+            Some(mut prev_node) => {
+                let prev_prev = Node::get_element(next, prev_node.as_ref().reference);
+                prev_node.as_mut().reference =
+                    Node::calculate_reference(prev_prev, Some(other_head));
+            }
+            None => self.head = Some(other_head),
+        }
+
+        let other_tail_prev = if other_head == other_tail {
+            prev
+        } else {
+            Node::get_element(None, other_tail.as_ref().reference)
+        };
+        other_tail.as_mut().reference = Node::calculate_reference(other_tail_prev, next);
+        match next {
+            Some(mut next_node) => {
+                let next_next = Node::get_element(prev, next_node.as_ref().reference);
+                next_node.as_mut().reference =
+                    Node::calculate_reference(Some(other_tail), next_next);
+            }
+            None => self.tail = Some(other_tail),
+        }
+
+        self.len += mem::replace(&mut other.len, 0);
+    }
+
+    /// Splits the list after the given node, which is the `at - 1`th element of the
+    /// list and follows `element_before`. Returns everything after the given node.
+    unsafe fn split_after_node(
+        &mut self,
+        mut element: NonNull<Node<T>>,
+        element_before: Option<NonNull<Node<T>>>,
+        at: usize,
+    ) -> Self
+    where
+        A: Clone,
+    {
+        // The split node is the new tail node of the first part and owns
+        // the head of the second part
+        let mut second_part_head = None;
+
+        let next_element = Node::get_element(element_before, element.as_ref().reference);
+        element.as_mut().reference = Node::calculate_reference(element_before, None);
+        if let Some(mut next) = next_element {
+            let next_next_element = Node::get_element(Some(element), next.as_ref().reference);
+            next.as_mut().reference = Node::calculate_reference(None, next_next_element);
+            second_part_head = Some(next);
+        }
+
+        let second_part = XorLinkedList {
+            head: second_part_head,
+            tail: if second_part_head.is_some() {
+                self.tail
+            } else {
+                None
+            },
+            len: self.len - at,
+            pool: None,
+            pooled: 0,
+            alloc: self.alloc.clone(),
+            marker: PhantomData,
+        };
+
+        // Fix the tail ptr of the first part
+        self.tail = Some(element);
+        self.len = at;
+
+        second_part
+    }
+
+    /// Advances the adjacent node pair `(prev, node)` by `steps` nodes towards the back.
+    #[inline]
+    unsafe fn walk((mut prev, mut node): Position<T>, steps: usize) -> Position<T> {
+        for _ in 0..steps {
+            match node {
+                Some(current) => {
+                    let next = Node::get_element(prev, current.as_ref().reference);
+                    prev = node;
+                    node = next;
+                }
+                None => break,
+            }
+        }
+        (prev, node)
+    }
+
+    /// Merges the two adjacent, sorted runs of `left_len` and `right_len` nodes which
+    /// start at `first` by relinking the nodes of the right run into the left one.
+    /// Returns the adjacent node pair following the merged run.
+    unsafe fn merge_runs<F>(
+        &mut self,
+        (prev, first): Position<T>,
+        mut left_len: usize,
+        mut right_len: usize,
+        compare: &mut F,
+    ) -> Position<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let (mut left_prev, mut left) = (prev, first);
+        let (right_prev, mut right) = Self::walk((prev, first), left_len);
+        while left_len > 0 && right_len > 0 {
+            let (left_node, right_node) = match (left, right) {
+                (Some(left_node), Some(right_node)) => (left_node, right_node),
+                _ => break,
+            };
+            if compare(&right_node.as_ref().data, &left_node.as_ref().data) == Ordering::Less {
+                // Only move nodes of the right run to keep the sort stable
+                let right_next = Node::get_element(right_prev, right_node.as_ref().reference);
+                let node = self.unlink_node(right_prev, right_node, right_next);
+                self.link_node(left_prev, left, node);
+                left_prev = Some(node);
+                right = right_next;
+                right_len -= 1;
+            } else {
+                left = Node::get_element(left_prev, left_node.as_ref().reference);
+                left_prev = Some(left_node);
+                left_len -= 1;
+            }
+        }
+        Self::walk((right_prev, right), right_len)
+    }
+
+    /// Creates a node holding the given element, reusing a pooled node if
+    /// there is one.
+    #[inline]
+    pub(crate) fn allocate_node(&mut self, data: T) -> NonNull<Node<T>> {
+        match self.pool {
+            Some(node) => unsafe {
+                self.pool = Node::get_element(None, (*node.as_ptr()).reference);
+                self.pooled -= 1;
+                node.as_ptr().write(Node::new(data));
+                node
+            },
+            None => unsafe {
+                let node = alloc::allocate::<Node<T>, A>(&self.alloc);
+                node.as_ptr().write(Node::new(data));
+                node
+            },
+        }
+    }
+
+    /// Moves the element out of the given unlinked node and adds the node to
+    /// the pool.
+    #[inline]
+    pub(crate) unsafe fn release_node(&mut self, node: NonNull<Node<T>>) -> T {
+        let data = ptr::read(ptr::addr_of!((*node.as_ptr()).data));
+        (*node.as_ptr()).reference = Node::calculate_reference(self.pool, None);
+        self.pool = Some(node);
+        self.pooled += 1;
+        data
+    }
+
+    /// Moves all linked nodes into a new list, leaving the pool behind.
+    #[inline]
+    fn take_nodes(&mut self) -> Self
+    where
+        A: Clone,
+    {
+        XorLinkedList {
+            head: self.head.take(),
+            tail: self.tail.take(),
+            len: mem::replace(&mut self.len, 0),
+            pool: None,
+            pooled: 0,
+            alloc: self.alloc.clone(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the node at the front of the list.
+    #[inline]
+    pub(crate) fn front_node(&self) -> Option<NonNull<Node<T>>> {
+        self.head
     }
 
     /// Adds the given node to the front of the list.
     #[inline]
-    fn push_front_node(&mut self, mut node: NonNull<Node<T>>) {
+    pub(crate) fn push_front_node(&mut self, node: NonNull<Node<T>>) {
         unsafe {
             match self.head {
                 None => {
-                    node.as_mut().reference = 0;
+                    (*node.as_ptr()).reference = 0;
                     self.tail = Some(node);
                 }
-                Some(mut head) => {
-                    let next_head = Self::get_element(None, head.as_ref().reference);
-                    head.as_mut().reference = Self::calculate_reference(Some(node), next_head);
-                    node.as_mut().reference = Self::calculate_reference(None, Some(head));
+                Some(head) => {
+                    let next_head = Node::get_element(None, (*head.as_ptr()).reference);
+                    (*head.as_ptr()).reference = Node::calculate_reference(Some(node), next_head);
+                    (*node.as_ptr()).reference = Node::calculate_reference(None, Some(head));
                 }
             }
             self.head = Some(node);
@@ -151,36 +659,36 @@ impl<T> XorLinkedList<T> {
         }
     }
 
-    /// Removes and returns the node at the front of the list.
+    /// Removes the node at the front of the list. The caller takes over the
+    /// ownership of the returned node.
     #[inline]
-    fn pop_front_node(&mut self) -> Option<Box<Node<T>>> {
-        self.head.map(|node| unsafe {
-            if let Some(mut new_head) = Self::get_element(None, node.as_ref().reference) {
-                let next_new_head = Self::get_element(Some(node), new_head.as_ref().reference);
-                new_head.as_mut().reference = Self::calculate_reference(None, next_new_head);
+    pub(crate) fn pop_front_node(&mut self) -> Option<NonNull<Node<T>>> {
+        self.head.inspect(|&node| unsafe {
+            if let Some(new_head) = Node::get_element(None, (*node.as_ptr()).reference) {
+                let next_new_head = Node::get_element(Some(node), (*new_head.as_ptr()).reference);
+                (*new_head.as_ptr()).reference = Node::calculate_reference(None, next_new_head);
                 self.head = Some(new_head);
             } else {
                 self.head = None;
                 self.tail = None;
             }
             self.len -= 1;
-            Box::from_raw(node.as_ptr())
         })
     }
 
     /// Adds the given node to the back of the list.
     #[inline]
-    fn push_back_node(&mut self, mut node: NonNull<Node<T>>) {
+    pub(crate) fn push_back_node(&mut self, node: NonNull<Node<T>>) {
         unsafe {
             match self.tail {
                 None => {
-                    node.as_mut().reference = 0;
+                    (*node.as_ptr()).reference = 0;
                     self.head = Some(node);
                 }
-                Some(mut tail) => {
-                    let prev_head = Self::get_element(None, tail.as_ref().reference);
-                    tail.as_mut().reference = Self::calculate_reference(prev_head, Some(node));
-                    node.as_mut().reference = Self::calculate_reference(Some(tail), None);
+                Some(tail) => {
+                    let prev_head = Node::get_element(None, (*tail.as_ptr()).reference);
+                    (*tail.as_ptr()).reference = Node::calculate_reference(prev_head, Some(node));
+                    (*node.as_ptr()).reference = Node::calculate_reference(Some(tail), None);
                 }
             }
             self.tail = Some(node);
@@ -188,20 +696,20 @@ impl<T> XorLinkedList<T> {
         }
     }
 
-    /// Removes and returns the node at the back of the list.
+    /// Removes the node at the back of the list. The caller takes over the
+    /// ownership of the returned node.
     #[inline]
-    fn pop_back_node(&mut self) -> Option<Box<Node<T>>> {
-        self.tail.map(|node| unsafe {
-            if let Some(mut new_tail) = Self::get_element(None, node.as_ref().reference) {
-                let next_new_tail = Self::get_element(Some(node), new_tail.as_ref().reference);
-                new_tail.as_mut().reference = Self::calculate_reference(next_new_tail, None);
+    pub(crate) fn pop_back_node(&mut self) -> Option<NonNull<Node<T>>> {
+        self.tail.inspect(|&node| unsafe {
+            if let Some(new_tail) = Node::get_element(None, (*node.as_ptr()).reference) {
+                let next_new_tail = Node::get_element(Some(node), (*new_tail.as_ptr()).reference);
+                (*new_tail.as_ptr()).reference = Node::calculate_reference(next_new_tail, None);
                 self.tail = Some(new_tail);
             } else {
                 self.head = None;
                 self.tail = None;
             }
             self.len -= 1;
-            Box::from_raw(node.as_ptr())
         })
     }
 }
@@ -226,79 +734,255 @@ impl<T> XorLinkedList<T> {
     /// ```
     #[inline]
     pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    /// Creates an empty `XorLinkedList` with preallocated nodes for at least
+    /// `capacity` elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let list: XorLinkedList<u32> = XorLinkedList::with_capacity(10);
+    /// assert_eq!(list.capacity(), 10);
+    /// ```
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+}
+
+impl<T, A: Allocator> XorLinkedList<T, A> {
+    /// Creates an empty `XorLinkedList` whose nodes are allocated by `alloc`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::{alloc::Global, XorLinkedList};
+    ///
+    /// let list: XorLinkedList<u32> = XorLinkedList::new_in(Global);
+    /// ```
+    #[inline]
+    pub fn new_in(alloc: A) -> Self {
         XorLinkedList {
             head: None,
             tail: None,
             len: 0,
+            pool: None,
+            pooled: 0,
+            alloc,
             marker: PhantomData,
         }
     }
 
-    /// Moves all elements from `other` to the end of the list.
-    ///
-    /// This reuses all the nodes from `other` and moves them into `self`. After
-    /// this operation, `other` becomes empty.
-    ///
-    /// This operation should compute in O(1) time and O(1) memory.
+    /// Creates an empty `XorLinkedList` whose nodes are allocated by `alloc`. This
+    /// is the same as [`new_in`].
     ///
     /// # Examples
     ///
     /// ```
-    /// use rust_utils::XorLinkedList;
+    /// use rust_utils::{alloc::Global, XorLinkedList};
     ///
-    /// let mut list1 = XorLinkedList::new();
-    /// list1.push_back('a');
+    /// let list: XorLinkedList<u32> = XorLinkedList::with_allocator(Global);
+    /// ```
     ///
-    /// let mut list2 = XorLinkedList::new();
-    /// list2.push_back('b');
-    /// list2.push_back('c');
+    /// [`new_in`]: #method.new_in
+    #[inline]
+    pub fn with_allocator(alloc: A) -> Self {
+        Self::new_in(alloc)
+    }
+
+    /// Creates an empty `XorLinkedList` whose nodes are allocated by `alloc`,
+    /// with preallocated nodes for at least `capacity` elements
     ///
-    /// list1.append(&mut list2);
+    /// # Examples
     ///
-    /// let mut iter = list1.iter();
-    /// assert_eq!(iter.next(), Some(&'a'));
-    /// assert_eq!(iter.next(), Some(&'b'));
-    /// assert_eq!(iter.next(), Some(&'c'));
-    /// assert!(iter.next().is_none());
+    /// ```
+    /// use rust_utils::{alloc::Global, XorLinkedList};
     ///
-    /// assert!(list2.is_empty());
+    /// let list: XorLinkedList<u32> = XorLinkedList::with_capacity_in(10, Global);
+    /// assert_eq!(list.capacity(), 10);
     /// ```
-    pub fn append(&mut self, other: &mut Self) {
-        match self.tail {
-            None => mem::swap(self, other),
-            Some(mut tail) => {
-                if let Some(mut other_head) = other.head.take() {
-                    unsafe {
-                        let other_head_next =
-                            Self::get_element(None, other_head.as_ref().reference);
-                        other_head.as_mut().reference =
-                            Self::calculate_reference(Some(tail), other_head_next);
-
-                        let tail_prev = Self::get_element(None, tail.as_ref().reference);
-                        tail.as_mut().reference =
-                            Self::calculate_reference(tail_prev, Some(other_head));
-                    }
+    #[inline]
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        let mut list = Self::new_in(alloc);
+        list.reserve(capacity);
+        list
+    }
 
-                    self.tail = other.tail.take();
-                    self.len += mem::replace(&mut other.len, 0);
-                }
-            }
-        }
+    /// Returns a reference to the underlying allocator
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        &self.alloc
     }
 
-    /// Provides a forward iterator
+    /// Returns the number of elements the list can hold without allocating,
+    /// which are the linked nodes and the pooled nodes.
     ///
     /// # Examples
     ///
     /// ```
     /// use rust_utils::XorLinkedList;
     ///
-    /// let mut list: XorLinkedList<u32> = XorLinkedList::new();
-    ///
-    /// list.push_back(0);
+    /// let mut list = XorLinkedList::new();
     /// list.push_back(1);
     /// list.push_back(2);
-    ///
+    /// list.pop_back();
+    /// assert_eq!(list.capacity(), 2);
+    /// ```
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.len + self.pooled
+    }
+
+    /// Preallocates nodes for at least `additional` more elements to be inserted
+    /// without allocating.
+    ///
+    /// This operation should compute in O(additional) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list = XorLinkedList::new();
+    /// list.push_back(1);
+    /// list.reserve(10);
+    /// assert_eq!(list.capacity(), 11);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        while self.pooled < additional {
+            let node = alloc::allocate::<Node<T>, A>(&self.alloc);
+            unsafe {
+                (*node.as_ptr()).reference = Node::calculate_reference(self.pool, None);
+            }
+            self.pool = Some(node);
+            self.pooled += 1;
+        }
+    }
+
+    /// Frees all pooled nodes, so that the capacity equals the length of the list.
+    ///
+    /// This operation should compute in O(n) time, where `n` is the number of
+    /// pooled nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list = XorLinkedList::with_capacity(10);
+    /// list.push_back(1);
+    /// list.shrink_to_fit();
+    /// assert_eq!(list.capacity(), 1);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        while let Some(node) = self.pool {
+            unsafe {
+                self.pool = Node::get_element(None, (*node.as_ptr()).reference);
+                alloc::deallocate(&self.alloc, node);
+            }
+        }
+        self.pooled = 0;
+    }
+
+    /// Moves all elements from `other` to the end of the list.
+    ///
+    /// This reuses all the nodes from `other` and moves them into `self`. After
+    /// this operation, `other` becomes empty.
+    ///
+    /// This operation should compute in O(1) time and O(1) memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list1 = XorLinkedList::new();
+    /// list1.push_back('a');
+    ///
+    /// let mut list2 = XorLinkedList::new();
+    /// list2.push_back('b');
+    /// list2.push_back('c');
+    ///
+    /// list1.append(&mut list2);
+    ///
+    /// let mut iter = list1.iter();
+    /// assert_eq!(iter.next(), Some(&'a'));
+    /// assert_eq!(iter.next(), Some(&'b'));
+    /// assert_eq!(iter.next(), Some(&'c'));
+    /// assert!(iter.next().is_none());
+    ///
+    /// assert!(list2.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut Self) {
+        unsafe {
+            let tail = self.tail;
+            self.splice_between(tail, None, other);
+        }
+    }
+
+    /// Reverses the order of the elements in place.
+    ///
+    /// As every node stores the combination of both of its neighbours, the list
+    /// is symmetric and swapping the head with the tail reverses it without
+    /// touching any node.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<u32> = (0..4).collect();
+    ///
+    /// list.reverse();
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![3, 2, 1, 0]);
+    ///
+    /// list.push_back(4);
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![3, 2, 1, 0, 4]);
+    /// ```
+    #[inline]
+    pub fn reverse(&mut self) {
+        mem::swap(&mut self.head, &mut self.tail);
+    }
+
+    /// Consumes the list and returns it with the order of its elements reversed.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let list: XorLinkedList<u32> = (0..4).collect();
+    ///
+    /// let reversed: Vec<u32> = list.reversed().into_iter().collect();
+    /// assert_eq!(reversed, vec![3, 2, 1, 0]);
+    /// ```
+    #[inline]
+    pub fn reversed(mut self) -> Self {
+        self.reverse();
+        self
+    }
+
+    /// Provides a forward iterator
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<u32> = XorLinkedList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(1);
+    /// list.push_back(2);
+    ///
     /// let mut iter = list.iter();
     /// assert_eq!(iter.next(), Some(&0));
     /// assert_eq!(iter.next(), Some(&1));
@@ -306,7 +990,7 @@ impl<T> XorLinkedList<T> {
     /// assert_eq!(iter.next(), None);
     /// ```
     #[inline]
-    pub fn iter(&self) -> Iter<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
         Iter {
             head: self.head,
             tail: self.tail,
@@ -341,7 +1025,7 @@ impl<T> XorLinkedList<T> {
     /// assert_eq!(iter.next(), None);
     /// ```
     #[inline]
-    pub fn iter_mut(&mut self) -> IterMut<T> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         IterMut {
             head: self.head,
             tail: self.tail,
@@ -352,6 +1036,142 @@ impl<T> XorLinkedList<T> {
         }
     }
 
+    /// Provides a cursor at the front element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<u32> = XorLinkedList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(1);
+    ///
+    /// let mut cursor = list.cursor_front();
+    /// assert_eq!(cursor.current(), Some(&0));
+    /// cursor.move_next();
+    /// assert_eq!(cursor.current(), Some(&1));
+    /// cursor.move_next();
+    /// assert_eq!(cursor.current(), None);
+    /// cursor.move_prev();
+    /// assert_eq!(cursor.current(), Some(&1));
+    /// ```
+    #[inline]
+    pub fn cursor_front(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            index: 0,
+            current: self.head,
+            prev: None,
+            list: self,
+        }
+    }
+
+    /// Provides a cursor at the back element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<u32> = XorLinkedList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(1);
+    ///
+    /// let mut cursor = list.cursor_back();
+    /// assert_eq!(cursor.current(), Some(&1));
+    /// assert_eq!(cursor.index(), Some(1));
+    /// assert_eq!(cursor.peek_prev(), Some(&0));
+    /// ```
+    #[inline]
+    pub fn cursor_back(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            index: self.len.saturating_sub(1),
+            current: self.tail,
+            prev: self.tail_prev(),
+            list: self,
+        }
+    }
+
+    /// Provides a cursor with editing operations at the front element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<u32> = XorLinkedList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(2);
+    ///
+    /// {
+    ///     let mut cursor = list.cursor_front_mut();
+    ///     cursor.insert_after(1);
+    ///     assert_eq!(cursor.current(), Some(&mut 0));
+    ///     assert_eq!(cursor.peek_next(), Some(&mut 1));
+    /// }
+    ///
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&0));
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next(), Some(&2));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[inline]
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T, A> {
+        CursorMut {
+            index: 0,
+            current: self.head,
+            prev: None,
+            list: self,
+        }
+    }
+
+    /// Provides a cursor with editing operations at the back element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<u32> = XorLinkedList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(2);
+    ///
+    /// {
+    ///     let mut cursor = list.cursor_back_mut();
+    ///     cursor.insert_before(1);
+    ///     assert_eq!(cursor.current(), Some(&mut 2));
+    ///     assert_eq!(cursor.peek_prev(), Some(&mut 1));
+    /// }
+    ///
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&0));
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next(), Some(&2));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    #[inline]
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T, A> {
+        CursorMut {
+            index: self.len.saturating_sub(1),
+            current: self.tail,
+            prev: self.tail_prev(),
+            list: self,
+        }
+    }
+
     /// Returns `true` if the `XorLinkedList` is empty
     ///
     /// This operation should compute in O(1) time.
@@ -397,10 +1217,13 @@ impl<T> XorLinkedList<T> {
         self.len
     }
 
-    /// Removes all elements from the `XorLinkedList`.
+    /// Removes all elements from the `XorLinkedList` and frees their nodes. Nodes
+    /// which are already pooled are kept, use [`shrink_to_fit`] to free them as well.
     ///
     /// This operation should compute in O(n) time
     ///
+    /// [`shrink_to_fit`]: #method.shrink_to_fit
+    ///
     /// # Examples
     ///
     /// ```
@@ -417,9 +1240,13 @@ impl<T> XorLinkedList<T> {
     /// assert_eq!(dl.len(), 0);
     /// assert_eq!(dl.front(), None);
     /// ```
-    #[inline]
     pub fn clear(&mut self) {
-        *self = Self::new();
+        while let Some(node) = self.pop_back_node() {
+            unsafe {
+                ptr::drop_in_place(node.as_ptr());
+                alloc::deallocate(&self.alloc, node);
+            }
+        }
     }
 
     /// Returns `true` if the `XorLinkedList` contains an element equal to the
@@ -534,6 +1361,60 @@ impl<T> XorLinkedList<T> {
         unsafe { self.tail.as_mut().map(|node| &mut node.as_mut().data) }
     }
 
+    /// Provides a reference to the element at the given index, or `None` if
+    /// the index is out of bounds
+    ///
+    /// This operation should compute in O(min(i, n - i)) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut dl = XorLinkedList::new();
+    /// dl.push_back(3);
+    /// dl.push_back(4);
+    /// dl.push_back(5);
+    ///
+    /// assert_eq!(dl.get(1), Some(&4));
+    /// assert_eq!(dl.get(3), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            unsafe { Some(&(*self.node_at(index).0.as_ptr()).data) }
+        } else {
+            None
+        }
+    }
+
+    /// Provides a mutable reference to the element at the given index, or `None`
+    /// if the index is out of bounds
+    ///
+    /// This operation should compute in O(min(i, n - i)) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut dl = XorLinkedList::new();
+    /// dl.push_back(3);
+    /// dl.push_back(4);
+    /// dl.push_back(5);
+    ///
+    /// if let Some(elem) = dl.get_mut(1) {
+    ///     *elem = 7;
+    /// }
+    /// assert_eq!(dl.get(1), Some(&7));
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len {
+            unsafe { Some(&mut (*self.node_at(index).0.as_ptr()).data) }
+        } else {
+            None
+        }
+    }
+
     /// Adds an element first in the list.
     ///
     /// This operation should compute in O(1) time.
@@ -552,10 +1433,8 @@ impl<T> XorLinkedList<T> {
     /// assert_eq!(dl.front().unwrap(), &1);
     /// ```
     pub fn push_front(&mut self, data: T) {
-        unsafe {
-            let value = Box::new(Node::new(data));
-            self.push_front_node(NonNull::new_unchecked(Box::into_raw(value)));
-        }
+        let node = self.allocate_node(data);
+        self.push_front_node(node);
     }
 
     /// Removes the first element and returns it, or `None` if the list is
@@ -578,7 +1457,8 @@ impl<T> XorLinkedList<T> {
     /// assert_eq!(d.pop_front(), None);
     /// ```
     pub fn pop_front(&mut self) -> Option<T> {
-        self.pop_front_node().map(|node| node.into_data())
+        self.pop_front_node()
+            .map(|node| unsafe { self.release_node(node) })
     }
 
     /// Appends an element to the back of a list
@@ -594,10 +1474,8 @@ impl<T> XorLinkedList<T> {
     /// assert_eq!(3, *d.back().unwrap());
     /// ```
     pub fn push_back(&mut self, data: T) {
-        unsafe {
-            let value = Box::new(Node::new(data));
-            self.push_back_node(NonNull::new_unchecked(Box::into_raw(value)));
-        }
+        let node = self.allocate_node(data);
+        self.push_back_node(node);
     }
 
     /// Removes the last element from a list and returns it, or `None` if
@@ -615,104 +1493,707 @@ impl<T> XorLinkedList<T> {
     /// assert_eq!(d.pop_back(), Some(3));
     /// ```
     pub fn pop_back(&mut self) -> Option<T> {
-        self.pop_back_node().map(|node| node.into_data())
+        self.pop_back_node()
+            .map(|node| unsafe { self.release_node(node) })
     }
 
-    /// Splits the list into two at the given index. Returns everything after the given index,
-    /// including the index
+    /// Inserts an element at `index` within the list, shifting all elements
+    /// with indices greater than or equal to `index` towards the back.
     ///
-    /// This operation should compute in O(n) time.
+    /// This operation should compute in O(min(i, n - i)) time.
     ///
     /// # Panics
     ///
-    /// Panics if `at > len`.
+    /// Panics if `index > len`.
     ///
     /// # Examples
     ///
     /// ```
     /// use rust_utils::XorLinkedList;
     ///
-    /// let mut d = XorLinkedList::new();
-    ///
-    /// d.push_front(1);
-    /// d.push_front(2);
-    /// d.push_front(3);
+    /// let mut dl = XorLinkedList::new();
+    /// dl.push_back('a');
+    /// dl.push_back('c');
     ///
-    /// let mut splitted = d.split_off(2);
+    /// dl.insert(1, 'b');
     ///
-    /// assert_eq!(splitted.pop_front(), Some(1));
-    /// assert_eq!(splitted.pop_front(), None);
+    /// let mut iter = dl.iter();
+    /// assert_eq!(iter.next(), Some(&'a'));
+    /// assert_eq!(iter.next(), Some(&'b'));
+    /// assert_eq!(iter.next(), Some(&'c'));
+    /// assert_eq!(iter.next(), None);
     /// ```
-    pub fn split_off(&mut self, at: usize) -> XorLinkedList<T> {
-        let len = self.len();
-        assert!(at <= len, "Cannot split off at a nonexistent index");
-        if at == 0 {
-            return mem::replace(self, Self::new());
-        } else if at == len {
-            return Self::new();
+    pub fn insert(&mut self, index: usize, value: T) {
+        assert!(index <= self.len, "Cannot insert at a nonexistent index");
+        if index == self.len {
+            self.push_back(value);
+        } else {
+            self.cursor_mut_at(index).insert_before(value);
         }
+    }
 
-        // Below, we iterate towards the `i-1`th node, either from the start or the end,
-        // depending on which would be faster
-        let split_node = if at - 1 <= len - 1 - (at - 1) {
-            let mut iter = self.iter_mut();
-            let mut last = None;
-            // instead of skipping using .skip() (which creates a new struct),
-            // we skip manually so we can access the head field without
-            // depending on implementation details of Skip
-            for _ in 0..at - 1 {
-                last = iter.head;
-                iter.next();
-            }
-            (iter.head, last)
+    /// Removes and returns the element at `index` from the list, or `None` if
+    /// the index is out of bounds.
+    ///
+    /// This operation should compute in O(min(i, n - i)) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut dl = XorLinkedList::new();
+    /// dl.push_back(1);
+    /// dl.push_back(2);
+    /// dl.push_back(3);
+    ///
+    /// assert_eq!(dl.remove(1), Some(2));
+    /// assert_eq!(dl.remove(2), None);
+    /// assert_eq!(dl.len(), 2);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index < self.len {
+            self.cursor_mut_at(index).remove_current()
         } else {
-            // better off starting from the end
-            let mut iter = self.iter_mut();
-            let mut last = None;
-            for _ in 0..len - (at - 1) {
-                last = iter.tail;
-                iter.next_back();
-            }
-            (last, iter.tail)
+            None
+        }
+    }
+
+    /// Splits the list into two at the given index. Returns everything after the given index,
+    /// including the index
+    ///
+    /// This operation should compute in O(n) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut d = XorLinkedList::new();
+    ///
+    /// d.push_front(1);
+    /// d.push_front(2);
+    /// d.push_front(3);
+    ///
+    /// let mut splitted = d.split_off(2);
+    ///
+    /// assert_eq!(splitted.pop_front(), Some(1));
+    /// assert_eq!(splitted.pop_front(), None);
+    /// ```
+    pub fn split_off(&mut self, at: usize) -> Self
+    where
+        A: Clone,
+    {
+        let len = self.len();
+        assert!(at <= len, "Cannot split off at a nonexistent index");
+        if at == 0 {
+            return self.take_nodes();
+        } else if at == len {
+            return Self::new_in(self.alloc.clone());
+        }
+
+        let split_node = self.node_at(at - 1);
+        unsafe { self.split_after_node(split_node.0, split_node.1, at) }
+    }
+
+    /// Removes the specified range from the list in bulk, returning all removed
+    /// elements as an iterator.
+    ///
+    /// The range is unlinked from the list right away and the remaining nodes are
+    /// relinked, so the list is consistent no matter how much of the iterator is
+    /// consumed. Elements which are not yielded are dropped together with the
+    /// iterator.
+    ///
+    /// This operation should compute in O(min(i, n - i) + m) time, where `i` is
+    /// the start of the range and `m` its length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if the end
+    /// point is greater than the length of the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<u32> = (0..6).collect();
+    ///
+    /// let drained: Vec<u32> = list.drain(1..4).collect();
+    /// assert_eq!(drained, vec![1, 2, 3]);
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![0, 4, 5]);
+    ///
+    /// // A partially consumed iterator removes the whole range
+    /// assert_eq!(list.drain(..2).next_back(), Some(4));
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![5]);
+    /// ```
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, A>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start
+                .checked_add(1)
+                .expect("Cannot drain from a nonexistent index"),
+            Bound::Unbounded => 0,
         };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end
+                .checked_add(1)
+                .expect("Cannot drain up to a nonexistent index"),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len,
+        };
+        assert!(
+            start <= end,
+            "Cannot drain a range which starts after its end"
+        );
+        assert!(end <= self.len, "Cannot drain up to a nonexistent index");
 
-        // The split node is the new tail node of the first part and owns
-        // the head of the second part
-        let mut second_part_head = None;
+        if start == end {
+            return Drain {
+                list: self,
+                head: None,
+                tail: None,
+                len: 0,
+            };
+        }
 
+        let (mut first, prev) = self.node_at(start);
         unsafe {
-            let mut element = split_node.0.unwrap();
-            let element_before = split_node.1;
-            let next_element =
-                XorLinkedList::get_element(element_before, element.as_ref().reference);
-            element.as_mut().reference = XorLinkedList::calculate_reference(element_before, None);
-            if let Some(mut next_element) = next_element {
-                let next_next_element =
-                    XorLinkedList::get_element(Some(element), next_element.as_ref().reference);
-                next_element.as_mut().reference =
-                    XorLinkedList::calculate_reference(None, next_next_element);
-                second_part_head = Some(next_element);
+            let (range_tail, next) = Self::walk((prev, Some(first)), end - start);
+            let mut last = range_tail.expect("the range is within the list");
+
+            // Detach the range by linking its neighbours to each other. As the
+            // references are XORed, the range itself only has to drop its outer
+            // neighbours.
+            match prev {
+                Some(mut prev_node) => {
+                    prev_node.as_mut().reference ^= Node::calculate_reference(Some(first), next)
+                }
+                None => self.head = next,
+            }
+            match next {
+                Some(mut next_node) => {
+                    next_node.as_mut().reference ^= Node::calculate_reference(Some(last), prev)
+                }
+                None => self.tail = prev,
+            }
+            first.as_mut().reference ^= Node::calculate_reference(prev, None);
+            last.as_mut().reference ^= Node::calculate_reference(None, next);
+            self.len -= end - start;
+
+            Drain {
+                list: self,
+                head: Some(first),
+                tail: Some(last),
+                len: end - start,
             }
         }
+    }
 
-        let second_part = XorLinkedList {
-            head: second_part_head,
-            tail: self.tail,
-            len: len - at,
-            marker: PhantomData,
-        };
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` for which `f(&e)` returns false.
+    /// This method operates in place, visiting each element exactly once in the
+    /// original order, and preserves the order of the retained elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut d: XorLinkedList<u32> = (1..7).collect();
+    ///
+    /// d.retain(|&x| x % 2 == 0);
+    ///
+    /// assert_eq!(d.iter().cloned().collect::<Vec<_>>(), vec![2, 4, 6]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain_mut(|elem| f(elem));
+    }
 
-        // Fix the tail ptr of the first part
-        self.tail = split_node.0;
-        self.len = at;
+    /// Retains only the elements specified by the predicate, passing a mutable
+    /// reference to it.
+    ///
+    /// In other words, remove all elements `e` for which `f(&mut e)` returns false.
+    /// This method operates in place, visiting each element exactly once in the
+    /// original order, and preserves the order of the retained elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut d: XorLinkedList<u32> = (1..7).collect();
+    ///
+    /// d.retain_mut(|x| {
+    ///     *x += 10;
+    ///     *x % 2 == 0
+    /// });
+    ///
+    /// assert_eq!(d.iter().cloned().collect::<Vec<_>>(), vec![12, 14, 16]);
+    /// ```
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let mut cursor = self.cursor_front_mut();
+        while let Some(elem) = cursor.current() {
+            if f(elem) {
+                cursor.move_next();
+            } else {
+                cursor.remove_current();
+            }
+        }
+    }
 
-        second_part
+    /// Removes consecutive repeated elements in the list according to the
+    /// `PartialEq` trait implementation.
+    ///
+    /// If the list is sorted, this removes all duplicates. The nodes of the
+    /// removed elements are unlinked in place.
+    ///
+    /// This operation should compute in O(n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<u32> = vec![1, 2, 2, 3, 2].into_iter().collect();
+    ///
+    /// list.dedup();
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![1, 2, 3, 2]);
+    /// ```
+    #[inline]
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b);
+    }
+
+    /// Removes all but the first of consecutive elements in the list that
+    /// resolve to the same key.
+    ///
+    /// If the list is sorted by the key, this removes all duplicates. The nodes
+    /// of the removed elements are unlinked in place.
+    ///
+    /// This operation should compute in O(n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<u32> = vec![10, 20, 21, 30, 20].into_iter().collect();
+    ///
+    /// list.dedup_by_key(|i| *i / 10);
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![10, 20, 30, 20]);
+    /// ```
+    #[inline]
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    /// Removes all but the first of consecutive elements in the list satisfying
+    /// a given equality relation.
+    ///
+    /// The `same_bucket` function is passed references to two elements from the
+    /// list and must determine if the elements compare equal. The elements are
+    /// passed in opposite order from their order in the list, so if
+    /// `same_bucket(a, b)` returns `true`, `a` is removed.
+    ///
+    /// If the list is sorted, this removes all duplicates. The nodes of the
+    /// removed elements are unlinked in place.
+    ///
+    /// This operation should compute in O(n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<&str> =
+    ///     vec!["foo", "bar", "Bar", "baz", "bar"].into_iter().collect();
+    ///
+    /// list.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec!["foo", "bar", "baz", "bar"]);
+    /// ```
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        let mut cursor = self.cursor_front_mut();
+        while let Some(mut kept) = cursor.current {
+            cursor.move_next();
+            while let Some(mut node) = cursor.current {
+                if unsafe { same_bucket(&mut node.as_mut().data, &mut kept.as_mut().data) } {
+                    cursor.remove_current();
+                } else {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Creates an iterator which uses a closure to determine if an element should
+    /// be removed.
+    ///
+    /// If the closure returns true, then the element is removed and yielded.
+    /// If the closure returns false, the element will remain in the list and will
+    /// not be yielded by the iterator.
+    ///
+    /// The iterator unlinks the elements lazily. If it is dropped before being fully
+    /// consumed, the remaining elements are kept in the list. If the closure panics,
+    /// the list stays consistent and keeps every element not yet yielded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut numbers: XorLinkedList<u32> = XorLinkedList::new();
+    /// numbers.extend(&[1, 2, 3, 4, 5, 6, 8, 9, 11, 13, 14, 15]);
+    ///
+    /// let evens = numbers.extract_if(|x| *x % 2 == 0).collect::<XorLinkedList<_>>();
+    /// let odds = numbers;
+    ///
+    /// assert_eq!(evens.into_iter().collect::<Vec<_>>(), vec![2, 4, 6, 8, 14]);
+    /// assert_eq!(odds.into_iter().collect::<Vec<_>>(), vec![1, 3, 5, 9, 11, 13, 15]);
+    /// ```
+    pub fn extract_if<F>(&mut self, filter: F) -> ExtractIf<'_, T, F, A>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        ExtractIf {
+            cursor: self.cursor_front_mut(),
+            pred: filter,
+        }
+    }
+
+    /// Sorts the list.
+    ///
+    /// This sort is stable (i.e. does not reorder equal elements) and `O(n log n)`
+    /// worst-case. It neither moves nor allocates elements but rearranges the
+    /// links between the nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<i32> = vec![5, 4, 1, 3, 2].into_iter().collect();
+    ///
+    /// list.sort();
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+    /// ```
+    #[inline]
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(|a, b| a.cmp(b));
+    }
+
+    /// Sorts the list with a comparator function.
+    ///
+    /// This sort is stable (i.e. does not reorder equal elements) and `O(n log n)`
+    /// worst-case. It neither moves nor allocates elements but rearranges the
+    /// links between the nodes.
+    ///
+    /// If the comparator function panics, the list contains all of its elements
+    /// in an unspecified order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<i32> = vec![5, 4, 1, 3, 2].into_iter().collect();
+    ///
+    /// list.sort_by(|a, b| b.cmp(a));
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![5, 4, 3, 2, 1]);
+    /// ```
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        // Bottom-up merge sort, merging pairs of runs of doubling width
+        let len = self.len;
+        let mut width = 1;
+        while width < len {
+            let mut position = (None, self.head);
+            let mut remaining = len;
+            while remaining > width {
+                let right_len = (remaining - width).min(width);
+                position = unsafe { self.merge_runs(position, width, right_len, &mut compare) };
+                remaining -= width + right_len;
+            }
+            width *= 2;
+        }
+    }
+
+    /// Sorts the list with a key extraction function.
+    ///
+    /// This sort is stable (i.e. does not reorder equal elements) and `O(n log n)`
+    /// worst-case. It neither moves nor allocates elements but rearranges the
+    /// links between the nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<i32> = vec![-5, 4, 1, -3, 2].into_iter().collect();
+    ///
+    /// list.sort_by_key(|k| k.abs());
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![1, 2, -3, 4, -5]);
+    /// ```
+    #[inline]
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)));
+    }
+
+    /// Merges all elements of the sorted list `other` into the sorted list `self`,
+    /// so that the merged list is sorted as well.
+    ///
+    /// The merge is stable, elements of `self` are placed in front of equal
+    /// elements of `other`. It neither moves nor allocates elements but relinks
+    /// the nodes of `other` into `self`. After this operation, `other` becomes
+    /// empty.
+    ///
+    /// If either list is not sorted, the order of the merged elements is
+    /// unspecified.
+    ///
+    /// This operation should compute in O(n + m) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<u32> = vec![1, 4, 6].into_iter().collect();
+    /// let mut other: XorLinkedList<u32> = vec![2, 3, 5, 7].into_iter().collect();
+    ///
+    /// list.merge_sorted(&mut other);
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6, 7]);
+    /// assert!(other.is_empty());
+    /// ```
+    #[inline]
+    pub fn merge_sorted(&mut self, other: &mut Self)
+    where
+        T: Ord,
+    {
+        self.merge_sorted_by(other, |a, b| a.cmp(b));
+    }
+
+    /// Merges all elements of `other` into `self` with a comparator function.
+    /// Both lists have to be sorted with respect to the comparator, so that the
+    /// merged list is sorted as well.
+    ///
+    /// The merge is stable, elements of `self` are placed in front of equal
+    /// elements of `other`. It neither moves nor allocates elements but relinks
+    /// the nodes of `other` into `self`. After this operation, `other` becomes
+    /// empty.
+    ///
+    /// If the comparator function panics, the list contains the elements of both
+    /// lists in an unspecified order.
+    ///
+    /// This operation should compute in O(n + m) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<u32> = vec![6, 4, 1].into_iter().collect();
+    /// let mut other: XorLinkedList<u32> = vec![7, 5, 3, 2].into_iter().collect();
+    ///
+    /// list.merge_sorted_by(&mut other, |a, b| b.cmp(a));
+    /// assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![7, 6, 5, 4, 3, 2, 1]);
+    /// ```
+    pub fn merge_sorted_by<F>(&mut self, other: &mut Self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let left_len = self.len;
+        let right_len = other.len;
+        self.append(other);
+        let first = (None, self.head);
+        unsafe { self.merge_runs(first, left_len, right_len, &mut compare) };
+    }
+
+    /// Consumes the list, creating two lists from it.
+    ///
+    /// The first list contains all elements for which `f` returns `true`, the
+    /// second one all elements for which it returns `false`. Both keep the
+    /// relative order of their elements. The nodes are moved into the new lists,
+    /// no element is moved or reallocated.
+    ///
+    /// This operation should compute in O(n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let list: XorLinkedList<u32> = (1..7).collect();
+    ///
+    /// let (even, odd) = list.partition(|x| x % 2 == 0);
+    /// assert_eq!(even.iter().cloned().collect::<Vec<_>>(), vec![2, 4, 6]);
+    /// assert_eq!(odd.iter().cloned().collect::<Vec<_>>(), vec![1, 3, 5]);
+    /// ```
+    pub fn partition<F>(mut self, mut f: F) -> (Self, Self)
+    where
+        F: FnMut(&T) -> bool,
+        A: Clone,
+    {
+        let mut left = Self::new_in(self.alloc.clone());
+        let mut right = Self::new_in(self.alloc.clone());
+        while let Some(head) = self.head {
+            let target = if f(unsafe { &head.as_ref().data }) {
+                &mut left
+            } else {
+                &mut right
+            };
+            self.pop_front_node();
+            target.push_back_node(head);
+        }
+        (left, right)
+    }
+
+    /// Consumes the list, splitting it into sublists between each pair of
+    /// adjacent elements `a` and `b` for which `pred(a, b)` returns `true`.
+    ///
+    /// The nodes are moved into the sublists, no element is moved or
+    /// reallocated. An empty list results in no sublists.
+    ///
+    /// This operation should compute in O(n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let list: XorLinkedList<u32> = vec![1, 2, 3, 7, 8, 12].into_iter().collect();
+    ///
+    /// let runs = list.split_when(|a, b| b - a > 1);
+    /// let values: Vec<Vec<u32>> = runs.iter().map(|run| run.iter().cloned().collect()).collect();
+    /// assert_eq!(values, vec![vec![1, 2, 3], vec![7, 8], vec![12]]);
+    /// ```
+    pub fn split_when<F>(mut self, mut pred: F) -> Vec<Self>
+    where
+        F: FnMut(&T, &T) -> bool,
+        A: Clone,
+    {
+        let mut lists = Vec::new();
+        let mut current = Self::new_in(self.alloc.clone());
+        while let Some(head) = self.head {
+            if let Some(tail) = current.tail {
+                if unsafe { pred(&tail.as_ref().data, &head.as_ref().data) } {
+                    let next = Self::new_in(self.alloc.clone());
+                    lists.push(mem::replace(&mut current, next));
+                }
+            }
+            self.pop_front_node();
+            current.push_back_node(head);
+        }
+        if !current.is_empty() {
+            lists.push(current);
+        }
+        lists
+    }
+
+    /// Consumes the list, grouping consecutive elements which resolve to the
+    /// same key into sublists.
+    ///
+    /// Each group is returned together with the key of its elements. The key is
+    /// computed once per element. The nodes are moved into the sublists, no
+    /// element is moved or reallocated.
+    ///
+    /// This operation should compute in O(n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let list: XorLinkedList<u32> = vec![10, 11, 20, 30, 31, 15].into_iter().collect();
+    ///
+    /// let groups = list.group_by(|x| x / 10);
+    /// let keys: Vec<u32> = groups.iter().map(|group| group.0).collect();
+    /// assert_eq!(keys, vec![1, 2, 3, 1]);
+    /// assert_eq!(groups[2].1.iter().cloned().collect::<Vec<_>>(), vec![30, 31]);
+    /// ```
+    pub fn group_by<K, F>(mut self, mut key: F) -> Vec<(K, Self)>
+    where
+        F: FnMut(&T) -> K,
+        K: PartialEq,
+        A: Clone,
+    {
+        let mut groups: Vec<(K, Self)> = Vec::new();
+        while let Some(head) = self.head {
+            let head_key = key(unsafe { &head.as_ref().data });
+            self.pop_front_node();
+            match groups.last_mut() {
+                Some(group) if group.0 == head_key => {
+                    group.1.push_back_node(head);
+                    continue;
+                }
+                _ => {}
+            }
+            let mut group = Self::new_in(self.alloc.clone());
+            group.push_back_node(head);
+            groups.push((head_key, group));
+        }
+        groups
+    }
+
+    /// Checks if the elements of the list are sorted.
+    ///
+    /// That is, for each element `a` and its following element `b`, `a <= b` must hold.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let list: XorLinkedList<i32> = vec![1, 2, 2, 9].into_iter().collect();
+    /// assert!(list.is_sorted());
+    ///
+    /// let list: XorLinkedList<i32> = vec![1, 3, 2, 4].into_iter().collect();
+    /// assert!(!list.is_sorted());
+    /// ```
+    pub fn is_sorted(&self) -> bool
+    where
+        T: PartialOrd,
+    {
+        self.iter().zip(self.iter().skip(1)).all(|(a, b)| a <= b)
     }
 }
 
-impl<T> Drop for XorLinkedList<T> {
+impl<T, A: Allocator> Drop for XorLinkedList<T, A> {
     fn drop(&mut self) {
-        while let Some(_) = Self::pop_back_node(self) {}
+        self.clear();
+        self.shrink_to_fit();
     }
 }
 
@@ -725,8 +2206,7 @@ impl<'a, T> Iterator for Iter<'a, T> {
             None
         } else {
             self.head.map(|node| unsafe {
-                if let Some(node_next) =
-                    XorLinkedList::get_element(self.last_head, node.as_ref().reference)
+                if let Some(node_next) = Node::get_element(self.last_head, node.as_ref().reference)
                 {
                     self.head = Some(node_next);
                 } else {
@@ -753,12 +2233,11 @@ impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
             None
         } else {
             self.tail.map(|node| unsafe {
-                if let Some(node_prev) =
-                    XorLinkedList::get_element(self.last_tail, node.as_ref().reference)
+                if let Some(node_prev) = Node::get_element(self.last_tail, node.as_ref().reference)
                 {
-                    self.head = Some(node_prev);
+                    self.tail = Some(node_prev);
                 } else {
-                    self.head = None;
+                    self.tail = None;
                 }
                 self.last_tail = Some(node);
                 let node_local = &*node.as_ptr();
@@ -771,6 +2250,21 @@ impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
 
 impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
 
+impl<'a, T> Default for Iter<'a, T> {
+    /// Creates an empty `Iter`
+    #[inline]
+    fn default() -> Self {
+        Iter {
+            head: None,
+            tail: None,
+            last_head: None,
+            last_tail: None,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
 impl<'a, T> FusedIterator for Iter<'a, T> {}
 
 impl<'a, T> Iterator for IterMut<'a, T> {
@@ -782,8 +2276,7 @@ impl<'a, T> Iterator for IterMut<'a, T> {
             None
         } else {
             self.head.map(|node| unsafe {
-                if let Some(node_next) =
-                    XorLinkedList::get_element(self.last_head, node.as_ref().reference)
+                if let Some(node_next) = Node::get_element(self.last_head, node.as_ref().reference)
                 {
                     self.head = Some(node_next);
                 } else {
@@ -811,7 +2304,7 @@ impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
         } else {
             self.tail.map(|node| unsafe {
                 let test = node.as_ref().reference;
-                if let Some(node_prev) = XorLinkedList::get_element(self.last_tail, test) {
+                if let Some(node_prev) = Node::get_element(self.last_tail, test) {
                     self.tail = Some(node_prev);
                 } else {
                     self.tail = None;
@@ -827,9 +2320,24 @@ impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
 
 impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
 
+impl<'a, T> Default for IterMut<'a, T> {
+    /// Creates an empty `IterMut`
+    #[inline]
+    fn default() -> Self {
+        IterMut {
+            head: None,
+            tail: None,
+            last_head: None,
+            last_tail: None,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
 impl<'a, T> FusedIterator for IterMut<'a, T> {}
 
-impl<T> Iterator for IntoIter<T> {
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
     type Item = T;
 
     #[inline]
@@ -837,23 +2345,530 @@ impl<T> Iterator for IntoIter<T> {
         self.list.pop_front()
     }
 
-    #[inline]
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.list.len, Some(self.list.len))
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T> Default for IntoIter<T> {
+    /// Creates an empty `IntoIter`
+    #[inline]
+    fn default() -> Self {
+        XorLinkedList::new().into_iter()
+    }
+}
+
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+impl<'a, T, A: Allocator> Drain<'a, T, A> {
+    /// Returns an iterator over the elements which have not been yielded yet.
+    fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            last_head: None,
+            last_tail: None,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, A: Allocator> Iterator for Drain<'a, T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.head.map(|node| unsafe {
+            self.head = Node::get_element(None, node.as_ref().reference);
+            match self.head {
+                Some(mut head) => {
+                    head.as_mut().reference ^= Node::calculate_reference(Some(node), None)
+                }
+                None => self.tail = None,
+            }
+            self.len -= 1;
+            self.list.release_node(node)
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T, A: Allocator> DoubleEndedIterator for Drain<'a, T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.tail.map(|node| unsafe {
+            self.tail = Node::get_element(None, node.as_ref().reference);
+            match self.tail {
+                Some(mut tail) => {
+                    tail.as_mut().reference ^= Node::calculate_reference(None, Some(node))
+                }
+                None => self.head = None,
+            }
+            self.len -= 1;
+            self.list.release_node(node)
+        })
+    }
+}
+
+impl<'a, T, A: Allocator> ExactSizeIterator for Drain<'a, T, A> {}
+
+impl<'a, T, A: Allocator> FusedIterator for Drain<'a, T, A> {}
+
+impl<'a, T, A: Allocator> Drop for Drain<'a, T, A> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+impl<'a, T, F, A: Allocator> Iterator for ExtractIf<'a, T, F, A>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while let Some(elem) = self.cursor.current() {
+            if (self.pred)(elem) {
+                return self.cursor.remove_current();
+            }
+            self.cursor.move_next();
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self
+            .cursor
+            .index()
+            .map(|index| self.cursor.list.len - index)
+            .unwrap_or(0);
+        (0, Some(remaining))
+    }
+}
+
+impl<'a, T, F, A: Allocator> FusedIterator for ExtractIf<'a, T, F, A> where F: FnMut(&mut T) -> bool {}
+
+impl<'a, T, A: Allocator> Cursor<'a, T, A> {
+    /// Returns the cursor position index within the `XorLinkedList`.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[inline]
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    /// Moves the cursor to the next element of the `XorLinkedList`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the `XorLinkedList`. If it is pointing to the last
+    /// element of the `XorLinkedList` then this will move it to the "ghost" non-element.
+    pub fn move_next(&mut self) {
+        match self.current.take() {
+            None => {
+                self.current = self.list.head;
+                self.prev = None;
+                self.index = 0;
+            }
+            Some(current) => unsafe {
+                self.current = Node::get_element(self.prev, current.as_ref().reference);
+                self.prev = Some(current);
+                self.index += 1;
+            },
+        }
+    }
+
+    /// Moves the cursor to the previous element of the `XorLinkedList`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the `XorLinkedList`. If it is pointing to the first
+    /// element of the `XorLinkedList` then this will move it to the "ghost" non-element.
+    pub fn move_prev(&mut self) {
+        match self.prev.take() {
+            None => {
+                self.current = None;
+                self.prev = self.list.tail;
+                self.index = self.list.len;
+            }
+            Some(prev) => unsafe {
+                self.prev = Node::get_element(self.current, prev.as_ref().reference);
+                self.current = Some(prev);
+                self.index -= 1;
+            },
+        }
+    }
+
+    /// Returns a reference to the element that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[inline]
+    pub fn current(&self) -> Option<&'a T> {
+        unsafe { self.current.map(|current| &(*current.as_ptr()).data) }
+    }
+
+    /// Returns a reference to the next element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the first element of the `XorLinkedList`. If it is pointing to the last
+    /// element of the `XorLinkedList` then this returns `None`.
+    #[inline]
+    pub fn peek_next(&self) -> Option<&'a T> {
+        let next = match self.current {
+            None => self.list.head,
+            Some(current) => unsafe { Node::get_element(self.prev, current.as_ref().reference) },
+        };
+        unsafe { next.map(|node| &(*node.as_ptr()).data) }
+    }
+
+    /// Returns a reference to the previous element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the last element of the `XorLinkedList`. If it is pointing to the first
+    /// element of the `XorLinkedList` then this returns `None`.
+    #[inline]
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        unsafe { self.prev.map(|prev| &(*prev.as_ptr()).data) }
+    }
+}
+
+impl<'a, T, A: Allocator> CursorMut<'a, T, A> {
+    /// Returns the cursor position index within the `XorLinkedList`.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[inline]
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    /// Moves the cursor to the next element of the `XorLinkedList`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the `XorLinkedList`. If it is pointing to the last
+    /// element of the `XorLinkedList` then this will move it to the "ghost" non-element.
+    pub fn move_next(&mut self) {
+        match self.current.take() {
+            None => {
+                self.current = self.list.head;
+                self.prev = None;
+                self.index = 0;
+            }
+            Some(current) => unsafe {
+                self.current = Node::get_element(self.prev, current.as_ref().reference);
+                self.prev = Some(current);
+                self.index += 1;
+            },
+        }
+    }
+
+    /// Moves the cursor to the previous element of the `XorLinkedList`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the `XorLinkedList`. If it is pointing to the first
+    /// element of the `XorLinkedList` then this will move it to the "ghost" non-element.
+    pub fn move_prev(&mut self) {
+        match self.prev.take() {
+            None => {
+                self.current = None;
+                self.prev = self.list.tail;
+                self.index = self.list.len;
+            }
+            Some(prev) => unsafe {
+                self.prev = Node::get_element(self.current, prev.as_ref().reference);
+                self.current = Some(prev);
+                self.index -= 1;
+            },
+        }
+    }
+
+    /// Returns a reference to the element that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[inline]
+    pub fn current(&mut self) -> Option<&mut T> {
+        unsafe { self.current.map(|current| &mut (*current.as_ptr()).data) }
+    }
+
+    /// Returns a reference to the next element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the first element of the `XorLinkedList`. If it is pointing to the last
+    /// element of the `XorLinkedList` then this returns `None`.
+    #[inline]
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        unsafe { self.next_node().map(|next| &mut (*next.as_ptr()).data) }
+    }
+
+    /// Returns a reference to the previous element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the last element of the `XorLinkedList`. If it is pointing to the first
+    /// element of the `XorLinkedList` then this returns `None`.
+    #[inline]
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        unsafe { self.prev.map(|prev| &mut (*prev.as_ptr()).data) }
+    }
+
+    /// Returns a read-only cursor pointing to the current element.
+    ///
+    /// The lifetime of the returned `Cursor` is bound to that of the
+    /// `CursorMut`, which means it cannot outlive the `CursorMut` and that the
+    /// `CursorMut` is frozen for the lifetime of the `Cursor`.
+    #[inline]
+    pub fn as_cursor(&self) -> Cursor<'_, T, A> {
+        Cursor {
+            index: self.index,
+            current: self.current,
+            prev: self.prev,
+            list: self.list,
+        }
+    }
+
+    /// Inserts a new element into the `XorLinkedList` after the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is
+    /// inserted at the front of the `XorLinkedList`.
+    ///
+    /// This operation should compute in O(1) time.
+    pub fn insert_after(&mut self, item: T) {
+        unsafe {
+            let node = self.list.allocate_node(item);
+            let next = self.next_node();
+            self.list.link_node(self.current, next, node);
+            if self.current.is_none() {
+                // The ghost non-element follows the tail which may have changed
+                self.prev = self.list.tail;
+                self.index = self.list.len;
+            }
+        }
+    }
+
+    /// Inserts a new element into the `XorLinkedList` before the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is
+    /// inserted at the end of the `XorLinkedList`.
+    ///
+    /// This operation should compute in O(1) time.
+    pub fn insert_before(&mut self, item: T) {
+        unsafe {
+            let node = self.list.allocate_node(item);
+            self.list.link_node(self.prev, self.current, node);
+            self.prev = Some(node);
+            self.index += 1;
+        }
+    }
+
+    /// Removes the current element from the `XorLinkedList`.
+    ///
+    /// The element that was removed is returned, and the cursor is
+    /// moved to point to the next element in the `XorLinkedList`.
+    ///
+    /// If the cursor is currently pointing to the "ghost" non-element then no element
+    /// is removed and `None` is returned.
+    ///
+    /// This operation should compute in O(1) time.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let current = self.current?;
+        unsafe {
+            let next = self.next_node();
+            let node = self.list.unlink_node(self.prev, current, next);
+            self.current = next;
+            Some(self.list.release_node(node))
+        }
+    }
+
+    /// Inserts the elements from the given `XorLinkedList` after the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new elements are
+    /// inserted at the start of the `XorLinkedList`.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<u32> = (0..2).collect();
+    /// let other: XorLinkedList<u32> = (10..12).collect();
+    ///
+    /// list.cursor_front_mut().splice_after(other);
+    ///
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&0));
+    /// assert_eq!(iter.next(), Some(&10));
+    /// assert_eq!(iter.next(), Some(&11));
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn splice_after(&mut self, mut list: XorLinkedList<T, A>) {
+        unsafe {
+            let next = self.next_node();
+            self.list.splice_between(self.current, next, &mut list);
+        }
+        if self.current.is_none() {
+            // The ghost non-element follows the tail which may have changed
+            self.prev = self.list.tail;
+            self.index = self.list.len;
+        }
+    }
+
+    /// Inserts the elements from the given `XorLinkedList` before the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new elements are
+    /// inserted at the end of the `XorLinkedList`.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<u32> = (0..2).collect();
+    /// let other: XorLinkedList<u32> = (10..12).collect();
+    ///
+    /// {
+    ///     let mut cursor = list.cursor_back_mut();
+    ///     cursor.splice_before(other);
+    ///     assert_eq!(cursor.index(), Some(3));
+    /// }
+    ///
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&0));
+    /// assert_eq!(iter.next(), Some(&10));
+    /// assert_eq!(iter.next(), Some(&11));
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn splice_before(&mut self, mut list: XorLinkedList<T, A>) {
+        if let Some(tail) = list.tail {
+            let len = list.len;
+            unsafe {
+                self.list.splice_between(self.prev, self.current, &mut list);
+            }
+            self.prev = Some(tail);
+            self.index += len;
+        }
+    }
+
+    /// Splits the list into two after the current element. This will return a
+    /// new list consisting of everything after the cursor, with the original
+    /// list retaining everything before.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the entire contents
+    /// of the `XorLinkedList` are moved.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<u32> = (0..4).collect();
+    ///
+    /// let splitted = list.cursor_front_mut().split_after();
+    ///
+    /// assert_eq!(list.len(), 1);
+    /// assert_eq!(splitted.len(), 3);
+    /// assert_eq!(splitted.front(), Some(&1));
+    /// ```
+    pub fn split_after(&mut self) -> XorLinkedList<T, A>
+    where
+        A: Clone,
+    {
+        match self.current {
+            None => {
+                self.prev = None;
+                self.index = 0;
+                self.list.take_nodes()
+            }
+            Some(current) => unsafe {
+                self.list
+                    .split_after_node(current, self.prev, self.index + 1)
+            },
+        }
+    }
+
+    /// Splits the list into two before the current element. This will return a
+    /// new list consisting of everything before the cursor, with the original
+    /// list retaining everything after.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the entire contents
+    /// of the `XorLinkedList` are moved.
+    ///
+    /// This operation should compute in O(1) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_utils::XorLinkedList;
+    ///
+    /// let mut list: XorLinkedList<u32> = (0..4).collect();
+    ///
+    /// let splitted = list.cursor_back_mut().split_before();
+    ///
+    /// assert_eq!(list.len(), 1);
+    /// assert_eq!(splitted.len(), 3);
+    /// assert_eq!(splitted.back(), Some(&2));
+    /// ```
+    pub fn split_before(&mut self) -> XorLinkedList<T, A>
+    where
+        A: Clone,
+    {
+        let prev = match (self.current, self.prev) {
+            (None, _) => {
+                self.prev = None;
+                self.index = 0;
+                return self.list.take_nodes();
+            }
+            (Some(_), None) => return XorLinkedList::new_in(self.list.alloc.clone()),
+            (Some(_), Some(prev)) => prev,
+        };
+        unsafe {
+            let prev_prev = Node::get_element(self.current, prev.as_ref().reference);
+            let mut second_part = self.list.split_after_node(prev, prev_prev, self.index);
+            self.prev = None;
+            self.index = 0;
+            // Keep the node pool with the list the cursor points into
+            let first_part = self.list.take_nodes();
+            self.list.append(&mut second_part);
+            first_part
+        }
     }
-}
 
-impl<T> DoubleEndedIterator for IntoIter<T> {
+    /// Returns the node following the current one, which is the head of the
+    /// `XorLinkedList` if the cursor is pointing to the "ghost" non-element.
     #[inline]
-    fn next_back(&mut self) -> Option<T> {
-        self.list.pop_back()
+    fn next_node(&self) -> Option<NonNull<Node<T>>> {
+        match self.current {
+            None => self.list.head,
+            Some(current) => unsafe { Node::get_element(self.prev, current.as_ref().reference) },
+        }
     }
 }
 
-impl<T> ExactSizeIterator for IntoIter<T> {}
-
-impl<T> FusedIterator for IntoIter<T> {}
-
 impl<T> FromIterator<T> for XorLinkedList<T> {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
         let mut list = Self::new();
@@ -862,18 +2877,18 @@ impl<T> FromIterator<T> for XorLinkedList<T> {
     }
 }
 
-impl<T> IntoIterator for XorLinkedList<T> {
+impl<T, A: Allocator> IntoIterator for XorLinkedList<T, A> {
     type Item = T;
-    type IntoIter = IntoIter<T>;
+    type IntoIter = IntoIter<T, A>;
 
     /// Consumes the list into an iterator yielding elements by value
     #[inline]
-    fn into_iter(self) -> IntoIter<T> {
+    fn into_iter(self) -> IntoIter<T, A> {
         IntoIter { list: self }
     }
 }
 
-impl<'a, T> IntoIterator for &'a XorLinkedList<T> {
+impl<'a, T, A: Allocator> IntoIterator for &'a XorLinkedList<T, A> {
     type Item = &'a T;
     type IntoIter = Iter<'a, T>;
 
@@ -882,7 +2897,7 @@ impl<'a, T> IntoIterator for &'a XorLinkedList<T> {
     }
 }
 
-impl<'a, T> IntoIterator for &'a mut XorLinkedList<T> {
+impl<'a, T, A: Allocator> IntoIterator for &'a mut XorLinkedList<T, A> {
     type Item = &'a mut T;
     type IntoIter = IterMut<'a, T>;
 
@@ -891,7 +2906,7 @@ impl<'a, T> IntoIterator for &'a mut XorLinkedList<T> {
     }
 }
 
-impl<T> Extend<T> for XorLinkedList<T> {
+impl<T, A: Allocator> Extend<T> for XorLinkedList<T, A> {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
         for elt in iter {
             self.push_back(elt);
@@ -899,46 +2914,64 @@ impl<T> Extend<T> for XorLinkedList<T> {
     }
 }
 
-impl<'a, T: 'a + Copy + fmt::Debug> Extend<&'a T> for XorLinkedList<T> {
+impl<'a, T: 'a + Copy, A: Allocator> Extend<&'a T> for XorLinkedList<T, A> {
     fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
         self.extend(iter.into_iter().cloned());
     }
 }
 
-impl<T: PartialEq + fmt::Debug> PartialEq for XorLinkedList<T> {
+impl<T: PartialEq, A: Allocator> PartialEq for XorLinkedList<T, A> {
     fn eq(&self, other: &Self) -> bool {
         self.len() == other.len() && self.iter().eq(other)
     }
 }
 
-impl<T: Eq + fmt::Debug> Eq for XorLinkedList<T> {}
+impl<T: Eq, A: Allocator> Eq for XorLinkedList<T, A> {}
 
-impl<T: PartialOrd + fmt::Debug> PartialOrd for XorLinkedList<T> {
+impl<T: PartialOrd, A: Allocator> PartialOrd for XorLinkedList<T, A> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         self.iter().partial_cmp(other)
     }
 }
 
-impl<T: Ord + fmt::Debug> Ord for XorLinkedList<T> {
+impl<T: Ord, A: Allocator> Ord for XorLinkedList<T, A> {
     #[inline]
     fn cmp(&self, other: &Self) -> Ordering {
         self.iter().cmp(other)
     }
 }
 
-impl<T: Clone + fmt::Debug> Clone for XorLinkedList<T> {
+impl<T: Clone, A: Allocator + Clone> Clone for XorLinkedList<T, A> {
     fn clone(&self) -> Self {
-        self.iter().cloned().collect()
+        let mut list = Self::new_in(self.alloc.clone());
+        list.extend(self.iter().cloned());
+        list
+    }
+}
+
+impl<T, A: Allocator> Index<usize> for XorLinkedList<T, A> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("Out of bounds access")
     }
 }
 
-impl<T: fmt::Debug> fmt::Debug for XorLinkedList<T> {
+impl<T, A: Allocator> IndexMut<usize> for XorLinkedList<T, A> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("Out of bounds access")
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for XorLinkedList<T, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_list().entries(self).finish()
     }
 }
 
-impl<T: Hash + fmt::Debug> Hash for XorLinkedList<T> {
+impl<T: Hash, A: Allocator> Hash for XorLinkedList<T, A> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.len().hash(state);
         for elt in self {
@@ -959,34 +2992,71 @@ fn assert_covariance() {
     fn c<'a>(x: IntoIter<&'static str>) -> IntoIter<&'a str> {
         x
     }
+    fn d<'i, 'a>(x: Cursor<'i, &'static str>) -> Cursor<'i, &'a str> {
+        x
+    }
 }
 
-unsafe impl<T: Send + fmt::Debug> Send for XorLinkedList<T> {}
+unsafe impl<T: Send, A: Allocator + Send> Send for XorLinkedList<T, A> {}
+
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for XorLinkedList<T, A> {}
+
+unsafe impl<'a, T: Sync> Send for Iter<'a, T> {}
 
-unsafe impl<T: Sync + fmt::Debug> Sync for XorLinkedList<T> {}
+unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
 
-unsafe impl<'a, T: Sync + fmt::Debug> Send for Iter<'a, T> {}
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
 
-unsafe impl<'a, T: Sync + fmt::Debug> Sync for Iter<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
 
-unsafe impl<'a, T: Send + fmt::Debug> Send for IterMut<'a, T> {}
+unsafe impl<'a, T: Sync, A: Allocator + Sync> Send for Cursor<'a, T, A> {}
 
-unsafe impl<'a, T: Sync + fmt::Debug> Sync for IterMut<'a, T> {}
+unsafe impl<'a, T: Sync, A: Allocator + Sync> Sync for Cursor<'a, T, A> {}
+
+unsafe impl<'a, T: Send, A: Allocator + Send> Send for CursorMut<'a, T, A> {}
+
+unsafe impl<'a, T: Sync, A: Allocator + Sync> Sync for CursorMut<'a, T, A> {}
+
+unsafe impl<'a, T: Send, A: Allocator + Send> Send for Drain<'a, T, A> {}
+
+unsafe impl<'a, T: Sync, A: Allocator + Sync> Sync for Drain<'a, T, A> {}
 
 #[cfg(test)]
 mod tests {
-    use super::{Node, XorLinkedList};
+    use super::{Cursor, CursorMut, Drain, IntoIter, Iter, IterMut, Node, XorLinkedList};
+    use alloc::Allocator;
+    #[cfg(feature = "allocator-api2")]
+    use alloc::Global;
+    #[cfg(feature = "allocator-api2")]
+    use allocator_api2::alloc::AllocError;
+    #[cfg(feature = "allocator-api2")]
+    use bumpalo::Bump;
     use rand::{thread_rng, RngCore};
-    use std::ptr::NonNull;
+    #[cfg(feature = "allocator-api2")]
+    use std::alloc::Layout;
+    use std::cell::Cell;
+    use std::collections::HashMap;
+    use std::hash::Hash;
+    use std::panic;
+    use std::ptr::{self, NonNull};
+    use std::rc::Rc;
     use std::thread;
     use std::vec::Vec;
 
+    struct DropCounter(Rc<Cell<usize>>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
     #[cfg(test)]
     fn list_from<T: Clone>(v: &[T]) -> XorLinkedList<T> {
         v.iter().cloned().collect()
     }
 
-    fn check_links<T>(list: &XorLinkedList<T>) {
+    fn check_links<T, A: Allocator>(list: &XorLinkedList<T, A>) {
         let mut node_ptr: NonNull<Node<T>>;
         let tail_ptr: NonNull<Node<T>>;
         let head_ptr: NonNull<Node<T>>;
@@ -1005,9 +3075,8 @@ mod tests {
         let mut last_ptr: Option<NonNull<Node<T>>> = None;
         unsafe {
             for _ in 0..(list.len - 1) {
-                let next_element =
-                    XorLinkedList::get_element(last_ptr, node_ptr.as_ref().reference)
-                        .expect("next link is null, not good");
+                let next_element = Node::get_element(last_ptr, node_ptr.as_ref().reference)
+                    .expect("next link is null, not good");
                 last_ptr = Some(node_ptr);
                 node_ptr = next_element;
             }
@@ -1015,9 +3084,8 @@ mod tests {
 
             last_ptr = None;
             for _ in 0..(list.len - 1) {
-                let prev_element =
-                    XorLinkedList::get_element(last_ptr, node_ptr.as_ref().reference)
-                        .expect("prev link is null, not good");
+                let prev_element = Node::get_element(last_ptr, node_ptr.as_ref().reference)
+                    .expect("prev link is null, not good");
                 last_ptr = Some(node_ptr);
                 node_ptr = prev_element;
             }
@@ -1097,7 +3165,9 @@ mod tests {
 
     #[test]
     fn test_fuzz() {
-        for _ in 0..25 {
+        // Miri is orders of magnitude slower, so only do a few rounds there
+        let rounds = if cfg!(miri) { 2 } else { 25 };
+        for _ in 0..rounds {
             fuzz_test(3);
             fuzz_test(16);
             fuzz_test(189);
@@ -1132,7 +3202,7 @@ mod tests {
         v1.push_front(1);
 
         // test all splits
-        for ix in 0..1 + v1.len() {
+        for ix in 0..=v1.len() {
             let mut a = v1.clone();
             let b = a.split_off(ix);
             check_links(&a);
@@ -1164,7 +3234,7 @@ mod tests {
                     m.push_front(-i);
                     v.insert(0, -i);
                 }
-                3 | 5 | _ => {
+                _ => {
                     m.push_back(i);
                     v.push(i);
                 }
@@ -1257,4 +3327,789 @@ mod tests {
         assert!(v1.contains(&4));
         assert!(!v1.contains(&5));
     }
+
+    #[test]
+    fn test_cursor_mut_move() {
+        let mut m = list_from(&[1, 2, 3]);
+        let mut cursor = m.cursor_front_mut();
+        assert_eq!(cursor.current(), Some(&mut 1));
+        assert_eq!(cursor.index(), Some(0));
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert_eq!(cursor.peek_prev(), Some(&mut 1));
+        assert_eq!(cursor.peek_next(), Some(&mut 3));
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.peek_next(), Some(&mut 1));
+        assert_eq!(cursor.peek_prev(), Some(&mut 3));
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 1));
+        cursor.move_prev();
+        assert_eq!(cursor.current(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&mut 3));
+        assert_eq!(cursor.index(), Some(2));
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert_eq!(cursor.index(), Some(1));
+    }
+
+    #[test]
+    fn test_cursor_mut_insert_and_remove() {
+        let mut m = list_from(&[1, 2, 3, 4, 5]);
+        {
+            let mut cursor = m.cursor_front_mut();
+            cursor.move_next();
+            cursor.insert_before(7);
+            cursor.insert_after(8);
+            assert_eq!(cursor.index(), Some(2));
+            assert_eq!(cursor.remove_current(), Some(2));
+            assert_eq!(cursor.current(), Some(&mut 8));
+            cursor.move_prev();
+            cursor.move_prev();
+            cursor.move_prev();
+            assert_eq!(cursor.index(), None);
+            cursor.insert_after(9);
+            cursor.insert_before(10);
+            assert_eq!(cursor.remove_current(), None);
+        }
+        check_links(&m);
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            &[9, 1, 7, 8, 3, 4, 5, 10]
+        );
+        {
+            let mut cursor = m.cursor_back_mut();
+            while cursor.remove_current().is_some() {
+                cursor.move_prev();
+            }
+            assert_eq!(cursor.index(), None);
+        }
+        check_links(&m);
+        assert!(m.is_empty());
+
+        let mut n = XorLinkedList::new();
+        {
+            let mut cursor = n.cursor_back_mut();
+            cursor.insert_after(2);
+            cursor.insert_after(1);
+            cursor.insert_before(3);
+        }
+        check_links(&n);
+        assert_eq!(n.iter().cloned().collect::<Vec<_>>(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_cursor_move_peek() {
+        let m = list_from(&[1, 2, 3, 4, 5, 6]);
+        let mut front = m.cursor_front();
+        assert_eq!(front.current(), Some(&1));
+        assert_eq!(front.peek_next(), Some(&2));
+        assert_eq!(front.peek_prev(), None);
+        assert_eq!(front.index(), Some(0));
+        front.move_prev();
+        assert_eq!(front.current(), None);
+        assert_eq!(front.peek_next(), Some(&1));
+        assert_eq!(front.peek_prev(), Some(&6));
+        assert_eq!(front.index(), None);
+        front.move_next();
+        front.move_next();
+        assert_eq!(front.current(), Some(&2));
+        assert_eq!(front.peek_next(), Some(&3));
+        assert_eq!(front.peek_prev(), Some(&1));
+        assert_eq!(front.index(), Some(1));
+
+        let mut back = m.cursor_back();
+        assert_eq!(back.current(), Some(&6));
+        assert_eq!(back.peek_next(), None);
+        assert_eq!(back.peek_prev(), Some(&5));
+        assert_eq!(back.index(), Some(5));
+        back.move_next();
+        assert_eq!(back.current(), None);
+        assert_eq!(back.peek_next(), Some(&1));
+        assert_eq!(back.peek_prev(), Some(&6));
+        assert_eq!(back.index(), None);
+        back.move_prev();
+        back.move_prev();
+        assert_eq!(back.current(), Some(&5));
+        assert_eq!(back.peek_next(), Some(&6));
+        assert_eq!(back.peek_prev(), Some(&4));
+        assert_eq!(back.index(), Some(4));
+
+        let mut backwards = Vec::new();
+        let mut rev = m.cursor_back();
+        while let Some(elt) = rev.current() {
+            backwards.push(*elt);
+            rev.move_prev();
+        }
+        assert_eq!(backwards, &[6, 5, 4, 3, 2, 1]);
+
+        let empty = XorLinkedList::<u32>::new();
+        let mut ghost = empty.cursor_front();
+        assert_eq!(ghost.current(), None);
+        assert_eq!(ghost.peek_next(), None);
+        assert_eq!(ghost.peek_prev(), None);
+        ghost.move_next();
+        assert_eq!(ghost.index(), None);
+        assert_eq!(empty.cursor_back().current(), None);
+    }
+
+    #[test]
+    fn test_cursor_mut_splice() {
+        let mut m = list_from(&[1, 2, 3, 4, 5, 6]);
+        {
+            let mut cursor = m.cursor_front_mut();
+            cursor.move_next();
+            cursor.splice_before(list_from(&[7, 8]));
+            assert_eq!(cursor.index(), Some(3));
+            cursor.splice_after(list_from(&[9]));
+            cursor.splice_after(XorLinkedList::new());
+            cursor.splice_before(XorLinkedList::new());
+            assert_eq!(cursor.current(), Some(&mut 2));
+            assert_eq!(cursor.peek_prev(), Some(&mut 8));
+            assert_eq!(cursor.peek_next(), Some(&mut 9));
+            cursor.move_prev();
+            cursor.move_prev();
+            cursor.move_prev();
+            cursor.move_prev();
+            assert_eq!(cursor.index(), None);
+            cursor.splice_after(list_from(&[10, 11]));
+            cursor.splice_before(list_from(&[12, 13]));
+            assert_eq!(cursor.index(), None);
+            assert_eq!(cursor.peek_prev(), Some(&mut 13));
+        }
+        check_links(&m);
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            &[10, 11, 1, 7, 8, 2, 9, 3, 4, 5, 6, 12, 13]
+        );
+
+        let mut n = XorLinkedList::new();
+        n.cursor_front_mut().splice_before(list_from(&[1]));
+        n.cursor_front_mut().splice_after(list_from(&[2]));
+        check_links(&n);
+        assert_eq!(n.iter().cloned().collect::<Vec<_>>(), &[1, 2]);
+    }
+
+    #[test]
+    fn test_cursor_mut_split() {
+        let v = vec![1, 2, 3, 4, 5];
+        for ix in 0..=v.len() {
+            let mut a = list_from(&v);
+            let (b, c) = {
+                let mut cursor = a.cursor_front_mut();
+                for _ in 0..ix {
+                    cursor.move_next();
+                }
+                (cursor.split_after(), cursor.split_before())
+            };
+            check_links(&a);
+            check_links(&b);
+            check_links(&c);
+            if ix == v.len() {
+                assert_eq!(b.iter().cloned().collect::<Vec<_>>(), v);
+                assert!(a.is_empty() && c.is_empty());
+            } else {
+                assert_eq!(c.iter().cloned().collect::<Vec<_>>(), &v[..ix]);
+                assert_eq!(a.iter().cloned().collect::<Vec<_>>(), &v[ix..=ix]);
+                assert_eq!(b.iter().cloned().collect::<Vec<_>>(), &v[ix + 1..]);
+                assert_eq!(a.cursor_front_mut().index(), Some(0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_and_index() {
+        let v = vec![1, 2, 3, 4, 5, 6, 7];
+        let mut m = list_from(&v);
+        for (i, elt) in v.iter().enumerate() {
+            assert_eq!(m.get(i), Some(elt));
+            assert_eq!(m[i], *elt);
+        }
+        assert_eq!(m.get(v.len()), None);
+        for i in 0..v.len() {
+            m[i] *= 10;
+            if let Some(elt) = m.get_mut(i) {
+                *elt += 1;
+            }
+        }
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            &[11, 21, 31, 41, 51, 61, 71]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_out_of_bounds() {
+        let m = list_from(&[1, 2, 3]);
+        let _ = m[3];
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut m = XorLinkedList::new();
+        let mut v = Vec::new();
+        for i in 0..16 {
+            let at = (i * 7) % (v.len() + 1);
+            m.insert(at, i);
+            v.insert(at, i);
+            check_links(&m);
+        }
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), v);
+        assert_eq!(m.remove(v.len()), None);
+        while !v.is_empty() {
+            let at = (v.len() * 2) / 3;
+            assert_eq!(m.remove(at), Some(v.remove(at)));
+            check_links(&m);
+        }
+        assert!(m.is_empty());
+    }
+
+    #[test]
+    fn test_iter_both_ends() {
+        // `next_back` used to advance the head instead of the tail
+        let v: Vec<u32> = (0..32).collect();
+        let mut m = list_from(&v);
+        for pattern in 0..64u32 {
+            let mut expected = v.iter();
+            let mut iter = m.iter();
+            for step in 0..v.len() as u32 + 2 {
+                if pattern >> (step % 6) & 1 == 0 {
+                    assert_eq!(iter.next(), expected.next());
+                } else {
+                    assert_eq!(iter.next_back(), expected.next_back());
+                }
+                assert_eq!(iter.len(), expected.len());
+            }
+        }
+
+        let mut expected = v.clone();
+        let mut iter = m.iter_mut();
+        let mut at = 0;
+        let mut back = v.len();
+        while at < back {
+            *iter.next_back().unwrap() += 100;
+            back -= 1;
+            expected[back] += 100;
+            if let Some(elt) = iter.next() {
+                *elt += 200;
+                expected[at] += 200;
+                at += 1;
+            }
+        }
+        assert!(iter.next().is_none() && iter.next_back().is_none());
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_sort() {
+        for len in 0..64 {
+            let v: Vec<(u8, usize)> = (0..len)
+                .map(|i| ((thread_rng().next_u32() % 8) as u8, i))
+                .collect();
+            let mut m = list_from(&v);
+            m.sort_by_key(|&(key, _)| key);
+            check_links(&m);
+
+            // the index breaks ties, thus a stable sort yields the fully sorted list
+            let mut sorted = v.clone();
+            sorted.sort();
+            assert_eq!(m.iter().cloned().collect::<Vec<_>>(), sorted);
+            assert!(m.is_sorted());
+
+            m.sort_by(|a, b| b.cmp(a));
+            check_links(&m);
+            sorted.reverse();
+            assert_eq!(m.iter().cloned().collect::<Vec<_>>(), sorted);
+            assert_eq!(m.is_sorted(), len < 2);
+        }
+    }
+
+    #[test]
+    fn test_merge_sorted() {
+        for len in 0..32 {
+            let split = (thread_rng().next_u32() as usize) % (len + 1);
+            let v: Vec<(u8, usize)> = (0..len)
+                .map(|i| ((thread_rng().next_u32() % 8) as u8, i))
+                .collect();
+            let mut left: Vec<_> = v[..split].to_vec();
+            let mut right: Vec<_> = v[split..].to_vec();
+            left.sort();
+            right.sort_by_key(|&(key, _)| key);
+
+            let mut m = list_from(&left);
+            let mut n = list_from(&right);
+            m.merge_sorted_by(&mut n, |a, b| a.0.cmp(&b.0));
+            check_links(&m);
+            check_links(&n);
+            assert!(n.is_empty());
+
+            // elements of the left list precede equal elements of the right one
+            let mut merged = left.clone();
+            merged.extend(right.iter().cloned());
+            merged.sort_by_key(|&(key, _)| key);
+            assert_eq!(m.iter().cloned().collect::<Vec<_>>(), merged);
+            assert_eq!(m.iter().rev().count(), len);
+        }
+
+        let mut m = list_from(&[1, 3, 5]);
+        let mut n = XorLinkedList::new();
+        m.merge_sorted(&mut n);
+        n.merge_sorted(&mut m);
+        check_links(&n);
+        assert!(m.is_empty());
+        assert_eq!(n.iter().cloned().collect::<Vec<_>>(), &[1, 3, 5]);
+        let mut o = list_from(&[0, 6]);
+        n.merge_sorted(&mut o);
+        n.push_back(7);
+        check_links(&n);
+        assert_eq!(n.iter().cloned().collect::<Vec<_>>(), &[0, 1, 3, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut m: XorLinkedList<u32> = (0..20).collect();
+        m.retain(|&x| x % 3 != 0);
+        check_links(&m);
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            &[1, 2, 4, 5, 7, 8, 10, 11, 13, 14, 16, 17, 19]
+        );
+        m.retain_mut(|x| {
+            *x *= 2;
+            *x > 20
+        });
+        check_links(&m);
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            &[22, 26, 28, 32, 34, 38]
+        );
+        m.retain(|_| false);
+        check_links(&m);
+        assert!(m.is_empty());
+    }
+
+    #[test]
+    fn test_dedup() {
+        let mut m = list_from(&[1, 1, 2, 3, 3, 3, 1, 4, 4]);
+        m.dedup();
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[1, 2, 3, 1, 4]);
+
+        let mut n = list_from(&[10, 11, 20, 35, 31, 32, 40]);
+        n.dedup_by_key(|x| *x / 10);
+        check_links(&n);
+        assert_eq!(n.iter().cloned().collect::<Vec<_>>(), &[10, 20, 35, 40]);
+
+        // removed elements are passed first, the retained ones may be updated
+        let mut o = list_from(&[1, 2, 3, 10, 11, 20]);
+        o.dedup_by(|a, b| {
+            if *a - *b == 1 {
+                *b = *a;
+                true
+            } else {
+                false
+            }
+        });
+        check_links(&o);
+        assert_eq!(o.iter().cloned().collect::<Vec<_>>(), &[3, 11, 20]);
+
+        let mut p = list_from(&[7, 7, 7]);
+        p.dedup();
+        check_links(&p);
+        assert_eq!(p.iter().cloned().collect::<Vec<_>>(), &[7]);
+        let mut empty = XorLinkedList::<u32>::new();
+        empty.dedup();
+        assert!(empty.is_empty());
+
+        let drops = Rc::new(Cell::new(0));
+        let mut q = XorLinkedList::new();
+        for i in 0..6 {
+            q.push_back((i / 2, DropCounter(Rc::clone(&drops))));
+        }
+        q.dedup_by_key(|elem| elem.0);
+        check_links(&q);
+        assert_eq!(q.len(), 3);
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn test_partition() {
+        let m = list_from(&[1, 2, 3, 4, 5, 6, 7]);
+        let addresses: Vec<*const i32> = m.iter().map(ptr::from_ref).collect();
+        let (even, odd) = m.partition(|x| x % 2 == 0);
+        check_links(&even);
+        check_links(&odd);
+        assert_eq!(even.iter().cloned().collect::<Vec<_>>(), &[2, 4, 6]);
+        assert_eq!(odd.iter().cloned().collect::<Vec<_>>(), &[1, 3, 5, 7]);
+        // the nodes are moved, not reallocated
+        assert!(even
+            .iter()
+            .chain(odd.iter())
+            .all(|x| addresses.contains(&ptr::from_ref(x))));
+        assert_eq!(even.capacity(), 3);
+
+        let (all, none) = list_from(&[1, 2]).partition(|_| true);
+        check_links(&all);
+        assert_eq!(all.len(), 2);
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn test_split_when() {
+        let m = list_from(&[1, 2, 3, 7, 8, 12]);
+        let runs = m.split_when(|a, b| b - a > 1);
+        assert_eq!(runs.len(), 3);
+        for run in &runs {
+            check_links(run);
+        }
+        let values: Vec<Vec<i32>> = runs.iter().map(|r| r.iter().cloned().collect()).collect();
+        assert_eq!(values, vec![vec![1, 2, 3], vec![7, 8], vec![12]]);
+
+        assert!(XorLinkedList::<i32>::new()
+            .split_when(|_, _| true)
+            .is_empty());
+        let singles = list_from(&[1, 2, 3]).split_when(|_, _| true);
+        assert_eq!(
+            singles.iter().map(|l| l.len()).collect::<Vec<_>>(),
+            &[1, 1, 1]
+        );
+        let whole = list_from(&[1, 2, 3]).split_when(|_, _| false);
+        assert_eq!(whole.len(), 1);
+        check_links(&whole[0]);
+    }
+
+    #[test]
+    fn test_group_by() {
+        let m = list_from(&[10, 11, 20, 30, 31, 32, 15]);
+        let mut calls = 0;
+        let groups = m.group_by(|x| {
+            calls += 1;
+            x / 10
+        });
+        assert_eq!(calls, 7);
+        assert_eq!(
+            groups.iter().map(|g| g.0).collect::<Vec<_>>(),
+            &[1, 2, 3, 1]
+        );
+        for group in &groups {
+            check_links(&group.1);
+        }
+        let values: Vec<Vec<i32>> = groups
+            .iter()
+            .map(|g| g.1.iter().cloned().collect())
+            .collect();
+        assert_eq!(
+            values,
+            vec![vec![10, 11], vec![20], vec![30, 31, 32], vec![15]]
+        );
+
+        assert!(XorLinkedList::<i32>::new().group_by(|x| *x).is_empty());
+    }
+
+    #[test]
+    fn test_extract_if() {
+        let mut m: XorLinkedList<u32> = (0..10).collect();
+        {
+            let mut iter = m.extract_if(|x| *x % 4 == 0 || *x == 9);
+            assert_eq!(iter.size_hint(), (0, Some(10)));
+            assert_eq!(iter.next(), Some(0));
+            assert_eq!(iter.next(), Some(4));
+            assert_eq!(iter.size_hint(), (0, Some(5)));
+        }
+        check_links(&m);
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            &[1, 2, 3, 5, 6, 7, 8, 9]
+        );
+        let extracted: Vec<_> = m.extract_if(|x| *x > 6).collect();
+        check_links(&m);
+        assert_eq!(extracted, &[7, 8, 9]);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[1, 2, 3, 5, 6]);
+    }
+
+    #[test]
+    fn test_extract_if_panic() {
+        let mut m: XorLinkedList<u32> = (0..8).collect();
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            m.extract_if(|x| {
+                assert!(*x != 5);
+                *x % 2 == 0
+            })
+            .for_each(drop);
+        }));
+        assert!(result.is_err());
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[1, 3, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_drain() {
+        let v: Vec<u32> = (0..8).collect();
+        for start in 0..=v.len() {
+            for end in start..=v.len() {
+                let mut m = list_from(&v);
+                {
+                    let mut drain = m.drain(start..end);
+                    assert_eq!(drain.len(), end - start);
+                    if end - start > 1 {
+                        assert_eq!(drain.next(), Some(v[start]));
+                        assert_eq!(drain.next_back(), Some(v[end - 1]));
+                    }
+                }
+                check_links(&m);
+                // the drained nodes are kept for reuse
+                assert_eq!(m.capacity(), v.len());
+                let mut expected = v.clone();
+                expected.drain(start..end);
+                assert_eq!(m.iter().cloned().collect::<Vec<_>>(), expected);
+            }
+        }
+
+        let mut m = list_from(&v);
+        assert_eq!(
+            m.drain(..).rev().collect::<Vec<_>>(),
+            &[7, 6, 5, 4, 3, 2, 1, 0]
+        );
+        assert!(m.is_empty());
+        let mut n = list_from(&v);
+        assert_eq!(n.drain(6..=7).collect::<Vec<_>>(), &[6, 7]);
+        check_links(&n);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_drain_out_of_bounds() {
+        let mut m = list_from(&[1, 2, 3]);
+        m.drain(2..4);
+    }
+
+    #[test]
+    fn test_traits_without_debug() {
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        struct NoDebug(u32);
+
+        fn assert_send<T: Send>() {}
+        fn assert_sync<T: Sync>() {}
+        fn assert_clone<T: Clone>() {}
+        fn assert_ord<T: Ord>() {}
+        fn assert_hash<T: Hash>() {}
+        fn assert_default<T: Default>() {}
+        fn assert_extend<'a, T: 'a + Extend<NoDebug> + Extend<&'a NoDebug>>() {}
+
+        assert_send::<XorLinkedList<NoDebug>>();
+        assert_sync::<XorLinkedList<NoDebug>>();
+        assert_clone::<XorLinkedList<NoDebug>>();
+        assert_ord::<XorLinkedList<NoDebug>>();
+        assert_hash::<XorLinkedList<NoDebug>>();
+        assert_default::<XorLinkedList<NoDebug>>();
+        assert_extend::<XorLinkedList<NoDebug>>();
+
+        assert_send::<Iter<NoDebug>>();
+        assert_sync::<Iter<NoDebug>>();
+        assert_clone::<Iter<NoDebug>>();
+        assert_default::<Iter<NoDebug>>();
+        assert_send::<IterMut<NoDebug>>();
+        assert_sync::<IterMut<NoDebug>>();
+        assert_default::<IterMut<NoDebug>>();
+        assert_send::<IntoIter<NoDebug>>();
+        assert_sync::<IntoIter<NoDebug>>();
+        assert_clone::<IntoIter<NoDebug>>();
+        assert_default::<IntoIter<NoDebug>>();
+        assert_send::<Cursor<NoDebug>>();
+        assert_sync::<Cursor<NoDebug>>();
+        assert_clone::<Cursor<NoDebug>>();
+        assert_send::<CursorMut<NoDebug>>();
+        assert_sync::<CursorMut<NoDebug>>();
+        assert_send::<Drain<NoDebug>>();
+        assert_sync::<Drain<NoDebug>>();
+
+        let mut map = HashMap::new();
+        map.insert(list_from(&[NoDebug(1), NoDebug(2)]), 1);
+        assert_eq!(map.get(&list_from(&[NoDebug(1), NoDebug(2)])), Some(&1));
+    }
+
+    #[test]
+    fn test_into_iter_clone() {
+        let mut iter = list_from(&[1, 2, 3]).into_iter();
+        iter.next();
+        let cloned = iter.clone();
+        assert_eq!(iter.collect::<Vec<_>>(), &[2, 3]);
+        assert_eq!(cloned.collect::<Vec<_>>(), &[2, 3]);
+        assert_eq!(IntoIter::<u32>::default().len(), 0);
+        assert_eq!(Iter::<u32>::default().next(), None);
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut m = list_from(&[1, 2, 3, 4]);
+        let nodes = (m.head, m.tail);
+        m.reverse();
+        check_links(&m);
+        assert_eq!((m.tail, m.head), nodes);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[4, 3, 2, 1]);
+        assert_eq!(m.iter().rev().cloned().collect::<Vec<_>>(), &[1, 2, 3, 4]);
+
+        for elt in m.iter_mut().take(2) {
+            *elt *= 10;
+        }
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[40, 30, 2, 1]);
+
+        {
+            let mut cursor = m.cursor_front_mut();
+            cursor.move_next();
+            assert_eq!(cursor.current(), Some(&mut 30));
+            cursor.insert_after(5);
+            cursor.move_prev();
+            assert_eq!(cursor.remove_current(), Some(40));
+            let mut back = m.cursor_back();
+            assert_eq!(back.current(), Some(&1));
+            back.move_next();
+            assert_eq!(back.current(), None);
+            back.move_next();
+            assert_eq!(back.current(), Some(&30));
+        }
+        check_links(&m);
+
+        m.push_front(0);
+        m.push_back(6);
+        let tail = m.split_off(3);
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[0, 30, 5]);
+        assert_eq!(tail.iter().cloned().collect::<Vec<_>>(), &[2, 1, 6]);
+        m.append(&mut tail.reversed());
+        m.sort();
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[0, 1, 2, 5, 6, 30]);
+        assert_eq!(
+            m.reversed().into_iter().collect::<Vec<_>>(),
+            &[30, 6, 5, 2, 1, 0]
+        );
+
+        let mut single = list_from(&[1]);
+        single.reverse();
+        check_links(&single);
+        assert_eq!(single.pop_back(), Some(1));
+        single.reverse();
+        assert!(single.is_empty());
+    }
+
+    #[test]
+    fn test_node_pool() {
+        let mut m = XorLinkedList::with_capacity(4);
+        assert_eq!(m.capacity(), 4);
+        let pooled = m.pool;
+        m.push_back(1);
+        assert_eq!(m.head, pooled);
+        m.push_front(0);
+        m.push_back(2);
+        assert_eq!(m.capacity(), 4);
+        assert_eq!(m.pop_front(), Some(0));
+        assert_eq!(m.remove(1), Some(2));
+        check_links(&m);
+        assert_eq!(m.len(), 1);
+        assert_eq!(m.capacity(), 4);
+
+        m.extend(2..8);
+        assert_eq!(m.capacity(), 7);
+        m.clear();
+        assert!(m.is_empty());
+        assert_eq!(m.capacity(), 0);
+
+        m.reserve(3);
+        m.push_back(1);
+        m.clear();
+        assert_eq!(m.capacity(), 2);
+        m.shrink_to_fit();
+
+        m.extend(0..4);
+        let first = m.split_off(0);
+        assert_eq!(first.capacity(), 4);
+        assert_eq!(m.capacity(), 0);
+        m.reserve(5);
+        assert_eq!(m.capacity(), 5);
+        m.shrink_to_fit();
+        assert_eq!(m.capacity(), 0);
+        assert!(m.pool.is_none());
+    }
+
+    #[test]
+    fn test_node_pool_drops() {
+        let drops = Rc::new(Cell::new(0));
+        {
+            let mut m = XorLinkedList::with_capacity(2);
+            for _ in 0..6 {
+                m.push_back(DropCounter(Rc::clone(&drops)));
+            }
+            m.pop_front();
+            m.pop_back();
+            assert_eq!(drops.get(), 2);
+            m.clear();
+            assert_eq!(drops.get(), 6);
+            m.push_back(DropCounter(Rc::clone(&drops)));
+            m.push_back(DropCounter(Rc::clone(&drops)));
+            assert_eq!(m.capacity(), 2);
+        }
+        assert_eq!(drops.get(), 8);
+    }
+
+    #[cfg(feature = "allocator-api2")]
+    #[derive(Default)]
+    struct CountingAlloc {
+        live: Cell<usize>,
+    }
+
+    #[cfg(feature = "allocator-api2")]
+    unsafe impl Allocator for CountingAlloc {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.live.set(self.live.get() + 1);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.live.set(self.live.get() - 1);
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    #[cfg(feature = "allocator-api2")]
+    #[test]
+    fn test_allocator() {
+        let counter = CountingAlloc::default();
+        {
+            let mut m = XorLinkedList::with_capacity_in(2, &counter);
+            assert_eq!(counter.live.get(), 2);
+            m.extend(0..6);
+            assert_eq!(counter.live.get(), 6);
+            let mut n = m.split_off(3);
+            let copy = n.clone();
+            assert_eq!(counter.live.get(), 9);
+            assert_eq!(n.drain(1..).collect::<Vec<_>>(), vec![4, 5]);
+            m.append(&mut n);
+            // the drained nodes are pooled by `n`
+            drop(n);
+            check_links(&m);
+            assert_eq!(m.iter().cloned().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+            assert_eq!(copy.into_iter().collect::<Vec<_>>(), vec![3, 4, 5]);
+            assert_eq!(counter.live.get(), 4);
+            m.clear();
+            assert_eq!(counter.live.get(), 0);
+            m.push_back(0);
+            m.shrink_to_fit();
+            assert_eq!(counter.live.get(), 1);
+        }
+        assert_eq!(counter.live.get(), 0);
+
+        let bump = Bump::new();
+        let mut m = XorLinkedList::new_in(&bump);
+        m.extend(0..4);
+        m.cursor_front_mut()
+            .splice_after(XorLinkedList::new_in(&bump));
+        check_links(&m);
+        assert_eq!(m.len(), 4);
+        assert!(bump.allocated_bytes() > 0);
+    }
 }